@@ -1,5 +1,9 @@
 use criterion::{criterion_group, criterion_main, Criterion};
-use nbt::decode::{read_compound_tag, read_gzip_compound_tag};
+use nbt::decode::{
+    read_compound_tag, read_compound_tag_from_slice, read_gzip_compound_tag, Decoder,
+};
+use nbt::encode::write_compound_tag;
+use nbt::CompoundTag;
 use std::io::Cursor;
 
 fn hello_world_read(c: &mut Criterion) {
@@ -24,6 +28,20 @@ fn servers_list_read(c: &mut Criterion) {
     });
 }
 
+fn servers_list_decoder_read(c: &mut Criterion) {
+    let data = include_bytes!("../test/binary/servers.dat").to_vec();
+    let mut decoder = Decoder::default();
+
+    c.bench_function("Bench servers list decoder read", |b| {
+        b.iter(|| {
+            let mut cursor = Cursor::new(&data);
+            decoder
+                .decode(&mut cursor)
+                .expect("Failed to read tag data");
+        });
+    });
+}
+
 fn big_test_read(c: &mut Criterion) {
     let data = include_bytes!("../test/binary/bigtest.dat").to_vec();
 
@@ -35,5 +53,26 @@ fn big_test_read(c: &mut Criterion) {
     });
 }
 
-criterion_group!(benches, hello_world_read, servers_list_read, big_test_read);
+fn block_states_read(c: &mut Criterion) {
+    let mut section = CompoundTag::new();
+    section.insert_i64_vec("BlockStates", (0..4096).collect());
+
+    let mut data = Vec::new();
+    write_compound_tag(&mut data, &section).expect("Failed to write tag data");
+
+    c.bench_function("Bench block states read", |b| {
+        b.iter(|| {
+            read_compound_tag_from_slice(&data).expect("Failed to read tag data");
+        });
+    });
+}
+
+criterion_group!(
+    benches,
+    hello_world_read,
+    servers_list_read,
+    servers_list_decoder_read,
+    big_test_read,
+    block_states_read
+);
 criterion_main!(benches);
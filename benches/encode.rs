@@ -1,5 +1,5 @@
 use criterion::{criterion_group, criterion_main, Criterion};
-use nbt::encode::write_compound_tag;
+use nbt::encode::{write_compound_tag, write_compound_tag_to_vec};
 use nbt::CompoundTag;
 
 fn hello_world_write(c: &mut Criterion) {
@@ -21,8 +21,7 @@ fn servers_list_write(c: &mut Criterion) {
     server.insert_str("name", "Minecraft Server");
     server.insert_bool("hideAddress", true);
 
-    let mut servers = Vec::new();
-    servers.push(server);
+    let servers = vec![server];
 
     let mut root_tag = CompoundTag::new();
     root_tag.insert_compound_tag_vec("servers", servers);
@@ -35,5 +34,43 @@ fn servers_list_write(c: &mut Criterion) {
     });
 }
 
-criterion_group!(benches, hello_world_write, servers_list_write);
+fn servers_list_write_to_vec(c: &mut Criterion) {
+    let mut server = CompoundTag::new();
+
+    server.insert_str("ip", "localhost:25565");
+    server.insert_str("name", "Minecraft Server");
+    server.insert_bool("hideAddress", true);
+
+    let servers = vec![server];
+
+    let mut root_tag = CompoundTag::new();
+    root_tag.insert_compound_tag_vec("servers", servers);
+
+    c.bench_function("Bench servers list write to vec", |b| {
+        b.iter(|| {
+            let mut vec = Vec::new();
+            write_compound_tag_to_vec(&mut vec, &root_tag).expect("Failed to write tag data");
+        });
+    });
+}
+
+fn block_states_write(c: &mut Criterion) {
+    let mut section = CompoundTag::new();
+    section.insert_i64_vec("BlockStates", (0..4096).collect());
+
+    c.bench_function("Bench block states write", |b| {
+        b.iter(|| {
+            let mut vec = Vec::new();
+            write_compound_tag(&mut vec, &section).expect("Failed to write tag data");
+        });
+    });
+}
+
+criterion_group!(
+    benches,
+    hello_world_write,
+    servers_list_write,
+    servers_list_write_to_vec,
+    block_states_write
+);
 criterion_main!(benches);
@@ -0,0 +1,139 @@
+//! Derive macro for `NbtCompound` trait of `named-binary-tag` crate.
+//!
+//! Use it through `derive` feature of `named-binary-tag`, which re-exports the macro.
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::ext::IdentExt;
+use syn::{parse_macro_input, Data, DeriveInput, Error, Field, Fields, LitStr};
+
+/// Derives `nbt::NbtCompound` for struct with named fields.
+///
+/// Each field is stored as a tag named after the field, its type must implement `nbt::NbtField`.
+///
+/// Field attributes:
+/// - `#[nbt(rename = "Name")]` uses provided tag name instead of field name.
+/// - `#[nbt(default)]` uses `Default::default()` if tag is missing.
+/// - `#[nbt(skip)]` neither reads nor writes the field, it is set to `Default::default()`.
+#[proc_macro_derive(NbtCompound, attributes(nbt))]
+pub fn derive_nbt_compound(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    expand(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+#[derive(Default)]
+struct FieldOptions {
+    rename: Option<LitStr>,
+    default: bool,
+    skip: bool,
+}
+
+impl FieldOptions {
+    fn from_field(field: &Field) -> syn::Result<Self> {
+        let mut options = FieldOptions::default();
+
+        for attr in field
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("nbt"))
+        {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename") {
+                    options.rename = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("default") {
+                    options.default = true;
+                } else if meta.path.is_ident("skip") {
+                    options.skip = true;
+                } else {
+                    return Err(meta.error("expected `rename`, `default` or `skip`"));
+                }
+
+                Ok(())
+            })?;
+        }
+
+        Ok(options)
+    }
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => return Err(struct_expected(&input)),
+        },
+        _ => return Err(struct_expected(&input)),
+    };
+
+    let mut reads = Vec::with_capacity(fields.len());
+    let mut writes = Vec::with_capacity(fields.len());
+
+    for field in fields {
+        let ident = field.ident.as_ref().expect("Fields are named");
+        let ty = &field.ty;
+        let options = FieldOptions::from_field(field)?;
+
+        if options.skip {
+            reads.push(quote!(#ident: ::std::default::Default::default()));
+            continue;
+        }
+
+        let name = options
+            .rename
+            .unwrap_or_else(|| LitStr::new(&ident.unraw().to_string(), ident.span()));
+
+        let read = quote!(<#ty as ::nbt::NbtField>::read_field(compound_tag, #name)?);
+
+        if options.default {
+            reads.push(quote! {
+                #ident: if compound_tag.contains_key(#name) {
+                    #read
+                } else {
+                    ::std::default::Default::default()
+                }
+            });
+        } else {
+            reads.push(quote!(#ident: #read));
+        }
+
+        writes.push(quote! {
+            if let ::std::option::Option::Some(tag) = ::nbt::NbtField::to_tag(&self.#ident) {
+                compound_tag.insert(#name, tag);
+            }
+        });
+    }
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::nbt::NbtCompound for #ident #ty_generics #where_clause {
+            fn from_compound_tag(
+                compound_tag: &::nbt::CompoundTag,
+            ) -> ::std::result::Result<Self, ::nbt::CompoundTagError<'_, 'static>> {
+                ::std::result::Result::Ok(#ident {
+                    #(#reads,)*
+                })
+            }
+
+            fn to_compound_tag(&self) -> ::nbt::CompoundTag {
+                #[allow(unused_mut)]
+                let mut compound_tag = ::nbt::CompoundTag::new();
+                #(#writes)*
+                compound_tag
+            }
+        }
+    })
+}
+
+fn struct_expected(input: &DeriveInput) -> Error {
+    Error::new_spanned(
+        &input.ident,
+        "NbtCompound can only be derived for structs with named fields",
+    )
+}
@@ -0,0 +1,286 @@
+//! Conversion of tags to CBOR values and back.
+//!
+//! Compound tags become maps with text keys in the same order, lists become arrays and
+//! strings become text. Byte, int and long arrays are written as typed arrays of
+//! [RFC 8746](https://www.rfc-editor.org/rfc/rfc8746) with big endian elements, so they
+//! keep their types.
+//!
+//! CBOR has a single integer and a single float type, so numeric tags are read back as
+//! `Int` if value fits or `Long` otherwise and as `Double`. Booleans are read as `Byte`,
+//! byte strings as `ByteArray`. Unknown tags are written as byte strings of their payload.
+//!
+//! # Example
+//! ```
+//! use nbt::cbor::{compound_tag_from_cbor, compound_tag_to_cbor};
+//! use nbt::CompoundTag;
+//!
+//! let mut compound_tag = CompoundTag::new();
+//! compound_tag.insert_str("id", "minecraft:pig");
+//! compound_tag.insert_i32("Age", -2400);
+//! compound_tag.insert_i64_vec("Seeds", vec![1, -1]);
+//!
+//! let mut bytes = Vec::new();
+//! ciborium::into_writer(&compound_tag_to_cbor(&compound_tag), &mut bytes).unwrap();
+//!
+//! let value: ciborium::Value = ciborium::from_reader(bytes.as_slice()).unwrap();
+//! assert_eq!(compound_tag_from_cbor(&value).unwrap(), compound_tag);
+//! ```
+use crate::nbtpath::NbtPath;
+use crate::{CompoundTag, Tag, TagType};
+use ciborium::Value;
+use std::convert::{TryFrom, TryInto};
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+
+/// Typed array tag of signed bytes.
+const SINT8_TAG: u64 = 72;
+/// Typed array tag of big endian signed 32 bit integers.
+const SINT32_BE_TAG: u64 = 74;
+/// Typed array tag of big endian signed 64 bit integers.
+const SINT64_BE_TAG: u64 = 75;
+
+/// Converts compound tag to CBOR map.
+pub fn compound_tag_to_cbor(compound_tag: &CompoundTag) -> Value {
+    let entries = compound_tag
+        .iter()
+        .map(|(name, tag)| (Value::Text(name.to_string()), tag_to_cbor(tag)))
+        .collect();
+
+    Value::Map(entries)
+}
+
+/// Converts tag to CBOR value.
+pub fn tag_to_cbor(tag: &Tag) -> Value {
+    match tag {
+        Tag::Byte(value) => Value::from(*value),
+        Tag::Short(value) => Value::from(*value),
+        Tag::Int(value) => Value::from(*value),
+        Tag::Long(value) => Value::from(*value),
+        Tag::Float(value) => Value::Float(f64::from(*value)),
+        Tag::Double(value) => Value::Float(*value),
+        Tag::ByteArray(value) => {
+            let bytes = value.iter().map(|value| *value as u8).collect();
+            typed_array(SINT8_TAG, bytes)
+        }
+        Tag::String(value) => Value::Text(value.clone()),
+        Tag::List(value) => Value::Array(value.iter().map(tag_to_cbor).collect()),
+        Tag::Compound(value) => compound_tag_to_cbor(value),
+        Tag::IntArray(value) => {
+            let bytes = value.iter().flat_map(|value| value.to_be_bytes()).collect();
+            typed_array(SINT32_BE_TAG, bytes)
+        }
+        Tag::LongArray(value) => {
+            let bytes = value.iter().flat_map(|value| value.to_be_bytes()).collect();
+            typed_array(SINT64_BE_TAG, bytes)
+        }
+        Tag::Unknown { bytes, .. } => Value::Bytes(bytes.clone()),
+    }
+}
+
+/// Converts CBOR map back to compound tag.
+pub fn compound_tag_from_cbor(value: &Value) -> Result<CompoundTag, CborError> {
+    let mut path = NbtPath::new();
+
+    match value_to_tag(value, &mut path)? {
+        Tag::Compound(compound_tag) => Ok(compound_tag),
+        _ => Err(CborError::UnexpectedValue { path }),
+    }
+}
+
+/// Converts CBOR value back to tag.
+pub fn tag_from_cbor(value: &Value) -> Result<Tag, CborError> {
+    value_to_tag(value, &mut NbtPath::new())
+}
+
+fn typed_array(tag: u64, bytes: Vec<u8>) -> Value {
+    Value::Tag(tag, Box::new(Value::Bytes(bytes)))
+}
+
+fn value_to_tag(value: &Value, path: &mut NbtPath) -> Result<Tag, CborError> {
+    let invalid_value = |path: &NbtPath, tag_type| CborError::InvalidValue {
+        path: path.clone(),
+        tag_type,
+    };
+
+    match value {
+        Value::Integer(value) => {
+            let value = i128::from(*value);
+
+            match (i32::try_from(value), i64::try_from(value)) {
+                (Ok(value), _) => Ok(Tag::Int(value)),
+                (_, Ok(value)) => Ok(Tag::Long(value)),
+                _ => Err(invalid_value(path, TagType::Long)),
+            }
+        }
+        Value::Bytes(bytes) => Ok(Tag::ByteArray(
+            bytes.iter().map(|value| *value as i8).collect(),
+        )),
+        Value::Float(value) => Ok(Tag::Double(*value)),
+        Value::Text(value) => Ok(Tag::String(value.clone())),
+        Value::Bool(value) => Ok(Tag::Byte(*value as i8)),
+        Value::Tag(tag, value) => {
+            let (tag_type, size) = match *tag {
+                SINT8_TAG => (TagType::ByteArray, 1),
+                SINT32_BE_TAG => (TagType::IntArray, 4),
+                SINT64_BE_TAG => (TagType::LongArray, 8),
+                _ => return Err(CborError::UnexpectedValue { path: path.clone() }),
+            };
+
+            let bytes = match value.as_ref() {
+                Value::Bytes(bytes) if bytes.len() % size == 0 => bytes,
+                _ => return Err(invalid_value(path, tag_type)),
+            };
+
+            let tag = match tag_type {
+                TagType::ByteArray => Tag::ByteArray(bytes.iter().map(|b| *b as i8).collect()),
+                TagType::IntArray => Tag::IntArray(
+                    bytes
+                        .chunks_exact(4)
+                        .map(|chunk| i32::from_be_bytes(chunk.try_into().unwrap()))
+                        .collect(),
+                ),
+                _ => Tag::LongArray(
+                    bytes
+                        .chunks_exact(8)
+                        .map(|chunk| i64::from_be_bytes(chunk.try_into().unwrap()))
+                        .collect(),
+                ),
+            };
+
+            Ok(tag)
+        }
+        Value::Array(values) => {
+            let mut tags = Vec::with_capacity(values.len());
+
+            for (index, value) in values.iter().enumerate() {
+                path.push_index(index);
+                tags.push(value_to_tag(value, path)?);
+                path.pop();
+            }
+
+            Ok(Tag::List(tags))
+        }
+        Value::Map(entries) => {
+            let mut compound_tag = CompoundTag::new();
+
+            for (name, value) in entries {
+                let name = match name {
+                    Value::Text(name) => name.as_str(),
+                    _ => return Err(CborError::NonTextKey { path: path.clone() }),
+                };
+
+                path.push_key(name);
+                compound_tag.insert(name, value_to_tag(value, path)?);
+                path.pop();
+            }
+
+            Ok(Tag::Compound(compound_tag))
+        }
+        _ => Err(CborError::UnexpectedValue { path: path.clone() }),
+    }
+}
+
+/// Possible types of errors while converting CBOR back to tags.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CborError {
+    /// CBOR value can't be a tag, e.g. `null` or unsupported semantic tag.
+    UnexpectedValue { path: NbtPath },
+    /// Map has a key which is not text.
+    NonTextKey { path: NbtPath },
+    /// Value doesn't fit into the tag type, e.g. integer out of long range.
+    InvalidValue { path: NbtPath, tag_type: TagType },
+}
+
+impl CborError {
+    /// Returns path of the problematic tag.
+    pub fn path(&self) -> &NbtPath {
+        match self {
+            CborError::UnexpectedValue { path } => path,
+            CborError::NonTextKey { path } => path,
+            CborError::InvalidValue { path, .. } => path,
+        }
+    }
+}
+
+impl Error for CborError {}
+
+impl Display for CborError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            CborError::UnexpectedValue { path } => {
+                write!(f, "Tag {} has unexpected CBOR value", path)
+            }
+            CborError::NonTextKey { path } => write!(f, "Tag {} has key which is not text", path),
+            CborError::InvalidValue { path, tag_type } => {
+                write!(f, "Tag {} has invalid value for {}", path, tag_type)
+            }
+        }
+    }
+}
+
+#[test]
+fn test_cbor_round_trip() {
+    let mut compound_tag = CompoundTag::new();
+    compound_tag.insert_i32("int", i32::MIN);
+    compound_tag.insert_i64("long", i64::MAX);
+    compound_tag.insert_f64("double", 0.1);
+    compound_tag.insert_i8_vec("bytes", vec![-1, 0, 1]);
+    compound_tag.insert_i32_vec("ints", vec![-2, 0x0102_0304]);
+    compound_tag.insert_i64_vec("longs", Vec::new());
+    compound_tag.insert_str_vec("list", vec!["a", "b"]);
+    compound_tag.insert_compound_tag("compound", CompoundTag::new());
+
+    let value = compound_tag_to_cbor(&compound_tag);
+
+    let mut bytes = Vec::new();
+    ciborium::into_writer(&value, &mut bytes).unwrap();
+
+    let decoded: Value = ciborium::from_reader(bytes.as_slice()).unwrap();
+    assert_eq!(decoded, value);
+    assert_eq!(compound_tag_from_cbor(&decoded).unwrap(), compound_tag);
+
+    assert_eq!(
+        tag_to_cbor(&Tag::IntArray(vec![-2, 0x0102_0304])),
+        typed_array(SINT32_BE_TAG, vec![255, 255, 255, 254, 1, 2, 3, 4])
+    );
+
+    // Numeric types are narrowed to the common ones.
+    assert_eq!(tag_from_cbor(&tag_to_cbor(&Tag::Byte(1))), Ok(Tag::Int(1)));
+    assert_eq!(
+        tag_from_cbor(&tag_to_cbor(&Tag::Float(0.5))),
+        Ok(Tag::Double(0.5))
+    );
+    assert_eq!(tag_from_cbor(&Value::Bool(true)), Ok(Tag::Byte(1)));
+}
+
+#[test]
+fn test_cbor_errors() {
+    let map = |name: Value, value: Value| Value::Map(vec![(name, value)]);
+    let text = |value: &str| Value::Text(value.to_string());
+
+    let error = compound_tag_from_cbor(&map(text("a"), Value::Array(vec![text("b"), Value::Null])))
+        .unwrap_err();
+    assert_eq!(error.to_string(), "Tag a[1] has unexpected CBOR value");
+
+    assert_eq!(
+        compound_tag_from_cbor(&map(text("a"), map(Value::from(1), Value::from(1)),)),
+        Err(CborError::NonTextKey {
+            path: "a".parse().unwrap()
+        })
+    );
+    assert_eq!(
+        tag_from_cbor(&typed_array(SINT64_BE_TAG, vec![0; 7])),
+        Err(CborError::InvalidValue {
+            path: NbtPath::new(),
+            tag_type: TagType::LongArray,
+        })
+    );
+    assert_eq!(
+        tag_from_cbor(&Value::from(u64::MAX)),
+        Err(CborError::InvalidValue {
+            path: NbtPath::new(),
+            tag_type: TagType::Long,
+        })
+    );
+    assert!(compound_tag_from_cbor(&text("a")).is_err());
+}
@@ -0,0 +1,129 @@
+//! Comparison of tag trees with relaxed equality.
+use crate::{CompoundTag, Tag};
+
+/// How tags are compared by [`CompoundTag::equals_with`].
+///
+/// Default options compare the same way as `==`.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[non_exhaustive]
+pub struct EqOptions {
+    /// Compare compound tags regardless of the order of their tags. Disabled by default.
+    pub ignore_key_order: bool,
+    /// Maximum absolute difference between equal float and double values.
+    /// Not set by default, values are compared by their bit patterns.
+    pub float_epsilon: Option<f64>,
+    /// Treat tag missing from a compound tag as equal to an empty or zero value tag,
+    /// see [`TagType::default_tag`](crate::TagType::default_tag). Disabled by default.
+    ///
+    /// Tags are looked up by name then, so their order is ignored as well.
+    pub absent_as_default: bool,
+}
+
+impl CompoundTag {
+    /// Compares compound tags using provided options. Name is not taken into account.
+    ///
+    /// # Example
+    /// ```
+    /// use nbt::compare::EqOptions;
+    /// use nbt::CompoundTag;
+    ///
+    /// let mut a = CompoundTag::new();
+    /// a.insert_f64("x", 0.1 + 0.2);
+    /// a.insert_i8("OnGround", 0);
+    ///
+    /// let mut b = CompoundTag::new();
+    /// b.insert_f64("x", 0.3);
+    ///
+    /// let mut options = EqOptions::default();
+    /// options.float_epsilon = Some(1e-9);
+    /// options.absent_as_default = true;
+    ///
+    /// assert_ne!(a, b);
+    /// assert!(a.equals_with(&b, &options));
+    /// ```
+    pub fn equals_with(&self, other: &CompoundTag, options: &EqOptions) -> bool {
+        if options.ignore_key_order || options.absent_as_default {
+            return contains_all(self, other, options) && contains_all(other, self, options);
+        }
+
+        self.tags.len() == other.tags.len()
+            && self
+                .iter()
+                .zip(other.iter())
+                .all(|((a_name, a), (b_name, b))| a_name == b_name && a.equals_with(b, options))
+    }
+}
+
+impl Tag {
+    /// Compares tags using provided options, see [`CompoundTag::equals_with`].
+    pub fn equals_with(&self, other: &Tag, options: &EqOptions) -> bool {
+        match (self, other) {
+            (Tag::Float(a), Tag::Float(b)) => floats_equal(*a as f64, *b as f64, options),
+            (Tag::Double(a), Tag::Double(b)) => floats_equal(*a, *b, options),
+            (Tag::List(a), Tag::List(b)) => {
+                a.len() == b.len() && a.iter().zip(b).all(|(a, b)| a.equals_with(b, options))
+            }
+            (Tag::Compound(a), Tag::Compound(b)) => a.equals_with(b, options),
+            (a, b) => a == b,
+        }
+    }
+}
+
+/// Checks that every tag of compound tag has an equal one in other compound tag, looked up by name.
+fn contains_all(compound_tag: &CompoundTag, other: &CompoundTag, options: &EqOptions) -> bool {
+    compound_tag
+        .iter()
+        .all(|(name, tag)| match other.tags.get(name) {
+            Some(other_tag) => tag.equals_with(other_tag, options),
+            None => {
+                options.absent_as_default && tag.equals_with(&tag.tag_type().default_tag(), options)
+            }
+        })
+}
+
+fn floats_equal(a: f64, b: f64, options: &EqOptions) -> bool {
+    a.to_bits() == b.to_bits()
+        || options
+            .float_epsilon
+            .is_some_and(|epsilon| (a - b).abs() <= epsilon)
+}
+
+#[test]
+fn test_equals_with() {
+    let mut a = CompoundTag::new();
+    a.insert_str("id", "minecraft:pig");
+    a.insert_f32_vec("Rotation", vec![90.0, 0.0]);
+    a.insert_compound_tag_vec("Passengers", Vec::new());
+
+    let mut b = CompoundTag::new();
+    b.insert_f32_vec("Rotation", vec![90.00001, 0.0]);
+    b.insert_str("id", "minecraft:pig");
+
+    let mut options = EqOptions::default();
+    assert!(a.equals_with(&a, &options));
+    assert!(!a.equals_with(&b, &options));
+
+    options.absent_as_default = true;
+    assert!(!a.equals_with(&b, &options));
+
+    options.float_epsilon = Some(1e-4);
+    assert!(a.equals_with(&b, &options));
+    assert!(b.equals_with(&a, &options));
+
+    options.absent_as_default = false;
+    b.insert_compound_tag_vec("Passengers", Vec::new());
+    assert!(!a.equals_with(&b, &options));
+
+    options.ignore_key_order = true;
+    assert!(a.equals_with(&b, &options));
+
+    b.insert_i8("Extra", 1);
+    assert!(!a.equals_with(&b, &options));
+
+    options.absent_as_default = true;
+    assert!(!a.equals_with(&b, &options));
+
+    let nan = Tag::Double(f64::NAN);
+    assert!(nan.equals_with(&nan, &EqOptions::default()));
+    assert!(!Tag::Int(0).equals_with(&Tag::Long(0), &options));
+}
@@ -1,12 +1,34 @@
-use crate::{CompoundTag, Tag};
-use byteorder::{BigEndian, ReadBytesExt};
+use crate::map::TagMap;
+use crate::nbtpath::{NbtPath, PathSegment};
+use crate::{CompoundTag, ErrorKind, Tag, TagType};
+use byteorder::{BigEndian, ByteOrder, ReadBytesExt};
 use flate2::read::{GzDecoder, ZlibDecoder};
-use linked_hash_map::LinkedHashMap;
-use std::{error::Error, io::Read};
+use std::borrow::Cow;
+use std::error::Error;
+use std::io::{Cursor, Read};
 use std::{fmt::Display, io};
 
+#[cfg(feature = "bumpalo")]
+mod arena;
+mod decoder;
+mod interner;
+#[cfg(feature = "rayon")]
+mod par;
+mod validate;
+
+#[cfg(feature = "bumpalo")]
+pub use self::arena::{
+    read_compound_tag_in, read_compound_tag_in_with_options, Arena, ArenaCompoundTag, ArenaTag,
+};
+pub use self::decoder::Decoder;
+pub use self::interner::Interner;
+#[cfg(feature = "rayon")]
+pub use self::par::read_compound_tag_from_slice_par;
+pub use self::validate::{validate, NbtStats};
+
 /// Possible types of errors while decoding tag.
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum TagDecodeError {
     /// Root of tag must be compound tag.
     RootMustBeCompoundTag {
@@ -17,21 +39,377 @@ pub enum TagDecodeError {
     UnknownTagType {
         /// Tag type id which is not recognized.
         tag_type_id: u8,
+        /// Where tag type id was read.
+        position: DecodePosition,
+    },
+    /// Reader has data left after the root tag.
+    TrailingData {
+        /// Number of bytes left.
+        remaining: u64,
+        /// Where root tag ended.
+        position: DecodePosition,
+    },
+    /// Tags are nested deeper than [`DecodeOptions::max_depth`] allows.
+    DepthLimitExceeded {
+        /// Maximum allowed depth.
+        max_depth: usize,
+        /// Where tag exceeding the limit starts.
+        position: DecodePosition,
+    },
+    /// Input is larger than [`DecodeOptions::max_bytes`] allows.
+    SizeLimitExceeded {
+        /// Maximum allowed number of bytes.
+        max_bytes: u64,
+        /// Where read exceeding the limit starts.
+        position: DecodePosition,
+    },
+    /// List or array is longer than [`DecodeOptions::max_elements`] allows.
+    ElementLimitExceeded {
+        /// Length which was read.
+        length: usize,
+        /// Maximum allowed length.
+        max_elements: usize,
+        /// Where length was read.
+        position: DecodePosition,
+    },
+    /// String is longer than [`DecodeOptions::max_string_len`] allows.
+    StringLimitExceeded {
+        /// Length in bytes which was read.
+        length: usize,
+        /// Maximum allowed length in bytes.
+        max_string_len: usize,
+        /// Where length was read.
+        position: DecodePosition,
+    },
+    /// List or array has negative length, only reported by [`DecodeOptions::strict`] decoding.
+    NegativeLength {
+        /// Length which was read.
+        length: i32,
+        /// Where length was read.
+        position: DecodePosition,
+    },
+    /// List has `TAG_End` element type but is not empty, or is empty but has unknown element type.
+    ///
+    /// Only reported by [`DecodeOptions::strict`] decoding. [`DecodeOptions::exact`] decoding also
+    /// reports empty lists with any element type other than `TAG_End`.
+    MalformedList {
+        /// Element type id of list.
+        tag_type_id: u8,
+        /// Length of list.
+        length: u32,
+        /// Where element type id was read.
+        position: DecodePosition,
+    },
+    /// Compound tag contains several tags with the same name,
+    /// only reported with [`DuplicateKeys::Error`] policy.
+    DuplicateKey {
+        /// Duplicate name.
+        name: String,
+        /// Where duplicate tag starts.
+        position: DecodePosition,
+    },
+    /// String is not valid UTF-8, only reported by [`DecodeOptions::exact`] decoding.
+    InvalidString {
+        /// Where string length was read.
+        position: DecodePosition,
+    },
+    /// Root tag name is not empty, only reported with [`RootName::RequireEmpty`] policy.
+    RootNameNotEmpty {
+        /// Actual name.
+        name: String,
+        /// Where name was read.
+        position: DecodePosition,
+    },
+    /// String, list or array claims more bytes than input has left.
+    ///
+    /// Only detected when input size is known, e.g. by [`read_compound_tag_from_cursor`].
+    LengthExceedsInput {
+        /// Minimal number of bytes claimed by the length.
+        length: u64,
+        /// Number of bytes left in input after the length.
+        remaining: u64,
+        /// Where length was read.
+        position: DecodePosition,
     },
     /// I/O Error which happened while were decoding.
-    IOError { io_error: io::Error },
+    IOError {
+        io_error: io::Error,
+        /// Where value failed to be read.
+        position: DecodePosition,
+    },
+}
+
+/// Result of decoding.
+pub type Result<T> = std::result::Result<T, TagDecodeError>;
+
+/// Limits and behaviour of decoding.
+///
+/// # Example
+/// ```
+/// use nbt::decode::{read_compound_tag_with_options, DecodeOptions};
+/// use std::io::Cursor;
+///
+/// let mut options = DecodeOptions::default();
+/// options.max_depth = 16;
+///
+/// let mut cursor = Cursor::new(include_bytes!("../test/binary/servers.dat").to_vec());
+/// let root_tag = read_compound_tag_with_options(&mut cursor, &options).unwrap();
+///
+/// assert_eq!(root_tag.get_compound_tag_vec("servers").unwrap().len(), 1);
+/// ```
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct DecodeOptions {
+    /// Maximum nesting depth of compound and list tags, root compound tag has depth 1.
+    ///
+    /// Protects against stack overflow on malicious input. Default is 512, same as vanilla.
+    pub max_depth: usize,
+    /// Maximum number of bytes to read from input. Unlimited by default.
+    pub max_bytes: Option<u64>,
+    /// Maximum length of a single list or array. Unlimited by default.
+    pub max_elements: Option<usize>,
+    /// Maximum length of a string or tag name in bytes. Unlimited by default.
+    pub max_string_len: Option<usize>,
+    /// Reject malformed constructs instead of tolerating them like vanilla does,
+    /// e.g. negative list and array lengths or non empty lists of `TAG_End`. Disabled by default.
+    pub strict: bool,
+    /// What to do with duplicate names inside of a compound tag. Default is to keep last one.
+    pub duplicate_keys: DuplicateKeys,
+    /// Reader of payloads of unknown tag types. If set, such tags are decoded into
+    /// [`Tag::Unknown`] instead of failing with [`TagDecodeError::UnknownTagType`].
+    pub unknown_tag_reader: Option<UnknownTagReader>,
+    /// What to do with name of the root tag. Default is to keep it in [`CompoundTag::name`].
+    pub root_name: RootName,
+    /// Reject well-formed input which can't be represented exactly, i.e. empty lists with element
+    /// type other than `TAG_End` and strings which are not valid UTF-8, like modified UTF-8 of
+    /// surrogate pairs. Disabled by default, such strings are decoded lossily.
+    pub exact: bool,
+    /// Maximum number of bytes allocated for a list, array or string before its elements are read.
+    ///
+    /// Longer values grow while being read, so bogus lengths can't exhaust memory. Default is 1 MiB.
+    pub max_preallocation: usize,
+    /// Interner sharing names of tags inside of compound tags. Not set by default,
+    /// every name is allocated separately.
+    pub interner: Option<Interner>,
+    /// Minimum length of a list of lists or compound tags which elements are decoded in parallel
+    /// by [`read_compound_tag_from_slice_par`]. Default is 256.
+    #[cfg(feature = "rayon")]
+    pub min_parallel_list_len: usize,
+}
+
+/// Policy for name of the root tag.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum RootName {
+    /// Store name in [`CompoundTag::name`].
+    #[default]
+    Keep,
+    /// Skip name without allocating, [`CompoundTag::name`] is `None`.
+    Skip,
+    /// Fail with [`TagDecodeError::RootNameNotEmpty`] if name is not empty, as network protocol expects.
+    RequireEmpty,
+}
+
+/// Reads raw payload of a tag with provided unknown type id, e.g. by knowing its length
+/// from a newer specification or a mod.
+///
+/// # Example
+/// ```
+/// use nbt::decode::{read_compound_tag_with_options, DecodeOptions};
+/// use nbt::Tag;
+/// use std::io::{self, Cursor, Read};
+///
+/// // Tag with type id 13 and 2 bytes long payload.
+/// fn read_unknown(id: u8, reader: &mut dyn Read) -> io::Result<Vec<u8>> {
+///     match id {
+///         13 => {
+///             let mut bytes = vec![0; 2];
+///             reader.read_exact(&mut bytes)?;
+///             Ok(bytes)
+///         }
+///         _ => Err(io::Error::new(io::ErrorKind::InvalidData, "unknown tag")),
+///     }
+/// }
+///
+/// let mut options = DecodeOptions::default();
+/// options.unknown_tag_reader = Some(read_unknown);
+///
+/// let vec = vec![10, 0, 0, 13, 0, 1, b'a', 1, 2, 0];
+/// let root_tag = read_compound_tag_with_options(&mut Cursor::new(vec), &options).unwrap();
+///
+/// let tag: &Tag = root_tag.get("a").unwrap();
+///
+/// match tag {
+///     Tag::Unknown { id, bytes } => assert_eq!((*id, bytes.as_slice()), (13, &[1, 2][..])),
+///     _ => panic!(),
+/// }
+/// ```
+pub type UnknownTagReader = fn(tag_type_id: u8, reader: &mut dyn Read) -> io::Result<Vec<u8>>;
+
+/// Policy for duplicate names inside of a compound tag.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum DuplicateKeys {
+    /// Keep the last occurrence, like vanilla does.
+    #[default]
+    KeepLast,
+    /// Keep the first occurrence and skip the rest.
+    KeepFirst,
+    /// Fail with [`TagDecodeError::DuplicateKey`].
+    Error,
+}
+
+impl Default for DecodeOptions {
+    fn default() -> Self {
+        DecodeOptions {
+            max_depth: 512,
+            max_bytes: None,
+            max_elements: None,
+            max_string_len: None,
+            strict: false,
+            duplicate_keys: DuplicateKeys::default(),
+            unknown_tag_reader: None,
+            root_name: RootName::default(),
+            exact: false,
+            max_preallocation: 1024 * 1024,
+            interner: None,
+            #[cfg(feature = "rayon")]
+            min_parallel_list_len: 256,
+        }
+    }
+}
+
+impl DecodeOptions {
+    /// Options which only accept input that is encoded back into the same bytes
+    /// if the tag isn't modified.
+    ///
+    /// Enables [`strict`](DecodeOptions::strict) and [`exact`](DecodeOptions::exact) decoding,
+    /// rejects duplicate keys and keeps the root name.
+    ///
+    /// # Example
+    /// ```
+    /// use nbt::decode::{read_compound_tag_with_options, DecodeOptions};
+    /// use nbt::encode::write_compound_tag;
+    /// use std::io::Cursor;
+    ///
+    /// let input = include_bytes!("../test/binary/servers.dat").to_vec();
+    /// let options = DecodeOptions::round_trip();
+    /// let root_tag = read_compound_tag_with_options(&mut Cursor::new(&input), &options).unwrap();
+    ///
+    /// let mut vec = Vec::new();
+    /// write_compound_tag(&mut vec, &root_tag).unwrap();
+    ///
+    /// assert_eq!(vec, input);
+    /// ```
+    pub fn round_trip() -> Self {
+        DecodeOptions {
+            strict: true,
+            duplicate_keys: DuplicateKeys::Error,
+            root_name: RootName::Keep,
+            exact: true,
+            ..DecodeOptions::default()
+        }
+    }
+
+    /// Returns options which share names of compound tags through provided interner.
+    ///
+    /// # Example
+    /// ```
+    /// use nbt::decode::{read_compound_tag_with_options, DecodeOptions, Interner};
+    /// use std::io::Cursor;
+    ///
+    /// let interner = Interner::new();
+    /// let options = DecodeOptions::round_trip().interner(&interner);
+    ///
+    /// let input = include_bytes!("../test/binary/servers.dat").to_vec();
+    /// read_compound_tag_with_options(&mut Cursor::new(&input), &options).unwrap();
+    ///
+    /// assert!(!interner.is_empty());
+    /// ```
+    pub fn interner(mut self, interner: &Interner) -> Self {
+        self.interner = Some(interner.clone());
+        self
+    }
+}
+
+/// Location in the input where decoding failed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DecodePosition {
+    /// Absolute byte offset from the start of the input.
+    pub offset: u64,
+    /// Name of the enclosing named tag, if any.
+    pub name: Option<String>,
+    /// Path of the tag being decoded relative to the root tag.
+    pub path: NbtPath,
+}
+
+impl Display for DecodePosition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "at offset {}", self.offset)?;
+
+        if !self.path.is_empty() {
+            write!(f, " while reading {}", self.path)
+        } else if let Some(name) = &self.name {
+            write!(f, " in tag {}", name)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl TagDecodeError {
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            TagDecodeError::RootMustBeCompoundTag { .. } => ErrorKind::RootMustBeCompoundTag,
+            TagDecodeError::UnknownTagType { .. } => ErrorKind::UnknownTagType,
+            TagDecodeError::TrailingData { .. } => ErrorKind::TrailingData,
+            TagDecodeError::DepthLimitExceeded { .. } => ErrorKind::DepthLimitExceeded,
+            TagDecodeError::SizeLimitExceeded { .. } => ErrorKind::SizeLimitExceeded,
+            TagDecodeError::ElementLimitExceeded { .. } => ErrorKind::ElementLimitExceeded,
+            TagDecodeError::StringLimitExceeded { .. } => ErrorKind::StringLimitExceeded,
+            TagDecodeError::NegativeLength { .. } => ErrorKind::NegativeLength,
+            TagDecodeError::MalformedList { .. } => ErrorKind::MalformedList,
+            TagDecodeError::DuplicateKey { .. } => ErrorKind::DuplicateKey,
+            TagDecodeError::InvalidString { .. } => ErrorKind::InvalidString,
+            TagDecodeError::RootNameNotEmpty { .. } => ErrorKind::RootNameNotEmpty,
+            TagDecodeError::LengthExceedsInput { .. } => ErrorKind::LengthExceedsInput,
+            TagDecodeError::IOError { .. } => ErrorKind::Io,
+        }
+    }
+
+    /// Returns location where decoding failed, if error happened inside of input.
+    pub fn position(&self) -> Option<&DecodePosition> {
+        match self {
+            TagDecodeError::RootMustBeCompoundTag { .. } => None,
+            TagDecodeError::UnknownTagType { position, .. } => Some(position),
+            TagDecodeError::TrailingData { position, .. } => Some(position),
+            TagDecodeError::DepthLimitExceeded { position, .. } => Some(position),
+            TagDecodeError::SizeLimitExceeded { position, .. } => Some(position),
+            TagDecodeError::ElementLimitExceeded { position, .. } => Some(position),
+            TagDecodeError::StringLimitExceeded { position, .. } => Some(position),
+            TagDecodeError::NegativeLength { position, .. } => Some(position),
+            TagDecodeError::MalformedList { position, .. } => Some(position),
+            TagDecodeError::DuplicateKey { position, .. } => Some(position),
+            TagDecodeError::InvalidString { position } => Some(position),
+            TagDecodeError::RootNameNotEmpty { position, .. } => Some(position),
+            TagDecodeError::LengthExceedsInput { position, .. } => Some(position),
+            TagDecodeError::IOError { position, .. } => Some(position),
+        }
+    }
 }
 
+/// Error with unknown position, for I/O which happens outside of decoder.
 impl From<io::Error> for TagDecodeError {
     fn from(io_error: io::Error) -> Self {
-        TagDecodeError::IOError { io_error }
+        TagDecodeError::IOError {
+            io_error,
+            position: DecodePosition::default(),
+        }
     }
 }
 
 impl Error for TagDecodeError {
     fn source(&self) -> Option<&(dyn Error + 'static)> {
         match self {
-            TagDecodeError::IOError { io_error } => Some(io_error),
+            TagDecodeError::IOError { io_error, .. } => Some(io_error),
             _ => None,
         }
     }
@@ -45,19 +423,230 @@ impl Display for TagDecodeError {
                 "Root must be a TAG_Compound but is a {}",
                 actual_tag.type_name()
             ),
-            Self::UnknownTagType { tag_type_id } => write!(f, "Unknown tag type: {}", tag_type_id),
-            Self::IOError { .. } => write!(f, "IO Error"),
+            Self::UnknownTagType {
+                tag_type_id,
+                position,
+            } => write!(f, "Unknown tag type: {} {}", tag_type_id, position),
+            Self::TrailingData {
+                remaining,
+                position,
+            } => write!(
+                f,
+                "Trailing data: {} bytes left after root tag ended {}",
+                remaining, position
+            ),
+            Self::DepthLimitExceeded {
+                max_depth,
+                position,
+            } => write!(
+                f,
+                "Depth limit exceeded: tags nested deeper than {} {}",
+                max_depth, position
+            ),
+            Self::SizeLimitExceeded {
+                max_bytes,
+                position,
+            } => write!(
+                f,
+                "Size limit exceeded: input is larger than {} bytes {}",
+                max_bytes, position
+            ),
+            Self::ElementLimitExceeded {
+                length,
+                max_elements,
+                position,
+            } => write!(
+                f,
+                "Element limit exceeded: {} elements, maximum is {} {}",
+                length, max_elements, position
+            ),
+            Self::StringLimitExceeded {
+                length,
+                max_string_len,
+                position,
+            } => write!(
+                f,
+                "String limit exceeded: {} bytes, maximum is {} {}",
+                length, max_string_len, position
+            ),
+            Self::NegativeLength { length, position } => {
+                write!(f, "Negative length: {} {}", length, position)
+            }
+            Self::MalformedList {
+                tag_type_id,
+                length,
+                position,
+            } => write!(
+                f,
+                "Malformed list: element type {} with {} elements {}",
+                tag_type_id, length, position
+            ),
+            Self::DuplicateKey { name, position } => {
+                write!(f, "Duplicate key: {:?} {}", name, position)
+            }
+            Self::InvalidString { position } => {
+                write!(f, "String is not valid UTF-8 {}", position)
+            }
+            Self::RootNameNotEmpty { name, position } => {
+                write!(f, "Root name is not empty: {:?} {}", name, position)
+            }
+            Self::LengthExceedsInput {
+                length,
+                remaining,
+                position,
+            } => write!(
+                f,
+                "Length exceeds input: {} bytes claimed, {} bytes left {}",
+                length, remaining, position
+            ),
+            Self::IOError { io_error, position } => {
+                write!(f, "IO Error {}: {}", position, io_error)
+            }
         }
     }
 }
 
+/// Rich diagnostics with labeled spans over the input bytes.
+///
+/// Attach the input with `miette::Report::with_source_code` to render the spans.
+#[cfg(feature = "miette")]
+impl miette::Diagnostic for TagDecodeError {
+    fn code<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        let code = match self {
+            TagDecodeError::RootMustBeCompoundTag { .. } => {
+                "nbt::decode::root_must_be_compound_tag"
+            }
+            TagDecodeError::UnknownTagType { .. } => "nbt::decode::unknown_tag_type",
+            TagDecodeError::TrailingData { .. } => "nbt::decode::trailing_data",
+            TagDecodeError::DepthLimitExceeded { .. } => "nbt::decode::depth_limit_exceeded",
+            TagDecodeError::SizeLimitExceeded { .. } => "nbt::decode::size_limit_exceeded",
+            TagDecodeError::ElementLimitExceeded { .. } => "nbt::decode::element_limit_exceeded",
+            TagDecodeError::StringLimitExceeded { .. } => "nbt::decode::string_limit_exceeded",
+            TagDecodeError::NegativeLength { .. } => "nbt::decode::negative_length",
+            TagDecodeError::MalformedList { .. } => "nbt::decode::malformed_list",
+            TagDecodeError::DuplicateKey { .. } => "nbt::decode::duplicate_key",
+            TagDecodeError::InvalidString { .. } => "nbt::decode::invalid_string",
+            TagDecodeError::RootNameNotEmpty { .. } => "nbt::decode::root_name_not_empty",
+            TagDecodeError::LengthExceedsInput { .. } => "nbt::decode::length_exceeds_input",
+            TagDecodeError::IOError { .. } => "nbt::decode::io_error",
+        };
+
+        Some(Box::new(code))
+    }
+
+    fn help<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        let help = match self {
+            TagDecodeError::RootMustBeCompoundTag { .. } => {
+                "use read_tag to decode a payload which isn't a compound tag"
+            }
+            TagDecodeError::UnknownTagType { .. } => {
+                "input may be corrupted or compressed with a different algorithm"
+            }
+            TagDecodeError::TrailingData { .. } => {
+                "input may be truncated or contain several concatenated tags"
+            }
+            TagDecodeError::DepthLimitExceeded { .. } => {
+                "increase DecodeOptions::max_depth if input is trusted"
+            }
+            TagDecodeError::SizeLimitExceeded { .. } => {
+                "increase DecodeOptions::max_bytes if input is trusted"
+            }
+            TagDecodeError::ElementLimitExceeded { .. } => {
+                "increase DecodeOptions::max_elements if input is trusted"
+            }
+            TagDecodeError::StringLimitExceeded { .. } => {
+                "increase DecodeOptions::max_string_len if input is trusted"
+            }
+            TagDecodeError::NegativeLength { .. } => {
+                "disable DecodeOptions::strict to treat negative lengths as empty"
+            }
+            TagDecodeError::MalformedList { .. } => {
+                "disable DecodeOptions::strict and DecodeOptions::exact to read such lists as empty"
+            }
+            TagDecodeError::DuplicateKey { .. } => {
+                "use DuplicateKeys::KeepLast or DuplicateKeys::KeepFirst to tolerate duplicates"
+            }
+            TagDecodeError::InvalidString { .. } => {
+                "disable DecodeOptions::exact to replace invalid sequences"
+            }
+            TagDecodeError::RootNameNotEmpty { .. } => {
+                "use RootName::Keep or RootName::Skip to accept named root tags"
+            }
+            TagDecodeError::LengthExceedsInput { .. } => "input is truncated or corrupted",
+            TagDecodeError::IOError { .. } => return None,
+        };
+
+        Some(Box::new(help))
+    }
+
+    fn labels(&self) -> Option<Box<dyn Iterator<Item = miette::LabeledSpan> + '_>> {
+        let label = match self {
+            TagDecodeError::RootMustBeCompoundTag { .. } => return None,
+            TagDecodeError::UnknownTagType {
+                tag_type_id,
+                position,
+            } => miette::LabeledSpan::at(
+                position.offset as usize..position.offset as usize + 1,
+                format!("unknown tag type {}", tag_type_id),
+            ),
+            TagDecodeError::TrailingData {
+                remaining,
+                position,
+            } => miette::LabeledSpan::at(
+                position.offset as usize..(position.offset + remaining) as usize,
+                "trailing data",
+            ),
+            TagDecodeError::DepthLimitExceeded { position, .. } => {
+                miette::LabeledSpan::at_offset(position.offset as usize, "nested too deep")
+            }
+            TagDecodeError::SizeLimitExceeded { position, .. } => {
+                miette::LabeledSpan::at_offset(position.offset as usize, "size limit reached here")
+            }
+            TagDecodeError::ElementLimitExceeded { position, .. } => miette::LabeledSpan::at(
+                position.offset as usize..position.offset as usize + 4,
+                "too many elements",
+            ),
+            TagDecodeError::StringLimitExceeded { position, .. } => miette::LabeledSpan::at(
+                position.offset as usize..position.offset as usize + 2,
+                "string too long",
+            ),
+            TagDecodeError::NegativeLength { position, .. } => miette::LabeledSpan::at(
+                position.offset as usize..position.offset as usize + 4,
+                "negative length",
+            ),
+            TagDecodeError::MalformedList { position, .. } => miette::LabeledSpan::at(
+                position.offset as usize..position.offset as usize + 5,
+                "malformed list header",
+            ),
+            TagDecodeError::DuplicateKey { position, .. } => {
+                miette::LabeledSpan::at_offset(position.offset as usize, "duplicate key")
+            }
+            TagDecodeError::InvalidString { position } => {
+                miette::LabeledSpan::at_offset(position.offset as usize, "invalid string")
+            }
+            TagDecodeError::RootNameNotEmpty { name, position } => miette::LabeledSpan::at(
+                position.offset as usize..position.offset as usize + 2 + name.len(),
+                "root name",
+            ),
+            TagDecodeError::LengthExceedsInput { position, .. } => {
+                miette::LabeledSpan::at_offset(position.offset as usize, "length exceeds input")
+            }
+            TagDecodeError::IOError { position, .. } => {
+                miette::LabeledSpan::at_offset(position.offset as usize, "failed to read here")
+            }
+        };
+
+        Some(Box::new(std::iter::once(label)))
+    }
+}
+
 /// Read a compound tag from a reader compressed with gzip.
-pub fn read_gzip_compound_tag<R: Read>(reader: &mut R) -> Result<CompoundTag, TagDecodeError> {
+pub fn read_gzip_compound_tag<R: Read>(reader: &mut R) -> Result<CompoundTag> {
     read_compound_tag(&mut GzDecoder::new(reader))
 }
 
 /// Read a compound tag from a reader compressed with zlib.
-pub fn read_zlib_compound_tag<R: Read>(reader: &mut R) -> Result<CompoundTag, TagDecodeError> {
+pub fn read_zlib_compound_tag<R: Read>(reader: &mut R) -> Result<CompoundTag> {
     read_compound_tag(&mut ZlibDecoder::new(reader))
 }
 
@@ -83,133 +672,1621 @@ pub fn read_zlib_compound_tag<R: Read>(reader: &mut R) -> Result<CompoundTag, Ta
 /// assert_eq!(name, "Minecraft Server");
 /// assert!(hide_address);
 /// ```
-pub fn read_compound_tag<R: Read>(reader: &mut R) -> Result<CompoundTag, TagDecodeError> {
-    let tag_id = reader.read_u8()?;
-    let name = read_string(reader)?;
-    let tag = read_tag(tag_id, Some(name.as_str()), reader)?;
+pub fn read_compound_tag<R: Read>(reader: &mut R) -> Result<CompoundTag> {
+    read_compound_tag_with_options(reader, &DecodeOptions::default())
+}
 
-    match tag {
-        Tag::Compound(value) => Ok(value),
-        actual_tag => Err(TagDecodeError::RootMustBeCompoundTag { actual_tag }),
-    }
+/// Read a compound tag from a reader using provided options.
+pub fn read_compound_tag_with_options<R: Read>(
+    reader: &mut R,
+    options: &DecodeOptions,
+) -> Result<CompoundTag> {
+    TagDecoder::new(reader, options).read_root_compound_tag()
+}
+
+/// Read a compound tag from a reader or `None` if input contains `TAG_End` instead.
+///
+/// Single `TAG_End` means absence of a tag, e.g. in network protocol since 1.20.2.
+///
+/// # Example
+/// ```
+/// use nbt::decode::read_optional_compound_tag;
+/// use std::io::Cursor;
+///
+/// let mut cursor = Cursor::new(vec![0]);
+/// assert!(read_optional_compound_tag(&mut cursor).unwrap().is_none());
+///
+/// let mut cursor = Cursor::new(include_bytes!("../test/binary/servers.dat").to_vec());
+/// assert!(read_optional_compound_tag(&mut cursor).unwrap().is_some());
+/// ```
+pub fn read_optional_compound_tag<R: Read>(reader: &mut R) -> Result<Option<CompoundTag>> {
+    read_optional_compound_tag_with_options(reader, &DecodeOptions::default())
 }
 
-fn read_tag<R: Read>(
-    tag_id: u8,
-    name: Option<&str>,
+/// Read a compound tag from a reader using provided options or `None` if input
+/// contains `TAG_End` instead.
+pub fn read_optional_compound_tag_with_options<R: Read>(
     reader: &mut R,
-) -> Result<Tag, TagDecodeError> {
-    match tag_id {
-        1 => {
-            let value = reader.read_i8()?;
+    options: &DecodeOptions,
+) -> Result<Option<CompoundTag>> {
+    TagDecoder::new(reader, options).read_optional_root_compound_tag()
+}
 
-            Ok(Tag::Byte(value))
-        }
-        2 => {
-            let value = reader.read_i16::<BigEndian>()?;
+/// Read a compound tag from a cursor over bytes in memory using provided options.
+///
+/// Since input size is known, lengths of strings, lists and arrays are checked against
+/// remaining input before anything is allocated.
+///
+/// # Example
+/// ```
+/// use nbt::decode::{read_compound_tag_from_cursor, DecodeOptions, TagDecodeError};
+/// use std::io::Cursor;
+///
+/// // Root compound tag with byte array claiming 2 GiB.
+/// let vec = vec![10, 0, 0, 7, 0, 1, b'a', 127, 255, 255, 255];
+/// let mut cursor = Cursor::new(vec);
+///
+/// match read_compound_tag_from_cursor(&mut cursor, &DecodeOptions::default()) {
+///     Err(TagDecodeError::LengthExceedsInput { remaining, .. }) => assert_eq!(remaining, 0),
+///     _ => panic!(),
+/// }
+/// ```
+pub fn read_compound_tag_from_cursor<T: AsRef<[u8]>>(
+    cursor: &mut Cursor<T>,
+    options: &DecodeOptions,
+) -> Result<CompoundTag> {
+    let input_len = (cursor.get_ref().as_ref().len() as u64).saturating_sub(cursor.position());
 
-            Ok(Tag::Short(value))
-        }
-        3 => {
-            let value = reader.read_i32::<BigEndian>()?;
+    let mut decoder = TagDecoder::new(cursor, options);
+    decoder.input_len = Some(input_len);
 
-            Ok(Tag::Int(value))
-        }
-        4 => {
-            let value = reader.read_i64::<BigEndian>()?;
+    decoder.read_root_compound_tag()
+}
 
-            Ok(Tag::Long(value))
-        }
-        5 => {
-            let value = reader.read_f32::<BigEndian>()?;
+/// Read a compound tag from bytes in memory and return it together with number of bytes consumed.
+///
+/// Lengths are checked against slice size, see [`read_compound_tag_from_cursor`].
+///
+/// # Example
+/// ```
+/// use nbt::decode::read_compound_tag_from_slice;
+///
+/// let mut vec = include_bytes!("../test/binary/hello_world.dat").to_vec();
+/// let length = vec.len();
+/// vec.extend_from_slice(&[1, 2, 3]);
+///
+/// let (root_tag, consumed) = read_compound_tag_from_slice(&vec).unwrap();
+///
+/// assert_eq!(root_tag.get_str("name").unwrap(), "Bananrama");
+/// assert_eq!(consumed, length);
+/// ```
+pub fn read_compound_tag_from_slice(slice: &[u8]) -> Result<(CompoundTag, usize)> {
+    read_compound_tag_from_slice_with_options(slice, &DecodeOptions::default())
+}
 
-            Ok(Tag::Float(value))
-        }
-        6 => {
-            let value = reader.read_f64::<BigEndian>()?;
+/// Read a compound tag from bytes in memory using provided options and return it
+/// together with number of bytes consumed.
+pub fn read_compound_tag_from_slice_with_options(
+    slice: &[u8],
+    options: &DecodeOptions,
+) -> Result<(CompoundTag, usize)> {
+    let mut cursor = Cursor::new(slice);
+    let compound_tag = read_compound_tag_from_cursor(&mut cursor, options)?;
+
+    Ok((compound_tag, cursor.position() as usize))
+}
+
+/// Read a compound tag from a buffer, e.g. network frame, advancing it past the tag.
+///
+/// Bytes are read directly from buffer chunks and lengths are checked against
+/// remaining bytes, see [`read_compound_tag_from_cursor`].
+///
+/// # Example
+/// ```
+/// use bytes::{Buf, Bytes};
+/// use nbt::decode::read_compound_tag_buf;
+///
+/// let mut vec = include_bytes!("../test/binary/hello_world.dat").to_vec();
+/// vec.push(0x1a);
+///
+/// let mut frame = Bytes::from(vec);
+/// let root_tag = read_compound_tag_buf(&mut frame).unwrap();
+///
+/// assert_eq!(root_tag.get_str("name").unwrap(), "Bananrama");
+/// assert_eq!(frame.get_u8(), 0x1a);
+/// ```
+#[cfg(feature = "bytes")]
+pub fn read_compound_tag_buf<B: bytes::Buf>(buf: &mut B) -> Result<CompoundTag> {
+    read_compound_tag_buf_with_options(buf, &DecodeOptions::default())
+}
+
+/// Read a compound tag from a buffer using provided options, advancing it past the tag.
+#[cfg(feature = "bytes")]
+pub fn read_compound_tag_buf_with_options<B: bytes::Buf>(
+    buf: &mut B,
+    options: &DecodeOptions,
+) -> Result<CompoundTag> {
+    let input_len = buf.remaining() as u64;
+    let mut reader = bytes::Buf::reader(buf);
+
+    let mut decoder = TagDecoder::new(&mut reader, options);
+    decoder.input_len = Some(input_len);
+
+    decoder.read_root_compound_tag()
+}
+
+/// Read a compound tag from a reader and return it together with number of bytes consumed.
+///
+/// Decoder never reads past the end of the root tag, so reader is left right after it.
+/// Useful when tag is embedded in a stream without length prefix.
+///
+/// # Example
+/// ```
+/// use nbt::decode::{read_compound_tag_counted, DecodeOptions};
+/// use std::io::{Cursor, Read};
+///
+/// let mut vec = include_bytes!("../test/binary/hello_world.dat").to_vec();
+/// vec.extend_from_slice(&[1, 2, 3]);
+///
+/// let mut reader = Cursor::new(vec);
+/// let (_, consumed) = read_compound_tag_counted(&mut reader, &DecodeOptions::default()).unwrap();
+/// assert_eq!(consumed, 33);
+///
+/// let mut rest = Vec::new();
+/// reader.read_to_end(&mut rest).unwrap();
+/// assert_eq!(rest, vec![1, 2, 3]);
+/// ```
+pub fn read_compound_tag_counted<R: Read>(
+    reader: &mut R,
+    options: &DecodeOptions,
+) -> Result<(CompoundTag, u64)> {
+    let mut decoder = TagDecoder::new(reader, options);
+    let compound_tag = decoder.read_root_compound_tag()?;
+
+    Ok((compound_tag, decoder.offset))
+}
 
-            Ok(Tag::Double(value))
+/// Read a compound tag from a reader and check that reader has no data left after it.
+///
+/// Reader is consumed until end, so it must not be a stream which never ends
+/// (e.g. a network connection).
+///
+/// # Example
+/// ```
+/// use nbt::decode::{read_compound_tag_fully, TagDecodeError};
+/// use std::io::Cursor;
+///
+/// let mut vec = include_bytes!("../test/binary/servers.dat").to_vec();
+/// vec.push(0);
+///
+/// match read_compound_tag_fully(&mut Cursor::new(vec)) {
+///     Err(TagDecodeError::TrailingData { remaining, .. }) => assert_eq!(remaining, 1),
+///     _ => panic!(),
+/// }
+/// ```
+pub fn read_compound_tag_fully<R: Read>(reader: &mut R) -> Result<CompoundTag> {
+    read_compound_tag_fully_with_options(reader, &DecodeOptions::default())
+}
+
+/// Read a compound tag from a reader using provided options and check that reader has no data
+/// left after it.
+///
+/// Data after the root tag counts towards [`DecodeOptions::max_bytes`], so with the limit
+/// reader is consumed at most one byte past it.
+pub fn read_compound_tag_fully_with_options<R: Read>(
+    reader: &mut R,
+    options: &DecodeOptions,
+) -> Result<CompoundTag> {
+    let mut decoder = TagDecoder::new(reader, options);
+    let compound_tag = decoder.read_root_compound_tag()?;
+    decoder.read_end()?;
+
+    Ok(compound_tag)
+}
+
+/// Read a single tag payload of provided type from a reader.
+///
+/// Unlike [`read_compound_tag`] neither type id nor name are read, only the payload itself.
+///
+/// # Example
+/// ```
+/// use nbt::decode::read_tag;
+/// use nbt::{Tag, TagType};
+/// use std::io::Cursor;
+///
+/// let mut cursor = Cursor::new(vec![0, 0, 0, 2, 0, 0, 0, 1, 0, 0, 0, 2]);
+/// let tag = read_tag(TagType::IntArray, &mut cursor).unwrap();
+///
+/// match tag {
+///     Tag::IntArray(value) => assert_eq!(value, vec![1, 2]),
+///     _ => panic!(),
+/// }
+/// ```
+pub fn read_tag<R: Read>(tag_type: TagType, reader: &mut R) -> Result<Tag> {
+    read_tag_with_options(tag_type, reader, &DecodeOptions::default())
+}
+
+/// Read a single tag payload of provided type from a reader using provided options.
+pub fn read_tag_with_options<R: Read>(
+    tag_type: TagType,
+    reader: &mut R,
+    options: &DecodeOptions,
+) -> Result<Tag> {
+    TagDecoder::new(reader, options).read_payload(tag_type, false)
+}
+
+/// Decoding state: underlying reader, number of bytes consumed
+/// and breadcrumbs of tags being decoded, used to report error positions.
+struct TagDecoder<'r, 'o, R> {
+    reader: &'r mut R,
+    options: &'o DecodeOptions,
+    offset: u64,
+    /// Total input size in bytes, if known.
+    input_len: Option<u64>,
+    depth: usize,
+    root_name: Option<String>,
+    path: Vec<PathSegment>,
+    /// Whole input, if it is in memory. Offsets are relative to its start.
+    #[cfg(feature = "rayon")]
+    input: Option<&'r [u8]>,
+    /// Buffer for bytes of strings and arrays before they are converted.
+    scratch: Vec<u8>,
+    /// Names for the path of compound tags decoded into an arena, reused between them.
+    #[cfg(feature = "bumpalo")]
+    key_buffers: Vec<String>,
+}
+
+impl<'r, 'o, R: Read> TagDecoder<'r, 'o, R> {
+    fn new(reader: &'r mut R, options: &'o DecodeOptions) -> Self {
+        TagDecoder {
+            reader,
+            options,
+            offset: 0,
+            input_len: None,
+            depth: 0,
+            root_name: None,
+            path: Vec::new(),
+            #[cfg(feature = "rayon")]
+            input: None,
+            scratch: Vec::new(),
+            #[cfg(feature = "bumpalo")]
+            key_buffers: Vec::new(),
         }
-        7 => {
-            let length = reader.read_u32::<BigEndian>()?;
-            let mut value = Vec::new();
+    }
 
-            for _ in 0..length {
-                value.push(reader.read_i8()?);
-            }
+    /// Name of the innermost named tag being decoded.
+    fn current_name(&self) -> Option<&String> {
+        let name = self.path.iter().rev().find_map(|segment| match segment {
+            PathSegment::Key(name) => Some(name),
+            _ => None,
+        });
+
+        name.or(self.root_name.as_ref())
+    }
 
-            Ok(Tag::ByteArray(value))
+    fn position_at(&self, offset: u64) -> DecodePosition {
+        DecodePosition {
+            offset,
+            name: self.current_name().cloned(),
+            path: NbtPath::from(self.path.clone()),
         }
-        8 => {
-            let value = read_string(reader)?;
+    }
 
-            Ok(Tag::String(value))
+    fn io_error(&self, io_error: io::Error) -> TagDecodeError {
+        TagDecodeError::IOError {
+            io_error,
+            position: self.position_at(self.offset),
         }
-        9 => {
-            let list_tags_id = reader.read_u8()?;
-            let length = reader.read_u32::<BigEndian>()?;
-            let mut value = Vec::new();
+    }
 
-            for _ in 0..length {
-                value.push(read_tag(list_tags_id, None, reader)?);
+    /// Checks that provided number of bytes can be read without exceeding size limit.
+    fn reserve(&self, bytes: u64) -> Result<()> {
+        match self.options.max_bytes {
+            Some(max_bytes) if self.offset + bytes > max_bytes => {
+                Err(TagDecodeError::SizeLimitExceeded {
+                    max_bytes,
+                    position: self.position_at(self.offset),
+                })
             }
+            _ => Ok(()),
+        }
+    }
 
-            Ok(Tag::List(value))
+    /// Enters compound or list tag, checking depth limit.
+    fn enter(&mut self) -> Result<()> {
+        if self.depth >= self.options.max_depth {
+            return Err(TagDecodeError::DepthLimitExceeded {
+                max_depth: self.options.max_depth,
+                position: self.position_at(self.offset),
+            });
         }
-        10 => {
-            let mut tags = LinkedHashMap::new();
 
-            loop {
-                let tag_id = reader.read_u8()?;
+        self.depth += 1;
 
-                // Compound tag end reached.
-                if tag_id == 0 {
-                    break;
-                }
+        Ok(())
+    }
 
-                let name = read_string(reader)?;
-                let tag = read_tag(tag_id, Some(name.as_str()), reader)?;
+    fn read_u8(&mut self) -> Result<u8> {
+        self.reserve(1)?;
+        let value = self.reader.read_u8().map_err(|e| self.io_error(e))?;
+        self.offset += 1;
 
-                tags.insert(name, tag);
-            }
+        Ok(value)
+    }
 
-            let compound_tag = CompoundTag {
-                name: name.map(|s| s.into()),
-                tags,
-            };
+    fn read_i8(&mut self) -> Result<i8> {
+        self.reserve(1)?;
+        let value = self.reader.read_i8().map_err(|e| self.io_error(e))?;
+        self.offset += 1;
 
-            Ok(Tag::Compound(compound_tag))
-        }
-        11 => {
-            let length = reader.read_u32::<BigEndian>()?;
-            let mut value = Vec::new();
+        Ok(value)
+    }
 
-            for _ in 0..length {
-                value.push(reader.read_i32::<BigEndian>()?);
-            }
+    fn read_u16(&mut self) -> Result<u16> {
+        self.reserve(2)?;
+        let value = self
+            .reader
+            .read_u16::<BigEndian>()
+            .map_err(|e| self.io_error(e))?;
+        self.offset += 2;
 
-            Ok(Tag::IntArray(value))
-        }
-        12 => {
-            let length = reader.read_u32::<BigEndian>()?;
-            let mut value = Vec::new();
+        Ok(value)
+    }
 
-            for _ in 0..length {
-                value.push(reader.read_i64::<BigEndian>()?);
-            }
+    fn read_i16(&mut self) -> Result<i16> {
+        self.reserve(2)?;
+        let value = self
+            .reader
+            .read_i16::<BigEndian>()
+            .map_err(|e| self.io_error(e))?;
+        self.offset += 2;
 
-            Ok(Tag::LongArray(value))
-        }
-        tag_type_id => Err(TagDecodeError::UnknownTagType { tag_type_id }),
+        Ok(value)
     }
-}
 
-fn read_string<R: Read>(reader: &mut R) -> Result<String, TagDecodeError> {
-    let length = reader.read_u16::<BigEndian>()?;
-    let mut buf = vec![0; length as usize];
-    reader.read_exact(&mut buf)?;
+    fn read_i32(&mut self) -> Result<i32> {
+        self.reserve(4)?;
+        let value = self
+            .reader
+            .read_i32::<BigEndian>()
+            .map_err(|e| self.io_error(e))?;
+        self.offset += 4;
+
+        Ok(value)
+    }
+
+    fn read_i64(&mut self) -> Result<i64> {
+        self.reserve(8)?;
+        let value = self
+            .reader
+            .read_i64::<BigEndian>()
+            .map_err(|e| self.io_error(e))?;
+        self.offset += 8;
+
+        Ok(value)
+    }
+
+    fn read_f32(&mut self) -> Result<f32> {
+        self.reserve(4)?;
+        let value = self
+            .reader
+            .read_f32::<BigEndian>()
+            .map_err(|e| self.io_error(e))?;
+        self.offset += 4;
+
+        Ok(value)
+    }
 
-    Ok(String::from_utf8_lossy(&buf).into_owned())
+    fn read_f64(&mut self) -> Result<f64> {
+        self.reserve(8)?;
+        let value = self
+            .reader
+            .read_f64::<BigEndian>()
+            .map_err(|e| self.io_error(e))?;
+        self.offset += 8;
+
+        Ok(value)
+    }
+
+    fn read_string(&mut self) -> Result<String> {
+        Ok(self.read_str()?.into_owned())
+    }
+
+    /// Reads string into scratch buffer, so it can be copied elsewhere without allocating.
+    fn read_str(&mut self) -> Result<Cow<'_, str>> {
+        let length_offset = self.offset;
+        let length = self.read_string_length()?;
+        self.read_scratch(u64::from(length))?;
+
+        if self.options.exact {
+            return match std::str::from_utf8(&self.scratch) {
+                Ok(value) => Ok(Cow::Borrowed(value)),
+                Err(_) => Err(TagDecodeError::InvalidString {
+                    position: self.position_at(length_offset),
+                }),
+            };
+        }
+
+        Ok(String::from_utf8_lossy(&self.scratch))
+    }
+
+    /// Reads provided number of bytes at once.
+    ///
+    /// Buffer grows while data is actually read beyond [`DecodeOptions::max_preallocation`],
+    /// so bogus lengths can't allocate more than input has.
+    fn read_bytes(&mut self, length: u64) -> Result<Vec<u8>> {
+        let capacity = length.min(self.options.max_preallocation as u64);
+        let mut bytes = Vec::with_capacity(capacity as usize);
+        let result = (&mut *self.reader).take(length).read_to_end(&mut bytes);
+        result.map_err(|e| self.io_error(e))?;
+
+        if (bytes.len() as u64) < length {
+            return Err(self.io_error(io::ErrorKind::UnexpectedEof.into()));
+        }
+
+        self.offset += length;
+
+        Ok(bytes)
+    }
+
+    /// Reads provided number of bytes into scratch buffer, replacing its contents.
+    ///
+    /// Used for data which is converted right away, so buffer is allocated only once per decoder.
+    fn read_scratch(&mut self, length: u64) -> Result<()> {
+        let capacity = length.min(self.options.max_preallocation as u64);
+        self.scratch.clear();
+        self.scratch.reserve(capacity as usize);
+
+        let result = (&mut *self.reader)
+            .take(length)
+            .read_to_end(&mut self.scratch);
+        result.map_err(|e| self.io_error(e))?;
+
+        if (self.scratch.len() as u64) < length {
+            return Err(self.io_error(io::ErrorKind::UnexpectedEof.into()));
+        }
+
+        self.offset += length;
+
+        Ok(())
+    }
+
+    /// Reads array of provided length.
+    ///
+    /// If input size is known, length is already checked, so bytes are read straight into
+    /// the elements and swapped in place. Otherwise they are read into scratch buffer first.
+    fn read_array<T: ArrayElement>(
+        &mut self,
+        length: u32,
+        read_into: fn(&[u8], &mut [T]),
+        from_slice: fn(&mut [T]),
+    ) -> Result<Vec<T>> {
+        // Bytes are read first, so bogus lengths can't allocate more than input has.
+        if self.input_len.is_none() {
+            self.read_scratch(u64::from(length) * std::mem::size_of::<T>() as u64)?;
+
+            let mut value = vec![T::default(); length as usize];
+            read_into(&self.scratch, &mut value);
+
+            return Ok(value);
+        }
+
+        let mut value = vec![T::default(); length as usize];
+        let bytes = T::slice_as_bytes_mut(&mut value);
+        let result = self.reader.read_exact(bytes);
+        result.map_err(|e| self.io_error(e))?;
+
+        self.offset += bytes.len() as u64;
+        from_slice(&mut value);
+
+        Ok(value)
+    }
+
+    /// Skips provided number of bytes.
+    fn skip(&mut self, length: u64) -> Result<()> {
+        self.reserve(length)?;
+
+        let result = io::copy(&mut (&mut *self.reader).take(length), &mut io::sink());
+        let skipped = result.map_err(|e| self.io_error(e))?;
+
+        if skipped < length {
+            return Err(self.io_error(io::ErrorKind::UnexpectedEof.into()));
+        }
+
+        self.offset += length;
+
+        Ok(())
+    }
+
+    /// Reads and checks length of a string, which is then ready to be read.
+    fn read_string_length(&mut self) -> Result<u16> {
+        let length_offset = self.offset;
+        let length = self.read_u16()?;
+
+        if let Some(max_string_len) = self.options.max_string_len {
+            if usize::from(length) > max_string_len {
+                return Err(TagDecodeError::StringLimitExceeded {
+                    length: usize::from(length),
+                    max_string_len,
+                    position: self.position_at(length_offset),
+                });
+            }
+        }
+
+        self.check_remaining(u64::from(length), length_offset)?;
+        self.reserve(u64::from(length))?;
+
+        Ok(length)
+    }
+
+    /// Checks that provided number of bytes claimed by length is left in input, if input size is known.
+    fn check_remaining(&self, length: u64, length_offset: u64) -> Result<()> {
+        match self.input_len {
+            Some(input_len) if self.offset + length > input_len => {
+                Err(TagDecodeError::LengthExceedsInput {
+                    length,
+                    remaining: input_len.saturating_sub(self.offset),
+                    position: self.position_at(length_offset),
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Reads length of list or array with elements of provided size in bytes.
+    ///
+    /// Lengths are signed, negative length means empty list or array unless decoding is strict.
+    fn read_length(&mut self, element_size: u64) -> Result<u32> {
+        let length_offset = self.offset;
+        let length = self.read_i32()?;
+
+        if length < 0 && self.options.strict {
+            return Err(TagDecodeError::NegativeLength {
+                length,
+                position: self.position_at(length_offset),
+            });
+        }
+
+        let length = length.max(0) as u32;
+
+        if let Some(max_elements) = self.options.max_elements {
+            if length as usize > max_elements {
+                return Err(TagDecodeError::ElementLimitExceeded {
+                    length: length as usize,
+                    max_elements,
+                    position: self.position_at(length_offset),
+                });
+            }
+        }
+
+        let size = u64::from(length) * element_size;
+        self.check_remaining(size, length_offset)?;
+        self.reserve(size)?;
+
+        Ok(length)
+    }
+
+    /// Reads type id of a named tag.
+    fn read_tag_id(&mut self) -> Result<TagType> {
+        let offset = self.offset;
+        let tag_type_id = self.read_u8()?;
+
+        self.tag_type(tag_type_id, offset)
+    }
+
+    fn tag_type(&self, tag_type_id: u8, offset: u64) -> Result<TagType> {
+        match TagType::from_id(tag_type_id) {
+            Some(tag_type) => Ok(tag_type),
+            None if tag_type_id != 0 && self.options.unknown_tag_reader.is_some() => {
+                Ok(TagType::Unknown(tag_type_id))
+            }
+            None => Err(TagDecodeError::UnknownTagType {
+                tag_type_id,
+                position: self.position_at(offset),
+            }),
+        }
+    }
+
+    fn read_root_compound_tag(&mut self) -> Result<CompoundTag> {
+        let tag_id = self.read_tag_id()?;
+
+        self.read_root_compound_payload(tag_id)
+    }
+
+    /// Same as `read_root_compound_tag`, but returns `None` if tag type id is `TAG_End`.
+    fn read_optional_root_compound_tag(&mut self) -> Result<Option<CompoundTag>> {
+        let tag_offset = self.offset;
+        let tag_id = self.read_u8()?;
+
+        if tag_id == 0 {
+            return Ok(None);
+        }
+
+        let tag_type = self.tag_type(tag_id, tag_offset)?;
+
+        self.read_root_compound_payload(tag_type).map(Some)
+    }
+
+    /// Reads name and payload of the root tag, which must be a compound tag.
+    fn read_root_compound_payload(&mut self, tag_id: TagType) -> Result<CompoundTag> {
+        let name = self.read_root_name()?;
+        let tag = self.read_root_payload(tag_id, name)?;
+
+        match tag {
+            Tag::Compound(value) => Ok(value),
+            actual_tag => Err(TagDecodeError::RootMustBeCompoundTag { actual_tag }),
+        }
+    }
+
+    /// Reads name of the root tag according to [`DecodeOptions::root_name`].
+    fn read_root_name(&mut self) -> Result<Option<String>> {
+        match self.options.root_name {
+            RootName::Keep => self.read_string().map(Some),
+            RootName::Skip => {
+                let length = self.read_string_length()?;
+                self.skip(u64::from(length))?;
+
+                Ok(None)
+            }
+            RootName::RequireEmpty => {
+                let name_offset = self.offset;
+                let name = self.read_string()?;
+
+                if !name.is_empty() {
+                    return Err(TagDecodeError::RootNameNotEmpty {
+                        name,
+                        position: self.position_at(name_offset),
+                    });
+                }
+
+                Ok(Some(name))
+            }
+        }
+    }
+
+    /// Checks that reader has no data left.
+    fn read_end(&mut self) -> Result<()> {
+        // Reader can't consume more than one byte past the size limit.
+        let limit = self.options.max_bytes.map_or(u64::MAX, |max_bytes| {
+            max_bytes.saturating_sub(self.offset).saturating_add(1)
+        });
+        let remaining = io::copy(&mut (&mut *self.reader).take(limit), &mut io::sink())
+            .map_err(|e| self.io_error(e))?;
+        self.reserve(remaining)?;
+
+        if remaining > 0 {
+            return Err(TagDecodeError::TrailingData {
+                remaining,
+                position: self.position_at(self.offset),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Reads payload of the root tag with provided name.
+    fn read_root_payload(&mut self, tag_type: TagType, name: Option<String>) -> Result<Tag> {
+        self.root_name = name;
+        let tag = self.read_payload(tag_type, true)?;
+        self.root_name = None;
+
+        Ok(tag)
+    }
+
+    /// Reads payload of a compound tag entry and returns name back together with tag.
+    fn read_named_payload(&mut self, tag_type: TagType, name: String) -> Result<(String, Tag)> {
+        self.path.push(PathSegment::Key(name));
+        let tag = self.read_payload(tag_type, true)?;
+
+        match self.path.pop() {
+            Some(PathSegment::Key(name)) => Ok((name, tag)),
+            _ => unreachable!("Path must end with key pushed above"),
+        }
+    }
+
+    fn read_list(&mut self) -> Result<Tag> {
+        self.enter()?;
+
+        let (list_tags_type, length) = self.read_list_header()?;
+        let max_capacity = self.options.max_preallocation / std::mem::size_of::<Tag>();
+        let mut value = Vec::with_capacity(match list_tags_type {
+            Some(_) => (length as usize).min(max_capacity),
+            None => 0,
+        });
+
+        #[cfg(feature = "rayon")]
+        if let Some(list_tags_type) = list_tags_type {
+            if self.is_parallel_list(list_tags_type, length) {
+                value = self.read_list_elements_par(list_tags_type, length)?;
+                self.depth -= 1;
+
+                return Ok(Tag::List(value));
+            }
+        }
+
+        if let Some(list_tags_type) = list_tags_type {
+            for index in 0..length {
+                self.path.push(PathSegment::Index(index as usize));
+                value.push(self.read_payload(list_tags_type, false)?);
+                self.path.pop();
+            }
+        }
+
+        self.depth -= 1;
+
+        Ok(Tag::List(value))
+    }
+
+    /// Reads element type and length of a list.
+    ///
+    /// Element type is `None` if there are no elements to read.
+    fn read_list_header(&mut self) -> Result<(Option<TagType>, u32)> {
+        let list_tags_offset = self.offset;
+        let list_tags_id = self.read_u8()?;
+        let element_size = TagType::from_id(list_tags_id).map_or(0, min_payload_size);
+        let length = self.read_length(element_size)?;
+
+        let is_malformed = match TagType::from_id(list_tags_id) {
+            Some(_) => self.options.exact && length == 0,
+            None if list_tags_id == 0 => self.options.strict && length > 0,
+            None => (self.options.strict || self.options.exact) && length == 0,
+        };
+
+        if is_malformed {
+            return Err(TagDecodeError::MalformedList {
+                tag_type_id: list_tags_id,
+                length,
+                position: self.position_at(list_tags_offset),
+            });
+        }
+
+        // Element type of empty lists is not meaningful. Elements of TAG_End type have no payload,
+        // so such lists are read as empty.
+        if length > 0 && list_tags_id != 0 {
+            let list_tags_type = self.tag_type(list_tags_id, list_tags_offset)?;
+
+            Ok((Some(list_tags_type), length))
+        } else {
+            Ok((None, length))
+        }
+    }
+
+    fn read_compound(&mut self, named: bool) -> Result<Tag> {
+        self.enter()?;
+
+        let mut tags = TagMap::new();
+
+        loop {
+            let tag_offset = self.offset;
+            let tag_id = self.read_u8()?;
+
+            // Compound tag end reached.
+            if tag_id == 0 {
+                break;
+            }
+
+            let tag_type = self.tag_type(tag_id, tag_offset)?;
+            let name = self.read_string()?;
+            let is_duplicate = tags.contains_key(&name);
+
+            if is_duplicate && self.options.duplicate_keys == DuplicateKeys::Error {
+                return Err(TagDecodeError::DuplicateKey {
+                    name,
+                    position: self.position_at(tag_offset),
+                });
+            }
+
+            let (name, tag) = self.read_named_payload(tag_type, name)?;
+
+            if !is_duplicate || self.options.duplicate_keys == DuplicateKeys::KeepLast {
+                match &self.options.interner {
+                    Some(interner) => tags.insert(interner.intern(&name), tag),
+                    None => tags.insert(name, tag),
+                };
+            }
+        }
+
+        let name = if named {
+            self.current_name().cloned()
+        } else {
+            None
+        };
+
+        let compound_tag = CompoundTag { name, tags };
+        self.depth -= 1;
+
+        Ok(Tag::Compound(compound_tag))
+    }
+
+    fn read_payload(&mut self, tag_type: TagType, named: bool) -> Result<Tag> {
+        match tag_type {
+            TagType::Byte => {
+                let value = self.read_i8()?;
+
+                Ok(Tag::Byte(value))
+            }
+            TagType::Short => {
+                let value = self.read_i16()?;
+
+                Ok(Tag::Short(value))
+            }
+            TagType::Int => {
+                let value = self.read_i32()?;
+
+                Ok(Tag::Int(value))
+            }
+            TagType::Long => {
+                let value = self.read_i64()?;
+
+                Ok(Tag::Long(value))
+            }
+            TagType::Float => {
+                let value = self.read_f32()?;
+
+                Ok(Tag::Float(value))
+            }
+            TagType::Double => {
+                let value = self.read_f64()?;
+
+                Ok(Tag::Double(value))
+            }
+            TagType::ByteArray => {
+                let length = self.read_length(1)?;
+                let bytes = self.read_bytes(u64::from(length))?;
+                let value = bytes.into_iter().map(|v| v as i8).collect();
+
+                Ok(Tag::ByteArray(value))
+            }
+            TagType::String => {
+                let value = self.read_string()?;
+
+                Ok(Tag::String(value))
+            }
+            TagType::List => self.read_list(),
+            TagType::Compound => self.read_compound(named),
+            TagType::IntArray => {
+                let length = self.read_length(4)?;
+                let value =
+                    self.read_array(length, BigEndian::read_i32_into, BigEndian::from_slice_i32)?;
+
+                Ok(Tag::IntArray(value))
+            }
+            TagType::Unknown(id) => {
+                let read_unknown = self
+                    .options
+                    .unknown_tag_reader
+                    .expect("Unknown tag types are only accepted with unknown tag reader");
+
+                // Reader can't consume more than one byte past the size limit.
+                let limit = self.options.max_bytes.map_or(u64::MAX, |max_bytes| {
+                    max_bytes.saturating_sub(self.offset).saturating_add(1)
+                });
+                let mut reader = (&mut *self.reader).take(limit);
+                let result = read_unknown(id, &mut reader);
+                let consumed = limit - reader.limit();
+
+                self.reserve(consumed)?;
+                let bytes = result.map_err(|e| self.io_error(e))?;
+                self.offset += consumed;
+
+                Ok(Tag::Unknown { id, bytes })
+            }
+            TagType::LongArray => {
+                let length = self.read_length(8)?;
+                let value =
+                    self.read_array(length, BigEndian::read_i64_into, BigEndian::from_slice_i64)?;
+
+                Ok(Tag::LongArray(value))
+            }
+        }
+    }
+}
+
+/// Element of int or long array.
+trait ArrayElement: Copy + Default {
+    fn slice_as_bytes_mut(slice: &mut [Self]) -> &mut [u8];
+}
+
+macro_rules! impl_array_element {
+    ($type: ty) => {
+        impl ArrayElement for $type {
+            fn slice_as_bytes_mut(slice: &mut [Self]) -> &mut [u8] {
+                let len = std::mem::size_of_val(slice);
+
+                // SAFETY: Integers have no padding and every bit pattern is valid for them,
+                // so their memory can be viewed and written as bytes.
+                unsafe { std::slice::from_raw_parts_mut(slice.as_mut_ptr() as *mut u8, len) }
+            }
+        }
+    };
+}
+
+impl_array_element!(i32);
+impl_array_element!(i64);
+
+/// Returns lower bound of payload size in bytes.
+fn min_payload_size(tag_type: TagType) -> u64 {
+    match tag_type {
+        TagType::Byte => 1,
+        TagType::Short => 2,
+        TagType::Int => 4,
+        TagType::Long => 8,
+        TagType::Float => 4,
+        TagType::Double => 8,
+        TagType::ByteArray => 4,
+        TagType::String => 2,
+        // Element type and length.
+        TagType::List => 5,
+        // End tag.
+        TagType::Compound => 1,
+        TagType::IntArray => 4,
+        TagType::LongArray => 4,
+        TagType::Unknown(_) => 0,
+    }
+}
+
+#[test]
+fn test_read_tag() {
+    use std::convert::TryFrom;
+    use std::io::Cursor;
+
+    let mut cursor = Cursor::new(vec![2, 0, 0, 0, 2, 0, 1, 0, 2]);
+    let tag = read_tag(TagType::List, &mut cursor).unwrap();
+
+    match tag {
+        Tag::List(value) => {
+            assert_eq!(value.len(), 2);
+            assert_eq!(i16::try_from(&value[0]).unwrap(), 1);
+            assert_eq!(i16::try_from(&value[1]).unwrap(), 2);
+        }
+        _ => panic!(),
+    }
+
+    let mut cursor = Cursor::new(vec![0, 0, 0, 0, 0, 0, 0, 4]);
+    let tag = read_tag(TagType::Long, &mut cursor).unwrap();
+
+    match tag {
+        Tag::Long(value) => assert_eq!(value, 4),
+        _ => panic!(),
+    }
+}
+
+#[test]
+fn test_read_arrays() {
+    use crate::encode::write_tag;
+
+    let tags = vec![
+        Tag::ByteArray(vec![-1, 0, 1]),
+        Tag::IntArray(vec![i32::MIN, 0, i32::MAX]),
+        Tag::LongArray(vec![i64::MIN, 0, i64::MAX]),
+    ];
+
+    for tag in tags {
+        let mut vec = Vec::new();
+        write_tag(&mut vec, &tag).unwrap();
+
+        let decoded = read_tag(tag.tag_type(), &mut Cursor::new(vec.clone())).unwrap();
+        assert_eq!(decoded, tag);
+
+        vec.pop();
+
+        let error = read_tag(tag.tag_type(), &mut Cursor::new(vec)).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::Io);
+        assert_eq!(error.position().unwrap().offset, 4);
+    }
+
+    // Arrays are read in place when input size is known.
+    let mut root_tag = CompoundTag::new();
+    root_tag.insert_i32_vec("ints", (0..1000).collect());
+    root_tag.insert_i64_vec("longs", (0..1000).map(|v| v << 40).collect());
+
+    let mut vec = Vec::new();
+    crate::encode::write_compound_tag(&mut vec, &root_tag).unwrap();
+
+    let (decoded, _) = read_compound_tag_from_slice(&vec).unwrap();
+    assert_eq!(decoded, root_tag);
+}
+
+#[test]
+fn test_preallocation() {
+    use crate::encode::write_tag;
+
+    let tag = Tag::List(vec![Tag::Byte(1); 100]);
+    let mut vec = Vec::new();
+    write_tag(&mut vec, &tag).unwrap();
+
+    let read = |max_preallocation| {
+        let options = DecodeOptions {
+            max_preallocation,
+            ..Default::default()
+        };
+
+        read_tag_with_options(TagType::List, &mut Cursor::new(&vec), &options).unwrap()
+    };
+
+    match read(DecodeOptions::default().max_preallocation) {
+        Tag::List(value) => assert_eq!(value.capacity(), 100),
+        _ => panic!(),
+    }
+
+    match read(0) {
+        Tag::List(value) => assert_eq!(value.len(), 100),
+        _ => panic!(),
+    }
+
+    // Bogus length is not allocated upfront.
+    let vec = vec![0x7f, 0xff, 0xff, 0xff, 1, 2, 3];
+    let error = read_tag(TagType::ByteArray, &mut Cursor::new(vec)).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::Io);
+
+    // Same for int and long arrays read from a reader of unknown size.
+    for tag_type in [TagType::IntArray, TagType::LongArray] {
+        let error = read_tag(tag_type, &mut &[0x7f, 0xff, 0xff, 0xff, 1, 2, 3][..]).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::Io);
+    }
+}
+
+#[test]
+fn test_error_position() {
+    use std::io::Cursor;
+
+    let mut vec = include_bytes!("../test/binary/hello_world.dat").to_vec();
+    // Replace type id of the "name" tag.
+    vec[14] = 47;
+
+    let mut cursor = Cursor::new(vec);
+    let error = read_compound_tag(&mut cursor).unwrap_err();
+
+    match &error {
+        TagDecodeError::UnknownTagType {
+            tag_type_id,
+            position,
+        } => {
+            assert_eq!(*tag_type_id, 47);
+            assert_eq!(position.offset, 14);
+            assert_eq!(position.name.as_deref(), Some("hello world"));
+        }
+        _ => panic!(),
+    }
+
+    assert_eq!(error.kind(), ErrorKind::UnknownTagType);
+    assert_eq!(
+        error.to_string(),
+        "Unknown tag type: 47 at offset 14 in tag hello world"
+    );
+
+    let mut vec = include_bytes!("../test/binary/hello_world.dat").to_vec();
+    vec.truncate(26);
+
+    let mut cursor = Cursor::new(vec);
+    let error = read_compound_tag(&mut cursor).unwrap_err();
+
+    match &error {
+        TagDecodeError::IOError { position, .. } => {
+            assert_eq!(position.offset, 23);
+            assert_eq!(position.name.as_deref(), Some("name"));
+        }
+        _ => panic!(),
+    }
+
+    let error = TagDecodeError::from(io::Error::from(io::ErrorKind::UnexpectedEof));
+    assert_eq!(error.kind(), ErrorKind::Io);
+    assert_eq!(error.position(), Some(&DecodePosition::default()));
+}
+
+#[test]
+fn test_error_path() {
+    use crate::encode::write_compound_tag;
+    use std::io::Cursor;
+
+    let mut section = CompoundTag::new();
+    section.insert_i8("Y", 2);
+    section.insert_str_vec("Palette", vec!["minecraft:air", "minecraft:stone"]);
+
+    let mut level = CompoundTag::new();
+    level.insert_compound_tag_vec("Sections", vec![CompoundTag::new(), section]);
+
+    let mut root_tag = CompoundTag::new();
+    root_tag.insert_compound_tag("Level", level);
+
+    let mut vec = Vec::new();
+    write_compound_tag(&mut vec, &root_tag).unwrap();
+    vec.truncate(vec.len() - 10);
+
+    let mut cursor = Cursor::new(vec);
+    let error = read_compound_tag(&mut cursor).unwrap_err();
+    let position = error.position().unwrap();
+
+    assert_eq!(position.path.to_string(), "Level.Sections[1].Palette[1]");
+    assert_eq!(position.name.as_deref(), Some("Palette"));
+    assert!(error
+        .to_string()
+        .contains("while reading Level.Sections[1].Palette[1]"));
+}
+
+#[test]
+fn test_depth_limit() {
+    use std::io::Cursor;
+
+    fn nested_lists(depth: usize) -> Vec<u8> {
+        // Root compound tag with list "a".
+        let mut vec = vec![10, 0, 0, 9, 0, 1, b'a'];
+
+        for _ in 2..depth {
+            vec.extend_from_slice(&[9, 0, 0, 0, 1]);
+        }
+
+        vec.extend_from_slice(&[0, 0, 0, 0, 0, 0]);
+        vec
+    }
+
+    let options = DecodeOptions {
+        max_depth: 4,
+        ..Default::default()
+    };
+
+    assert!(read_compound_tag_with_options(&mut Cursor::new(nested_lists(4)), &options).is_ok());
+
+    let error =
+        read_compound_tag_with_options(&mut Cursor::new(nested_lists(5)), &options).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::DepthLimitExceeded);
+    assert_eq!(error.position().unwrap().path.to_string(), "a[0][0][0]");
+
+    let error = read_compound_tag_with_options(&mut Cursor::new(nested_lists(100_000)), &options)
+        .unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::DepthLimitExceeded);
+}
+
+#[test]
+fn test_limits() {
+    use std::io::Cursor;
+
+    let vec = include_bytes!("../test/binary/hello_world.dat").to_vec();
+
+    let options = DecodeOptions {
+        max_bytes: Some(vec.len() as u64),
+        max_string_len: Some(11),
+        ..Default::default()
+    };
+    assert!(read_compound_tag_with_options(&mut Cursor::new(vec.clone()), &options).is_ok());
+
+    let options = DecodeOptions {
+        max_bytes: Some(20),
+        ..Default::default()
+    };
+    let error =
+        read_compound_tag_with_options(&mut Cursor::new(vec.clone()), &options).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::SizeLimitExceeded);
+    assert_eq!(error.position().unwrap().offset, 17);
+
+    let options = DecodeOptions {
+        max_string_len: Some(4),
+        ..Default::default()
+    };
+    let error = read_compound_tag_with_options(&mut Cursor::new(vec), &options).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::StringLimitExceeded);
+    assert_eq!(error.position().unwrap().offset, 1);
+
+    // Byte array claiming i32::MAX elements.
+    let vec = vec![127, 255, 255, 255];
+
+    let options = DecodeOptions {
+        max_elements: Some(1024),
+        ..Default::default()
+    };
+    let error = read_tag_with_options(TagType::ByteArray, &mut Cursor::new(vec.clone()), &options)
+        .unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::ElementLimitExceeded);
+
+    let options = DecodeOptions {
+        max_bytes: Some(1024),
+        ..Default::default()
+    };
+    let error =
+        read_tag_with_options(TagType::ByteArray, &mut Cursor::new(vec), &options).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::SizeLimitExceeded);
+    assert_eq!(error.position().unwrap().offset, 4);
+}
+
+#[test]
+fn test_length_exceeds_input() {
+    let vec = include_bytes!("../test/binary/bigtest.dat").to_vec();
+    let vec = {
+        let mut decoded = Vec::new();
+        GzDecoder::new(&vec[..]).read_to_end(&mut decoded).unwrap();
+        decoded
+    };
+
+    let options = DecodeOptions::default();
+    assert!(read_compound_tag_from_cursor(&mut Cursor::new(&vec), &options).is_ok());
+
+    // List of 1000 shorts with only 10 bytes left.
+    let list = vec![
+        10, 0, 0, 9, 0, 1, b'a', 2, 0, 0, 3, 232, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    ];
+    let error = read_compound_tag_from_cursor(&mut Cursor::new(list), &options).unwrap_err();
+
+    match error {
+        TagDecodeError::LengthExceedsInput {
+            length,
+            remaining,
+            position,
+        } => {
+            assert_eq!(length, 2000);
+            assert_eq!(remaining, 10);
+            assert_eq!(position.offset, 8);
+        }
+        _ => panic!(),
+    }
+}
+
+#[test]
+fn test_negative_length() {
+    // Historical empty list of bytes with negative length.
+    let vec = vec![1, 255, 255, 255, 255];
+
+    let tag = read_tag(TagType::List, &mut Cursor::new(vec.clone())).unwrap();
+    assert_eq!(tag, Tag::List(vec![]));
+
+    let options = DecodeOptions {
+        strict: true,
+        ..Default::default()
+    };
+    let error = read_tag_with_options(TagType::List, &mut Cursor::new(vec), &options).unwrap_err();
+
+    match error {
+        TagDecodeError::NegativeLength { length, position } => {
+            assert_eq!(length, -1);
+            assert_eq!(position.offset, 1);
+        }
+        _ => panic!(),
+    }
+}
+
+#[test]
+fn test_malformed_list() {
+    let strict = DecodeOptions {
+        strict: true,
+        ..Default::default()
+    };
+
+    // List of two TAG_End elements.
+    let vec = vec![0, 0, 0, 0, 2];
+
+    let tag = read_tag(TagType::List, &mut Cursor::new(vec.clone())).unwrap();
+    assert_eq!(tag, Tag::List(vec![]));
+
+    let error = read_tag_with_options(TagType::List, &mut Cursor::new(vec), &strict).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::MalformedList);
+
+    // Empty list of unknown type.
+    let vec = vec![47, 0, 0, 0, 0];
+
+    assert!(read_tag(TagType::List, &mut Cursor::new(vec.clone())).is_ok());
+
+    let error = read_tag_with_options(TagType::List, &mut Cursor::new(vec), &strict).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Malformed list: element type 47 with 0 elements at offset 0"
+    );
+
+    // Empty list of TAG_End written by vanilla.
+    let vec = vec![0, 0, 0, 0, 0];
+    assert!(read_tag_with_options(TagType::List, &mut Cursor::new(vec), &strict).is_ok());
+}
+
+#[test]
+fn test_duplicate_keys() {
+    // Root compound tag with bytes a = 1, b = 2, a = 3.
+    let vec = vec![
+        10, 0, 0, 1, 0, 1, b'a', 1, 1, 0, 1, b'b', 2, 1, 0, 1, b'a', 3, 0,
+    ];
+
+    let read = |duplicate_keys| {
+        let options = DecodeOptions {
+            duplicate_keys,
+            ..Default::default()
+        };
+
+        read_compound_tag_with_options(&mut Cursor::new(vec.clone()), &options)
+    };
+
+    let root_tag = read(DuplicateKeys::KeepLast).unwrap();
+    assert_eq!(root_tag.get_i8("a").unwrap(), 3);
+    assert_eq!(root_tag.iter().last().unwrap().0, "a");
+
+    let root_tag = read(DuplicateKeys::KeepFirst).unwrap();
+    assert_eq!(root_tag.get_i8("a").unwrap(), 1);
+    assert_eq!(root_tag.iter().count(), 2);
+
+    match read(DuplicateKeys::Error).unwrap_err() {
+        TagDecodeError::DuplicateKey { name, position } => {
+            assert_eq!(name, "a");
+            assert_eq!(position.offset, 13);
+        }
+        _ => panic!(),
+    }
+}
+
+#[test]
+fn test_unknown_tag() {
+    use crate::encode::write_compound_tag;
+
+    fn read_unknown(_: u8, reader: &mut dyn Read) -> io::Result<Vec<u8>> {
+        let mut bytes = vec![0; 3];
+        reader.read_exact(&mut bytes)?;
+
+        Ok(bytes)
+    }
+
+    // Root compound tag with list of two tags of type 42 followed by a byte.
+    let vec = vec![
+        10, 0, 0, 9, 0, 1, b'l', 42, 0, 0, 0, 2, 1, 2, 3, 4, 5, 6, 1, 0, 1, b'b', 7, 0,
+    ];
+
+    let error = read_compound_tag(&mut Cursor::new(vec.clone())).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::UnknownTagType);
+
+    let options = DecodeOptions {
+        unknown_tag_reader: Some(read_unknown),
+        ..Default::default()
+    };
+
+    let root_tag = read_compound_tag_with_options(&mut Cursor::new(vec.clone()), &options).unwrap();
+    let list: &Tag = root_tag.get("l").unwrap();
+
+    assert_eq!(list[1].tag_type(), TagType::Unknown(42));
+    assert_eq!(
+        list[1],
+        Tag::Unknown {
+            id: 42,
+            bytes: vec![4, 5, 6]
+        }
+    );
+    assert_eq!(root_tag.get_i8("b").unwrap(), 7);
+
+    let mut encoded = Vec::new();
+    write_compound_tag(&mut encoded, &root_tag).unwrap();
+    assert_eq!(encoded, vec);
+
+    fn read_to_end(_: u8, reader: &mut dyn Read) -> io::Result<Vec<u8>> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+
+        Ok(bytes)
+    }
+
+    // Reader consuming the rest of the input stops at the size limit.
+    let mut vec = vec![10, 0, 0, 42, 0, 1, b'u'];
+    vec.resize(1024 * 1024, 0);
+
+    let options = DecodeOptions {
+        unknown_tag_reader: Some(read_to_end),
+        max_bytes: Some(64),
+        ..Default::default()
+    };
+    let mut cursor = Cursor::new(vec);
+    let error = read_compound_tag_with_options(&mut cursor, &options).unwrap_err();
+
+    assert_eq!(error.kind(), ErrorKind::SizeLimitExceeded);
+    assert_eq!(cursor.position(), 65);
+}
+
+#[test]
+fn test_root_name() {
+    let read = |root_name| {
+        let options = DecodeOptions {
+            root_name,
+            ..Default::default()
+        };
+        let vec = include_bytes!("../test/binary/hello_world.dat").to_vec();
+
+        read_compound_tag_with_options(&mut Cursor::new(vec), &options)
+    };
+
+    let root_tag = read(RootName::Keep).unwrap();
+    assert_eq!(root_tag.name.as_deref(), Some("hello world"));
+
+    let root_tag = read(RootName::Skip).unwrap();
+    assert_eq!(root_tag.name, None);
+    assert_eq!(root_tag.get_str("name").unwrap(), "Bananrama");
+
+    let error = read(RootName::RequireEmpty).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::RootNameNotEmpty);
+    assert_eq!(error.position().unwrap().offset, 1);
+
+    let options = DecodeOptions {
+        root_name: RootName::RequireEmpty,
+        ..Default::default()
+    };
+    let vec = include_bytes!("../test/binary/servers.dat").to_vec();
+    assert!(read_compound_tag_with_options(&mut Cursor::new(vec), &options).is_ok());
+}
+
+#[test]
+fn test_round_trip() {
+    use crate::encode::write_compound_tag;
+    use flate2::read::GzDecoder;
+
+    let options = DecodeOptions::round_trip();
+
+    let mut input = Vec::new();
+    GzDecoder::new(&include_bytes!("../test/binary/bigtest.dat")[..])
+        .read_to_end(&mut input)
+        .unwrap();
+
+    let root_tag = read_compound_tag_with_options(&mut Cursor::new(&input), &options).unwrap();
+    let mut vec = Vec::new();
+    write_compound_tag(&mut vec, &root_tag).unwrap();
+
+    assert_eq!(vec, input);
+
+    // Empty list of TAG_Int would be written as empty list of TAG_End.
+    let vec = vec![10, 0, 0, 9, 0, 1, b'a', 3, 0, 0, 0, 0, 0];
+    assert!(read_compound_tag(&mut Cursor::new(&vec)).is_ok());
+
+    let error = read_compound_tag_with_options(&mut Cursor::new(&vec), &options).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::MalformedList);
+    assert_eq!(error.position().unwrap().offset, 7);
+
+    // Modified UTF-8 of U+1F600 as encoded by Java.
+    let vec = vec![
+        10, 0, 0, 8, 0, 1, b'a', 0, 6, 0xed, 0xa0, 0xbd, 0xed, 0xb8, 0x80, 0,
+    ];
+    assert!(read_compound_tag(&mut Cursor::new(&vec)).is_ok());
+
+    let error = read_compound_tag_with_options(&mut Cursor::new(&vec), &options).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::InvalidString);
+    assert_eq!(error.position().unwrap().offset, 7);
+    assert_eq!(error.position().unwrap().path.to_string(), "a");
+
+    let error = validate(&mut Cursor::new(&vec), &options).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::InvalidString);
+}
+
+#[test]
+fn test_read_optional_compound_tag() {
+    let mut cursor = Cursor::new(vec![0, 1]);
+    assert!(read_optional_compound_tag(&mut cursor).unwrap().is_none());
+    assert_eq!(cursor.position(), 1);
+
+    let error = read_optional_compound_tag(&mut Cursor::new(vec![])).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::Io);
+
+    let error = read_optional_compound_tag(&mut Cursor::new(vec![8, 0, 0, 0, 0])).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::RootMustBeCompoundTag);
+
+    let error = read_compound_tag(&mut Cursor::new(vec![0])).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::UnknownTagType);
+}
+
+#[test]
+fn test_read_compound_tag_from_slice() {
+    let vec = include_bytes!("../test/binary/servers.dat").to_vec();
+
+    let (root_tag, consumed) = read_compound_tag_from_slice(&vec).unwrap();
+    assert_eq!(consumed, vec.len());
+    assert_eq!(root_tag.get_compound_tag_vec("servers").unwrap().len(), 1);
+
+    let error = read_compound_tag_from_slice(&vec[..vec.len() - 1]).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::Io);
+}
+
+#[test]
+#[cfg(feature = "bytes")]
+fn test_read_compound_tag_buf() {
+    use bytes::Buf;
+
+    let tag = include_bytes!("../test/binary/servers.dat");
+    let frame = [0xca, 0xfe];
+
+    // Tag split across chunks of a chained buffer.
+    let (first, second) = tag.split_at(tag.len() / 2);
+    let mut buf = Buf::chain(Buf::chain(first, second), &frame[..]);
+
+    let root_tag = read_compound_tag_buf(&mut buf).unwrap();
+    assert_eq!(root_tag.get_compound_tag_vec("servers").unwrap().len(), 1);
+    assert_eq!(buf.remaining(), frame.len());
+
+    let mut buf = bytes::Bytes::from_static(&[10, 0, 0, 7, 0, 1, b'a', 127, 255, 255, 255]);
+
+    match read_compound_tag_buf(&mut buf) {
+        Err(TagDecodeError::LengthExceedsInput { remaining, .. }) => assert_eq!(remaining, 0),
+        _ => panic!(),
+    }
+}
+
+#[test]
+fn test_read_compound_tag_counted() {
+    use std::io::Read;
+
+    let tag = include_bytes!("../test/binary/servers.dat");
+    let frame = [0xca, 0xfe];
+    let mut reader = (&tag[..]).chain(&frame[..]).chain(&tag[..]);
+
+    let options = DecodeOptions::default();
+    let (_, consumed) = read_compound_tag_counted(&mut reader, &options).unwrap();
+    assert_eq!(consumed, tag.len() as u64);
+
+    let mut buf = [0; 2];
+    reader.read_exact(&mut buf).unwrap();
+    assert_eq!(buf, frame);
+
+    let (root_tag, _) = read_compound_tag_counted(&mut reader, &options).unwrap();
+    assert_eq!(root_tag.get_compound_tag_vec("servers").unwrap().len(), 1);
+}
+
+#[test]
+fn test_read_compound_tag_fully() {
+    use std::io::Cursor;
+
+    let vec = include_bytes!("../test/binary/hello_world.dat").to_vec();
+    let length = vec.len() as u64;
+    assert!(read_compound_tag_fully(&mut Cursor::new(vec.clone())).is_ok());
+
+    let mut concatenated = vec.clone();
+    concatenated.extend_from_slice(&vec);
+
+    let error = read_compound_tag_fully(&mut Cursor::new(&concatenated)).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::TrailingData);
+
+    match error {
+        TagDecodeError::TrailingData {
+            remaining,
+            position,
+        } => {
+            assert_eq!(remaining, length);
+            assert_eq!(position.offset, length);
+        }
+        _ => panic!(),
+    }
+
+    let read = |max_bytes| {
+        let options = DecodeOptions {
+            max_bytes: Some(max_bytes),
+            ..Default::default()
+        };
+        let mut cursor = Cursor::new(&concatenated);
+        let result = read_compound_tag_fully_with_options(&mut cursor, &options);
+
+        (result, cursor.position())
+    };
+
+    let (result, _) = read(2 * length);
+    assert_eq!(result.unwrap_err().kind(), ErrorKind::TrailingData);
+
+    // Trailing data is read only up to the limit.
+    let (result, position) = read(length + 1);
+    assert_eq!(result.unwrap_err().kind(), ErrorKind::SizeLimitExceeded);
+    assert_eq!(position, length + 2);
+
+    let options = DecodeOptions {
+        max_bytes: Some(length),
+        strict: true,
+        ..Default::default()
+    };
+    assert!(read_compound_tag_fully_with_options(&mut Cursor::new(vec), &options).is_ok());
+}
+
+#[test]
+#[cfg(feature = "miette")]
+fn test_diagnostic() {
+    use miette::Diagnostic;
+    use std::io::Cursor;
+
+    let mut vec = include_bytes!("../test/binary/hello_world.dat").to_vec();
+    vec[14] = 47;
+
+    let error = read_compound_tag(&mut Cursor::new(vec)).unwrap_err();
+    let labels: Vec<_> = error.labels().unwrap().collect();
+
+    assert_eq!(
+        error.code().unwrap().to_string(),
+        "nbt::decode::unknown_tag_type"
+    );
+    assert_eq!(labels.len(), 1);
+    assert_eq!(labels[0].offset(), 14);
+    assert_eq!(labels[0].len(), 1);
 }
 
 #[test]
@@ -253,10 +2330,10 @@ fn test_big_test_read() {
     let root_tag = read_gzip_compound_tag(&mut cursor).unwrap();
 
     assert_eq!(root_tag.name.as_ref().unwrap(), "Level");
-    assert_eq!(root_tag.get_i8("byteTest").unwrap(), i8::max_value());
-    assert_eq!(root_tag.get_i16("shortTest").unwrap(), i16::max_value());
-    assert_eq!(root_tag.get_i32("intTest").unwrap(), i32::max_value());
-    assert_eq!(root_tag.get_i64("longTest").unwrap(), i64::max_value());
+    assert_eq!(root_tag.get_i8("byteTest").unwrap(), i8::MAX);
+    assert_eq!(root_tag.get_i16("shortTest").unwrap(), i16::MAX);
+    assert_eq!(root_tag.get_i32("intTest").unwrap(), i32::MAX);
+    assert_eq!(root_tag.get_i64("longTest").unwrap(), i64::MAX);
     assert_eq!(root_tag.get_f32("floatTest").unwrap(), 0.4982314705848694);
     assert_eq!(root_tag.get_f64("doubleTest").unwrap(), 0.4931287132182315);
     assert_eq!(
@@ -0,0 +1,470 @@
+//! Decoding into tags allocated in an arena, which are freed all at once.
+use super::{DecodeOptions, DuplicateKeys, Result, TagDecodeError, TagDecoder};
+use crate::nbtpath::PathSegment;
+use crate::{CompoundTag, Tag, TagType};
+use bumpalo::collections::Vec as ArenaVec;
+use byteorder::{BigEndian, ByteOrder};
+use std::collections::hash_map::RandomState;
+use std::hash::BuildHasher;
+use std::io::Read;
+
+/// Arena which holds decoded tags until it is dropped or reset.
+pub type Arena = bumpalo::Bump;
+
+/// Tag borrowing its payload from an [`Arena`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ArenaTag<'a> {
+    Byte(i8),
+    Short(i16),
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    ByteArray(&'a [i8]),
+    String(&'a str),
+    List(&'a [ArenaTag<'a>]),
+    Compound(ArenaCompoundTag<'a>),
+    IntArray(&'a [i32]),
+    LongArray(&'a [i64]),
+    Unknown { id: u8, bytes: &'a [u8] },
+}
+
+impl<'a> ArenaTag<'a> {
+    pub fn tag_type(&self) -> TagType {
+        match self {
+            ArenaTag::Byte(_) => TagType::Byte,
+            ArenaTag::Short(_) => TagType::Short,
+            ArenaTag::Int(_) => TagType::Int,
+            ArenaTag::Long(_) => TagType::Long,
+            ArenaTag::Float(_) => TagType::Float,
+            ArenaTag::Double(_) => TagType::Double,
+            ArenaTag::ByteArray(_) => TagType::ByteArray,
+            ArenaTag::String(_) => TagType::String,
+            ArenaTag::List(_) => TagType::List,
+            ArenaTag::Compound(_) => TagType::Compound,
+            ArenaTag::IntArray(_) => TagType::IntArray,
+            ArenaTag::LongArray(_) => TagType::LongArray,
+            ArenaTag::Unknown { id, .. } => TagType::Unknown(*id),
+        }
+    }
+
+    /// Copies tag out of the arena.
+    pub fn to_tag(&self) -> Tag {
+        match *self {
+            ArenaTag::Byte(value) => Tag::Byte(value),
+            ArenaTag::Short(value) => Tag::Short(value),
+            ArenaTag::Int(value) => Tag::Int(value),
+            ArenaTag::Long(value) => Tag::Long(value),
+            ArenaTag::Float(value) => Tag::Float(value),
+            ArenaTag::Double(value) => Tag::Double(value),
+            ArenaTag::ByteArray(value) => Tag::ByteArray(value.to_vec()),
+            ArenaTag::String(value) => Tag::String(value.to_string()),
+            ArenaTag::List(value) => Tag::List(value.iter().map(ArenaTag::to_tag).collect()),
+            ArenaTag::Compound(value) => Tag::Compound(value.to_compound_tag()),
+            ArenaTag::IntArray(value) => Tag::IntArray(value.to_vec()),
+            ArenaTag::LongArray(value) => Tag::LongArray(value.to_vec()),
+            ArenaTag::Unknown { id, bytes } => Tag::Unknown {
+                id,
+                bytes: bytes.to_vec(),
+            },
+        }
+    }
+}
+
+/// Compound tag borrowing its tags from an [`Arena`].
+///
+/// Tags are kept in order and looked up by linear search.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ArenaCompoundTag<'a> {
+    /// Name of the root tag, `None` for nested compound tags.
+    pub name: Option<&'a str>,
+    tags: &'a [(&'a str, ArenaTag<'a>)],
+}
+
+impl<'a> ArenaCompoundTag<'a> {
+    pub fn get(&self, name: &str) -> Option<&'a ArenaTag<'a>> {
+        self.tags
+            .iter()
+            .find(|(tag_name, _)| *tag_name == name)
+            .map(|(_, tag)| tag)
+    }
+
+    pub fn len(&self) -> usize {
+        self.tags.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tags.is_empty()
+    }
+
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = (&'a str, &'a ArenaTag<'a>)> {
+        self.tags.iter().map(|(name, tag)| (*name, tag))
+    }
+
+    /// Copies compound tag out of the arena.
+    pub fn to_compound_tag(&self) -> CompoundTag {
+        let mut compound_tag: CompoundTag = self
+            .iter()
+            .map(|(name, tag)| (name, tag.to_tag()))
+            .collect();
+        compound_tag.name = self.name.map(str::to_string);
+
+        compound_tag
+    }
+}
+
+/// Read a compound tag from a reader into an arena.
+///
+/// # Example
+/// ```
+/// use nbt::decode::{read_compound_tag_in, Arena, ArenaTag};
+/// use std::io::Cursor;
+///
+/// let arena = Arena::new();
+///
+/// let mut cursor = Cursor::new(include_bytes!("../../test/binary/hello_world.dat").to_vec());
+/// let root_tag = read_compound_tag_in(&mut cursor, &arena).unwrap();
+///
+/// assert_eq!(root_tag.name, Some("hello world"));
+/// assert_eq!(root_tag.get("name"), Some(&ArenaTag::String("Bananrama")));
+/// ```
+pub fn read_compound_tag_in<'a, R: Read>(
+    reader: &mut R,
+    arena: &'a Arena,
+) -> Result<ArenaCompoundTag<'a>> {
+    read_compound_tag_in_with_options(reader, arena, &DecodeOptions::default())
+}
+
+/// Read a compound tag from a reader into an arena using provided options.
+///
+/// [`DecodeOptions::interner`] is ignored, names are stored in the arena.
+pub fn read_compound_tag_in_with_options<'a, R: Read>(
+    reader: &mut R,
+    arena: &'a Arena,
+    options: &DecodeOptions,
+) -> Result<ArenaCompoundTag<'a>> {
+    TagDecoder::new(reader, options).read_root_compound_tag_in(arena)
+}
+
+impl<'r, 'o, R: Read> TagDecoder<'r, 'o, R> {
+    fn read_root_compound_tag_in<'a>(&mut self, arena: &'a Arena) -> Result<ArenaCompoundTag<'a>> {
+        let tag_id = self.read_tag_id()?;
+
+        // Error contains the actual tag, so it is read as usual.
+        if tag_id != TagType::Compound {
+            return self
+                .read_root_compound_payload(tag_id)
+                .map(|_| unreachable!("Only compound tag can be read"));
+        }
+
+        self.root_name = self.read_root_name()?;
+        let name = self
+            .root_name
+            .as_deref()
+            .map(|name| &*arena.alloc_str(name));
+        let result = self.read_compound_in(arena, name);
+        self.root_name = None;
+
+        result
+    }
+
+    fn read_payload_in<'a>(&mut self, arena: &'a Arena, tag_type: TagType) -> Result<ArenaTag<'a>> {
+        match tag_type {
+            TagType::Byte => Ok(ArenaTag::Byte(self.read_i8()?)),
+            TagType::Short => Ok(ArenaTag::Short(self.read_i16()?)),
+            TagType::Int => Ok(ArenaTag::Int(self.read_i32()?)),
+            TagType::Long => Ok(ArenaTag::Long(self.read_i64()?)),
+            TagType::Float => Ok(ArenaTag::Float(self.read_f32()?)),
+            TagType::Double => Ok(ArenaTag::Double(self.read_f64()?)),
+            TagType::ByteArray => {
+                let length = self.read_length(1)?;
+                self.read_scratch(u64::from(length))?;
+                let value = arena.alloc_slice_fill_iter(self.scratch.iter().map(|v| *v as i8));
+
+                Ok(ArenaTag::ByteArray(value))
+            }
+            TagType::String => {
+                let value = self.read_str()?;
+
+                Ok(ArenaTag::String(arena.alloc_str(&value)))
+            }
+            TagType::List => self.read_list_in(arena),
+            TagType::Compound => self.read_compound_in(arena, None).map(ArenaTag::Compound),
+            TagType::IntArray => {
+                let length = self.read_length(4)?;
+                self.read_scratch(u64::from(length) * 4)?;
+                let value = arena.alloc_slice_fill_default(length as usize);
+                BigEndian::read_i32_into(&self.scratch, value);
+
+                Ok(ArenaTag::IntArray(value))
+            }
+            TagType::LongArray => {
+                let length = self.read_length(8)?;
+                self.read_scratch(u64::from(length) * 8)?;
+                let value = arena.alloc_slice_fill_default(length as usize);
+                BigEndian::read_i64_into(&self.scratch, value);
+
+                Ok(ArenaTag::LongArray(value))
+            }
+            TagType::Unknown(id) => match self.read_payload(tag_type, false)? {
+                Tag::Unknown { bytes, .. } => Ok(ArenaTag::Unknown {
+                    id,
+                    bytes: arena.alloc_slice_copy(&bytes),
+                }),
+                _ => unreachable!("Unknown tag type must be read as unknown tag"),
+            },
+        }
+    }
+
+    fn read_list_in<'a>(&mut self, arena: &'a Arena) -> Result<ArenaTag<'a>> {
+        self.enter()?;
+
+        let (list_tags_type, length) = self.read_list_header()?;
+        let max_capacity = self.options.max_preallocation / std::mem::size_of::<ArenaTag>();
+        let mut value = ArenaVec::new_in(arena);
+
+        if let Some(list_tags_type) = list_tags_type {
+            value.reserve((length as usize).min(max_capacity));
+
+            for index in 0..length {
+                self.path.push(PathSegment::Index(index as usize));
+                value.push(self.read_payload_in(arena, list_tags_type)?);
+                self.path.pop();
+            }
+        }
+
+        self.depth -= 1;
+
+        Ok(ArenaTag::List(value.into_bump_slice()))
+    }
+
+    fn read_compound_in<'a>(
+        &mut self,
+        arena: &'a Arena,
+        name: Option<&'a str>,
+    ) -> Result<ArenaCompoundTag<'a>> {
+        self.enter()?;
+
+        let mut tags: ArenaVec<(&'a str, ArenaTag<'a>)> = ArenaVec::new_in(arena);
+        // Index of the kept occurrence of each name.
+        let mut indices = NameIndex::new(arena);
+        // Indices of occurrences replaced by later ones, removed at the end.
+        let mut replaced = ArenaVec::new_in(arena);
+        // Path holds owned names, so their buffers are reused between compound tags.
+        let mut key = self.key_buffers.pop().unwrap_or_default();
+
+        loop {
+            let tag_offset = self.offset;
+            let tag_id = self.read_u8()?;
+
+            // Compound tag end reached.
+            if tag_id == 0 {
+                break;
+            }
+
+            let tag_type = self.tag_type(tag_id, tag_offset)?;
+            key.clear();
+            key.push_str(&self.read_str()?);
+            let duplicate = indices.get(&tags, &key);
+
+            if duplicate.is_some() && self.options.duplicate_keys == DuplicateKeys::Error {
+                return Err(TagDecodeError::DuplicateKey {
+                    name: key,
+                    position: self.position_at(tag_offset),
+                });
+            }
+
+            self.path.push(PathSegment::Key(key));
+            let tag = self.read_payload_in(arena, tag_type)?;
+            key = match self.path.pop() {
+                Some(PathSegment::Key(key)) => key,
+                _ => unreachable!("Path must end with key pushed above"),
+            };
+
+            // Same as compound tag, last occurrence is moved to the end.
+            match duplicate {
+                Some(index) if self.options.duplicate_keys == DuplicateKeys::KeepLast => {
+                    let name = tags[index].0;
+                    replaced.push(index);
+                    tags.push((name, tag));
+                    indices.insert(&tags, name, tags.len() - 1);
+                }
+                Some(_) => {}
+                None => {
+                    let name = &*arena.alloc_str(&key);
+                    tags.push((name, tag));
+                    indices.insert(&tags, name, tags.len() - 1);
+                }
+            }
+        }
+
+        self.key_buffers.push(key);
+
+        if !replaced.is_empty() {
+            replaced.sort_unstable();
+
+            let mut index = 0;
+            tags.retain(|_| {
+                let keep = replaced.binary_search(&index).is_err();
+                index += 1;
+                keep
+            });
+        }
+
+        self.depth -= 1;
+
+        Ok(ArenaCompoundTag {
+            name,
+            tags: tags.into_bump_slice(),
+        })
+    }
+}
+
+/// Hash table of tag indices by their names, allocated in the arena.
+///
+/// Names are not stored, slots point to tags which hold them.
+struct NameIndex<'a> {
+    arena: &'a Arena,
+    hasher: RandomState,
+    /// Tag indices or `EMPTY`, length is zero or a power of two.
+    slots: &'a mut [usize],
+    len: usize,
+}
+
+const EMPTY: usize = usize::MAX;
+
+impl<'a> NameIndex<'a> {
+    fn new(arena: &'a Arena) -> Self {
+        NameIndex {
+            arena,
+            hasher: RandomState::new(),
+            slots: &mut [],
+            len: 0,
+        }
+    }
+
+    /// Returns slot holding tag with provided name or an empty slot where it belongs.
+    fn find(&self, tags: &[(&str, ArenaTag)], name: &str) -> usize {
+        let mask = self.slots.len() - 1;
+        let mut slot = self.hasher.hash_one(name) as usize & mask;
+
+        loop {
+            match self.slots[slot] {
+                EMPTY => return slot,
+                index if tags[index].0 == name => return slot,
+                _ => slot = (slot + 1) & mask,
+            }
+        }
+    }
+
+    fn get(&self, tags: &[(&str, ArenaTag)], name: &str) -> Option<usize> {
+        if self.slots.is_empty() {
+            return None;
+        }
+
+        match self.slots[self.find(tags, name)] {
+            EMPTY => None,
+            index => Some(index),
+        }
+    }
+
+    /// Points name to tag with provided index, which replaces previous one.
+    fn insert(&mut self, tags: &[(&str, ArenaTag)], name: &str, index: usize) {
+        // Table is kept at most half full.
+        if (self.len + 1) * 2 > self.slots.len() {
+            let length = (self.slots.len() * 2).max(8);
+            let slots = std::mem::replace(
+                &mut self.slots,
+                self.arena.alloc_slice_fill_copy(length, EMPTY),
+            );
+
+            for &index in slots.iter().filter(|index| **index != EMPTY) {
+                let slot = self.find(tags, tags[index].0);
+                self.slots[slot] = index;
+            }
+        }
+
+        let slot = self.find(tags, name);
+
+        if self.slots[slot] == EMPTY {
+            self.len += 1;
+        }
+
+        self.slots[slot] = index;
+    }
+}
+
+#[test]
+fn test_read_compound_tag_in() {
+    use crate::decode::{read_compound_tag, read_gzip_compound_tag};
+    use crate::encode::write_compound_tag;
+    use crate::ErrorKind;
+    use std::io::Cursor;
+
+    let mut cursor = Cursor::new(include_bytes!("../../test/binary/bigtest.dat").to_vec());
+    let root_tag = read_gzip_compound_tag(&mut cursor).unwrap();
+
+    let mut vec = Vec::new();
+    write_compound_tag(&mut vec, &root_tag).unwrap();
+
+    let arena = Arena::new();
+    let arena_tag = read_compound_tag_in(&mut Cursor::new(&vec), &arena).unwrap();
+
+    assert_eq!(arena_tag.to_compound_tag(), root_tag);
+    assert_eq!(arena_tag.name, Some("Level"));
+
+    vec.truncate(vec.len() - 100);
+
+    let error = read_compound_tag_in(&mut Cursor::new(&vec), &arena).unwrap_err();
+    let expected = read_compound_tag(&mut Cursor::new(&vec)).unwrap_err();
+
+    assert_eq!(error.kind(), ErrorKind::Io);
+    assert_eq!(error.position(), expected.position());
+
+    // Compound tag with "a" twice.
+    let vec = vec![
+        10, 0, 0, 1, 0, 1, b'a', 1, 1, 0, 1, b'b', 2, 1, 0, 1, b'a', 3, 0,
+    ];
+    let arena_tag = read_compound_tag_in(&mut Cursor::new(&vec), &arena).unwrap();
+    let names: Vec<_> = arena_tag.iter().map(|(name, _)| name).collect();
+
+    assert_eq!(names, vec!["b", "a"]);
+    assert_eq!(arena_tag.get("a"), Some(&ArenaTag::Byte(3)));
+    assert_eq!(
+        arena_tag.to_compound_tag(),
+        read_compound_tag(&mut Cursor::new(&vec)).unwrap()
+    );
+
+    // Replaced occurrence is replaced again.
+    let vec = vec![
+        10, 0, 0, 1, 0, 1, b'a', 1, 1, 0, 1, b'a', 2, 1, 0, 1, b'b', 3, 1, 0, 1, b'a', 4, 0,
+    ];
+    let arena_tag = read_compound_tag_in(&mut Cursor::new(&vec), &arena).unwrap();
+
+    assert_eq!(
+        arena_tag.to_compound_tag(),
+        read_compound_tag(&mut Cursor::new(&vec)).unwrap()
+    );
+    assert_eq!(arena_tag.len(), 2);
+
+    // Enough names to grow the index, each of them twice.
+    let mut compound_tag = CompoundTag::new();
+    let mut vec = Vec::new();
+    write_compound_tag(&mut vec, &compound_tag).unwrap();
+    vec.pop();
+
+    for value in 0..100i8 {
+        let name = format!("{}", value % 50);
+        vec.extend_from_slice(&[1, 0, name.len() as u8]);
+        vec.extend_from_slice(name.as_bytes());
+        vec.push(value as u8);
+        compound_tag.insert_i8(name, value);
+    }
+
+    vec.push(0);
+
+    let arena_tag = read_compound_tag_in(&mut Cursor::new(&vec), &arena).unwrap();
+    assert_eq!(arena_tag.len(), 50);
+    assert_eq!(arena_tag.get("7"), Some(&ArenaTag::Byte(57)));
+    assert_eq!(arena_tag.to_compound_tag(), compound_tag);
+}
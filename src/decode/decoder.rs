@@ -0,0 +1,115 @@
+//! Decoder which is reused for many tags.
+use super::{DecodeOptions, Result, TagDecoder};
+use crate::CompoundTag;
+use std::io::Read;
+
+/// Decoder which keeps its scratch buffer for strings and arrays between calls,
+/// so decoding many small tags doesn't allocate temporary buffers every time.
+///
+/// Buffer is kept up to [`DecodeOptions::max_preallocation`] bytes.
+///
+/// # Example
+/// ```
+/// use nbt::decode::{DecodeOptions, Decoder};
+/// use nbt::CompoundTag;
+/// use std::io::Cursor;
+///
+/// let mut decoder = Decoder::new(DecodeOptions::default());
+/// let mut compound_tag = CompoundTag::new();
+///
+/// for _ in 0..2 {
+///     let mut cursor = Cursor::new(include_bytes!("../../test/binary/servers.dat").to_vec());
+///     decoder.decode_into(&mut cursor, &mut compound_tag).unwrap();
+///
+///     assert_eq!(compound_tag.get_compound_tag_vec("servers").unwrap().len(), 1);
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Decoder {
+    options: DecodeOptions,
+    scratch: Vec<u8>,
+}
+
+impl Decoder {
+    pub fn new(options: DecodeOptions) -> Self {
+        Decoder {
+            options,
+            scratch: Vec::new(),
+        }
+    }
+
+    pub fn options(&self) -> &DecodeOptions {
+        &self.options
+    }
+
+    /// Read a compound tag from a reader.
+    pub fn decode<R: Read>(&mut self, reader: &mut R) -> Result<CompoundTag> {
+        let mut decoder = TagDecoder::new(reader, &self.options);
+        decoder.scratch = std::mem::take(&mut self.scratch);
+
+        let result = decoder.read_root_compound_tag();
+        let mut scratch = decoder.scratch;
+
+        if scratch.capacity() > self.options.max_preallocation {
+            scratch = Vec::new();
+        }
+
+        self.scratch = scratch;
+
+        result
+    }
+
+    /// Read a compound tag from a reader, replacing contents of provided one.
+    ///
+    /// Provided compound tag is left unchanged on error.
+    pub fn decode_into<R: Read>(
+        &mut self,
+        reader: &mut R,
+        compound_tag: &mut CompoundTag,
+    ) -> Result<()> {
+        *compound_tag = self.decode(reader)?;
+
+        Ok(())
+    }
+}
+
+#[test]
+fn test_decoder() {
+    use crate::decode::{read_compound_tag, read_gzip_compound_tag};
+    use crate::encode::write_compound_tag;
+    use crate::ErrorKind;
+    use std::io::Cursor;
+
+    let mut cursor = Cursor::new(include_bytes!("../../test/binary/bigtest.dat").to_vec());
+    let root_tag = read_gzip_compound_tag(&mut cursor).unwrap();
+
+    let mut vec = Vec::new();
+    write_compound_tag(&mut vec, &root_tag).unwrap();
+
+    let mut decoder = Decoder::default();
+
+    for _ in 0..2 {
+        let decoded = decoder.decode(&mut Cursor::new(&vec)).unwrap();
+        assert_eq!(decoded, read_compound_tag(&mut Cursor::new(&vec)).unwrap());
+    }
+
+    assert!(decoder.scratch.capacity() > 0);
+
+    let mut compound_tag = root_tag.clone();
+    let error = decoder
+        .decode_into(&mut Cursor::new(&vec[..vec.len() - 1]), &mut compound_tag)
+        .unwrap_err();
+
+    assert_eq!(error.kind(), ErrorKind::Io);
+    assert_eq!(compound_tag, root_tag);
+
+    let options = DecodeOptions {
+        max_preallocation: 16,
+        ..Default::default()
+    };
+
+    let mut decoder = Decoder::new(options);
+    decoder.decode(&mut Cursor::new(&vec)).unwrap();
+
+    assert!(decoder.scratch.capacity() <= 16);
+}
@@ -0,0 +1,81 @@
+//! Sharing of repeated tag names between decoded compound tags.
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+
+/// Set of tag names shared by compound tags decoded with it.
+///
+/// Each distinct name is allocated once and kept until all clones of the interner and all tags
+/// using it are dropped. Clones share the same set, so interner can be reused across files
+/// and threads.
+///
+/// # Example
+/// ```
+/// use nbt::decode::{read_compound_tag_with_options, DecodeOptions, Interner};
+/// use std::io::Cursor;
+///
+/// let interner = Interner::new();
+/// let options = DecodeOptions::default().interner(&interner);
+///
+/// for _ in 0..2 {
+///     let mut cursor = Cursor::new(include_bytes!("../../test/binary/servers.dat").to_vec());
+///     read_compound_tag_with_options(&mut cursor, &options).unwrap();
+/// }
+///
+/// // "servers", "ip", "name" and "hideAddress".
+/// assert_eq!(interner.len(), 4);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Interner {
+    names: Arc<Mutex<HashSet<Arc<str>>>>,
+}
+
+impl Interner {
+    pub fn new() -> Self {
+        Interner::default()
+    }
+
+    /// Returns shared copy of provided name, allocating it on first use.
+    pub fn intern(&self, name: &str) -> Arc<str> {
+        let mut names = self.names.lock().unwrap_or_else(|e| e.into_inner());
+
+        match names.get(name) {
+            Some(name) => name.clone(),
+            None => {
+                let name: Arc<str> = Arc::from(name);
+                names.insert(name.clone());
+
+                name
+            }
+        }
+    }
+
+    /// Number of distinct names.
+    pub fn len(&self) -> usize {
+        self.names.lock().unwrap_or_else(|e| e.into_inner()).len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[test]
+fn test_interner() {
+    use crate::decode::{read_compound_tag, read_compound_tag_with_options, DecodeOptions};
+    use std::io::Cursor;
+
+    let interner = Interner::new();
+    let a = interner.intern("id");
+    let b = interner.clone().intern("id");
+
+    assert!(Arc::ptr_eq(&a, &b));
+    assert_eq!(interner.len(), 1);
+
+    let vec = include_bytes!("../../test/binary/servers.dat").to_vec();
+    let options = DecodeOptions::default().interner(&interner);
+    let root_tag = read_compound_tag_with_options(&mut Cursor::new(&vec), &options).unwrap();
+
+    assert_eq!(root_tag, read_compound_tag(&mut Cursor::new(&vec)).unwrap());
+    assert!(Arc::ptr_eq(&a, &interner.intern("id")));
+    assert_eq!(interner.len(), 5);
+}
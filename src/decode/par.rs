@@ -0,0 +1,145 @@
+//! Decoding elements of long lists in parallel.
+use super::{DecodeOptions, NbtStats, Result, TagDecoder};
+use crate::nbtpath::PathSegment;
+use crate::{CompoundTag, Tag, TagType};
+use rayon::prelude::*;
+use std::io::{Cursor, Read};
+
+/// Read a compound tag from bytes in memory, decoding elements of lists longer than
+/// [`DecodeOptions::min_parallel_list_len`] on the rayon thread pool, and return it
+/// together with number of bytes consumed.
+///
+/// Bounds of list elements are found by skipping over them first, so each element
+/// is decoded separately and results are stitched in order.
+///
+/// # Example
+/// ```
+/// use nbt::decode::{read_compound_tag_from_slice_par, DecodeOptions};
+/// use nbt::encode::write_compound_tag;
+/// use nbt::CompoundTag;
+///
+/// let players: Vec<_> = (0..1000)
+///     .map(|i| {
+///         let mut player = CompoundTag::new();
+///         player.insert_i32("id", i);
+///         player
+///     })
+///     .collect();
+///
+/// let mut root_tag = CompoundTag::new();
+/// root_tag.insert_compound_tag_vec("players", players);
+///
+/// let mut vec = Vec::new();
+/// write_compound_tag(&mut vec, &root_tag).unwrap();
+///
+/// let (decoded, _) = read_compound_tag_from_slice_par(&vec, &DecodeOptions::default()).unwrap();
+/// assert_eq!(decoded, root_tag);
+/// ```
+pub fn read_compound_tag_from_slice_par(
+    slice: &[u8],
+    options: &DecodeOptions,
+) -> Result<(CompoundTag, usize)> {
+    let mut cursor = Cursor::new(slice);
+
+    let mut decoder = TagDecoder::new(&mut cursor, options);
+    decoder.input_len = Some(slice.len() as u64);
+    decoder.input = Some(slice);
+
+    let compound_tag = decoder.read_root_compound_tag()?;
+    let length = decoder.offset as usize;
+
+    Ok((compound_tag, length))
+}
+
+impl<'r, 'o, R: Read> TagDecoder<'r, 'o, R> {
+    /// Whether elements of a list have to be decoded in parallel.
+    ///
+    /// Only lists and compound tags are worth it. Payloads of unknown tags can't be skipped
+    /// to find bounds of elements.
+    pub(super) fn is_parallel_list(&self, list_tags_type: TagType, length: u32) -> bool {
+        let is_nested = matches!(list_tags_type, TagType::List | TagType::Compound);
+
+        self.input.is_some()
+            && is_nested
+            && self.options.unknown_tag_reader.is_none()
+            && length as usize >= self.options.min_parallel_list_len
+    }
+
+    /// Reads list elements, each one by a separate decoder over its bytes.
+    pub(super) fn read_list_elements_par(
+        &mut self,
+        list_tags_type: TagType,
+        length: u32,
+    ) -> Result<Vec<Tag>> {
+        let input = self.input.expect("Parallel decoding requires input slice");
+        let mut bounds = Vec::with_capacity(length as usize);
+        let mut stats = NbtStats::default();
+
+        for index in 0..length {
+            let start = self.offset;
+
+            self.path.push(PathSegment::Index(index as usize));
+            self.skip_payload(list_tags_type, &mut stats)?;
+            self.path.pop();
+
+            bounds.push((start, self.offset));
+        }
+
+        let options = self.options;
+        let depth = self.depth;
+        let root_name = &self.root_name;
+        let path = &self.path;
+
+        bounds
+            .into_par_iter()
+            .enumerate()
+            .map(|(index, (start, end))| {
+                let mut cursor = Cursor::new(&input[start as usize..end as usize]);
+
+                let mut decoder = TagDecoder::new(&mut cursor, options);
+                decoder.offset = start;
+                decoder.input_len = Some(end);
+                decoder.input = Some(input);
+                decoder.depth = depth;
+                decoder.root_name = root_name.clone();
+                decoder.path = path.clone();
+                decoder.path.push(PathSegment::Index(index));
+
+                decoder.read_payload(list_tags_type, false)
+            })
+            .collect()
+    }
+}
+
+#[test]
+fn test_read_compound_tag_from_slice_par() {
+    use crate::decode::{read_compound_tag_from_cursor, read_gzip_compound_tag};
+    use crate::encode::write_compound_tag;
+
+    let mut cursor = Cursor::new(include_bytes!("../../test/binary/bigtest.dat").to_vec());
+    let mut root_tag = read_gzip_compound_tag(&mut cursor).unwrap();
+
+    let entities: Vec<_> = (0..100).map(|_| root_tag.clone()).collect();
+    root_tag.insert_compound_tag_vec("Entities", entities);
+
+    let mut vec = Vec::new();
+    write_compound_tag(&mut vec, &root_tag).unwrap();
+
+    let options = DecodeOptions {
+        min_parallel_list_len: 2,
+        ..Default::default()
+    };
+
+    let (decoded, length) = read_compound_tag_from_slice_par(&vec, &options).unwrap();
+    assert_eq!(decoded, root_tag);
+    assert_eq!(length, vec.len());
+
+    // Cut the last entity.
+    vec.truncate(vec.len() - 100);
+
+    let error = read_compound_tag_from_slice_par(&vec, &options).unwrap_err();
+    let expected = read_compound_tag_from_cursor(&mut Cursor::new(&vec), &options).unwrap_err();
+
+    assert_eq!(error.kind(), expected.kind());
+    assert_eq!(error.position(), expected.position());
+}
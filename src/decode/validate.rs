@@ -0,0 +1,188 @@
+//! Checking structure and limits of tags without building them.
+use super::{DecodeOptions, DuplicateKeys, Result, TagDecodeError, TagDecoder};
+use crate::nbtpath::PathSegment;
+use crate::TagType;
+use std::collections::HashSet;
+use std::io::Read;
+
+/// Statistics of a tag checked by [`validate`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NbtStats {
+    /// Number of tags, including the root compound tag.
+    pub tags: u64,
+    /// Maximum nesting depth of compound and list tags, root compound tag has depth 1.
+    pub max_depth: usize,
+    /// Number of bytes consumed.
+    pub bytes: u64,
+}
+
+/// Check a compound tag from a reader the same way as [`read_compound_tag_with_options`]
+/// would, but skip values instead of building them.
+///
+/// Only tag names are read, to report error positions and detect duplicates.
+///
+/// [`read_compound_tag_with_options`]: crate::decode::read_compound_tag_with_options
+///
+/// # Example
+/// ```
+/// use nbt::decode::{validate, DecodeOptions};
+/// use std::io::Cursor;
+///
+/// let mut cursor = Cursor::new(include_bytes!("../../test/binary/servers.dat").to_vec());
+/// let stats = validate(&mut cursor, &DecodeOptions::default()).unwrap();
+///
+/// assert_eq!(stats.tags, 6);
+/// assert_eq!(stats.max_depth, 3);
+/// ```
+pub fn validate<R: Read>(reader: &mut R, options: &DecodeOptions) -> Result<NbtStats> {
+    let mut decoder = TagDecoder::new(reader, options);
+    let mut stats = NbtStats::default();
+
+    decoder.skip_root_compound_tag(&mut stats)?;
+    stats.bytes = decoder.offset;
+
+    Ok(stats)
+}
+
+impl<'r, 'o, R: Read> TagDecoder<'r, 'o, R> {
+    fn skip_root_compound_tag(&mut self, stats: &mut NbtStats) -> Result<()> {
+        let tag_id = self.read_tag_id()?;
+
+        // Error contains the actual tag, so it is read as usual.
+        if tag_id != TagType::Compound {
+            return self.read_root_compound_payload(tag_id).map(|_| ());
+        }
+
+        self.root_name = self.read_root_name()?;
+        self.skip_payload(tag_id, stats)?;
+        self.root_name = None;
+
+        Ok(())
+    }
+
+    pub(super) fn skip_payload(&mut self, tag_type: TagType, stats: &mut NbtStats) -> Result<()> {
+        stats.tags += 1;
+
+        match tag_type {
+            TagType::Byte => self.skip(1),
+            TagType::Short => self.skip(2),
+            TagType::Int => self.skip(4),
+            TagType::Long => self.skip(8),
+            TagType::Float => self.skip(4),
+            TagType::Double => self.skip(8),
+            TagType::ByteArray => {
+                let length = self.read_length(1)?;
+                self.skip(u64::from(length))
+            }
+            // Exact decoding has to check contents of strings.
+            TagType::String if self.options.exact => self.read_string().map(|_| ()),
+            TagType::String => {
+                let length = self.read_string_length()?;
+                self.skip(u64::from(length))
+            }
+            TagType::List => self.skip_list(stats),
+            TagType::Compound => self.skip_compound(stats),
+            TagType::IntArray => {
+                let length = self.read_length(4)?;
+                self.skip(u64::from(length) * 4)
+            }
+            TagType::LongArray => {
+                let length = self.read_length(8)?;
+                self.skip(u64::from(length) * 8)
+            }
+            // Only unknown tag reader knows payload length.
+            TagType::Unknown(_) => self.read_payload(tag_type, false).map(|_| ()),
+        }
+    }
+
+    fn skip_list(&mut self, stats: &mut NbtStats) -> Result<()> {
+        self.enter()?;
+        stats.max_depth = stats.max_depth.max(self.depth);
+
+        let (list_tags_type, length) = self.read_list_header()?;
+
+        if let Some(list_tags_type) = list_tags_type {
+            for index in 0..length {
+                self.path.push(PathSegment::Index(index as usize));
+                self.skip_payload(list_tags_type, stats)?;
+                self.path.pop();
+            }
+        }
+
+        self.depth -= 1;
+
+        Ok(())
+    }
+
+    fn skip_compound(&mut self, stats: &mut NbtStats) -> Result<()> {
+        self.enter()?;
+        stats.max_depth = stats.max_depth.max(self.depth);
+
+        let detect_duplicates = self.options.duplicate_keys == DuplicateKeys::Error;
+        let mut names = HashSet::new();
+
+        loop {
+            let tag_offset = self.offset;
+            let tag_id = self.read_u8()?;
+
+            // Compound tag end reached.
+            if tag_id == 0 {
+                break;
+            }
+
+            let tag_type = self.tag_type(tag_id, tag_offset)?;
+            let name = self.read_string()?;
+
+            if detect_duplicates && !names.insert(name.clone()) {
+                return Err(TagDecodeError::DuplicateKey {
+                    name,
+                    position: self.position_at(tag_offset),
+                });
+            }
+
+            self.path.push(PathSegment::Key(name));
+            self.skip_payload(tag_type, stats)?;
+            self.path.pop();
+        }
+
+        self.depth -= 1;
+
+        Ok(())
+    }
+}
+
+#[test]
+fn test_validate() {
+    use crate::decode::{read_compound_tag, read_gzip_compound_tag};
+    use crate::ErrorKind;
+    use std::io::Cursor;
+
+    let mut cursor = Cursor::new(include_bytes!("../../test/binary/bigtest.dat").to_vec());
+    let root_tag = read_gzip_compound_tag(&mut cursor).unwrap();
+
+    let mut vec = Vec::new();
+    crate::encode::write_compound_tag(&mut vec, &root_tag).unwrap();
+
+    let stats = validate(&mut Cursor::new(vec.clone()), &DecodeOptions::default()).unwrap();
+
+    assert_eq!(stats.tags, root_tag.walk().count() as u64 + 1);
+    assert_eq!(stats.max_depth, 3);
+    assert_eq!(stats.bytes, vec.len() as u64);
+
+    vec.truncate(vec.len() - 100);
+
+    let error = validate(&mut Cursor::new(vec.clone()), &DecodeOptions::default()).unwrap_err();
+    let expected = read_compound_tag(&mut Cursor::new(vec)).unwrap_err();
+
+    assert_eq!(error.kind(), ErrorKind::Io);
+    assert_eq!(
+        error.position().unwrap().path,
+        expected.position().unwrap().path
+    );
+
+    let error = validate(
+        &mut Cursor::new(vec![8, 0, 0, 0, 0]),
+        &DecodeOptions::default(),
+    );
+    assert_eq!(error.unwrap_err().kind(), ErrorKind::RootMustBeCompoundTag);
+}
@@ -0,0 +1,298 @@
+use crate::{CompoundTag, CompoundTagError, ListElement, ListTag, Tag, TagType};
+
+/// Struct which can be converted from and to compound tag.
+///
+/// Usually derived with `#[derive(NbtCompound)]` of `derive` feature, which stores each field
+/// as [`NbtField`] named after it.
+///
+/// # Example
+/// ```
+/// # #[cfg(feature = "derive")]
+/// # {
+/// use nbt::{CompoundTag, NbtCompound};
+///
+/// #[derive(NbtCompound, Debug, PartialEq)]
+/// struct Sheep {
+///     #[nbt(rename = "Health")]
+///     health: f32,
+///     #[nbt(rename = "Sheared", default)]
+///     sheared: bool,
+///     #[nbt(rename = "CustomName")]
+///     custom_name: Option<String>,
+///     #[nbt(skip)]
+///     ticks: u32,
+/// }
+///
+/// let mut compound_tag = CompoundTag::new();
+/// compound_tag.insert_f32("Health", 8.0);
+///
+/// let sheep = Sheep::from_compound_tag(&compound_tag).unwrap();
+/// assert!(!sheep.sheared);
+/// assert_eq!(sheep.custom_name, None);
+///
+/// let mut compound_tag = sheep.to_compound_tag();
+/// assert_eq!(compound_tag.get_i8("Sheared").unwrap(), 0);
+/// assert!(!compound_tag.contains_key("CustomName"));
+///
+/// compound_tag.insert_i32("Health", 8);
+/// assert_eq!(
+///     Sheep::from_compound_tag(&compound_tag).unwrap_err().to_string(),
+///     "Tag Health has type TAG_Int, expected TAG_Float"
+/// );
+/// # }
+/// ```
+pub trait NbtCompound: Sized {
+    fn from_compound_tag(compound_tag: &CompoundTag)
+        -> Result<Self, CompoundTagError<'_, 'static>>;
+
+    fn to_compound_tag(&self) -> CompoundTag;
+}
+
+/// Type of a field of [`NbtCompound`] struct.
+pub trait NbtField: Sized {
+    /// Converts tag of the field with provided name.
+    fn from_tag<'a>(
+        tag: &'a Tag,
+        name: &'static str,
+    ) -> Result<Self, CompoundTagError<'a, 'static>>;
+
+    /// Value of the field if tag is missing, which is an error unless it's an option.
+    fn from_missing<'a>(name: &'static str) -> Result<Self, CompoundTagError<'a, 'static>> {
+        Err(CompoundTagError::TagNotFound { name })
+    }
+
+    /// Tag of the field or `None` if it is not written, e.g. for `None` options.
+    fn to_tag(&self) -> Option<Tag>;
+
+    /// Reads the field from compound tag.
+    fn read_field<'a>(
+        compound_tag: &'a CompoundTag,
+        name: &'static str,
+    ) -> Result<Self, CompoundTagError<'a, 'static>> {
+        match compound_tag.tags.get(name) {
+            Some(tag) => Self::from_tag(tag, name),
+            None => Self::from_missing(name),
+        }
+    }
+}
+
+fn wrong_type<'a>(
+    tag: &'a Tag,
+    name: &'static str,
+    expected_type: TagType,
+) -> CompoundTagError<'a, 'static> {
+    CompoundTagError::TagWrongType {
+        name,
+        actual_tag: tag,
+        expected_type: Some(expected_type),
+    }
+}
+
+macro_rules! impl_field (
+    ($type: ty, $tag: ident) => (
+        impl NbtField for $type {
+            fn from_tag<'a>(tag: &'a Tag, name: &'static str) -> Result<Self, CompoundTagError<'a, 'static>> {
+                match tag {
+                    Tag::$tag(value) => Ok(value.clone()),
+                    tag => Err(wrong_type(tag, name, TagType::$tag)),
+                }
+            }
+
+            fn to_tag(&self) -> Option<Tag> {
+                Some(Tag::$tag(self.clone()))
+            }
+        }
+    );
+);
+
+impl_field!(i8, Byte);
+impl_field!(i16, Short);
+impl_field!(i32, Int);
+impl_field!(i64, Long);
+impl_field!(f32, Float);
+impl_field!(f64, Double);
+impl_field!(Vec<i8>, ByteArray);
+impl_field!(String, String);
+impl_field!(Vec<Tag>, List);
+impl_field!(Vec<i32>, IntArray);
+impl_field!(Vec<i64>, LongArray);
+
+/// Stored as byte, same as [`CompoundTag::insert_bool`].
+impl NbtField for bool {
+    fn from_tag<'a>(
+        tag: &'a Tag,
+        name: &'static str,
+    ) -> Result<Self, CompoundTagError<'a, 'static>> {
+        Ok(i8::from_tag(tag, name)? == 1)
+    }
+
+    fn to_tag(&self) -> Option<Tag> {
+        Some(Tag::Byte(*self as i8))
+    }
+}
+
+/// Tag of any type.
+impl NbtField for Tag {
+    fn from_tag<'a>(tag: &'a Tag, _: &'static str) -> Result<Self, CompoundTagError<'a, 'static>> {
+        Ok(tag.clone())
+    }
+
+    fn to_tag(&self) -> Option<Tag> {
+        Some(self.clone())
+    }
+}
+
+/// Missing tag is `None`, which is not written back.
+impl<T: NbtField> NbtField for Option<T> {
+    fn from_tag<'a>(
+        tag: &'a Tag,
+        name: &'static str,
+    ) -> Result<Self, CompoundTagError<'a, 'static>> {
+        T::from_tag(tag, name).map(Some)
+    }
+
+    fn from_missing<'a>(_: &'static str) -> Result<Self, CompoundTagError<'a, 'static>> {
+        Ok(None)
+    }
+
+    fn to_tag(&self) -> Option<Tag> {
+        self.as_ref().and_then(T::to_tag)
+    }
+}
+
+/// Nested compound tag.
+impl<T: NbtCompound> NbtField for T {
+    fn from_tag<'a>(
+        tag: &'a Tag,
+        name: &'static str,
+    ) -> Result<Self, CompoundTagError<'a, 'static>> {
+        match tag {
+            Tag::Compound(compound_tag) => T::from_compound_tag(compound_tag),
+            tag => Err(wrong_type(tag, name, TagType::Compound)),
+        }
+    }
+
+    fn to_tag(&self) -> Option<Tag> {
+        Some(Tag::Compound(self.to_compound_tag()))
+    }
+}
+
+/// List of compound tags.
+impl<T: NbtCompound> NbtField for Vec<T> {
+    fn from_tag<'a>(
+        tag: &'a Tag,
+        name: &'static str,
+    ) -> Result<Self, CompoundTagError<'a, 'static>> {
+        match tag {
+            Tag::List(tags) => tags.iter().map(|tag| T::from_tag(tag, name)).collect(),
+            tag => Err(wrong_type(tag, name, TagType::List)),
+        }
+    }
+
+    fn to_tag(&self) -> Option<Tag> {
+        let tags = self.iter().map(T::to_compound_tag).map(Tag::Compound);
+        Some(Tag::List(tags.collect()))
+    }
+}
+
+impl<T: ListElement + Clone> NbtField for ListTag<T> {
+    fn from_tag<'a>(
+        tag: &'a Tag,
+        name: &'static str,
+    ) -> Result<Self, CompoundTagError<'a, 'static>> {
+        match tag {
+            Tag::List(tags) => tags
+                .iter()
+                .map(|tag| T::try_from(tag.clone()).map_err(|_| wrong_type(tag, name, T::TAG_TYPE)))
+                .collect(),
+            tag => Err(wrong_type(tag, name, TagType::List)),
+        }
+    }
+
+    fn to_tag(&self) -> Option<Tag> {
+        Some(Tag::from(self.clone()))
+    }
+}
+
+impl NbtCompound for CompoundTag {
+    fn from_compound_tag(
+        compound_tag: &CompoundTag,
+    ) -> Result<Self, CompoundTagError<'_, 'static>> {
+        Ok(compound_tag.clone())
+    }
+
+    fn to_compound_tag(&self) -> CompoundTag {
+        self.clone()
+    }
+}
+
+#[test]
+#[cfg(feature = "derive")]
+fn test_derive_nbt_compound() {
+    use crate::NbtCompound;
+
+    #[derive(NbtCompound, Debug, Clone, PartialEq)]
+    struct Item {
+        id: String,
+        #[nbt(rename = "Count", default)]
+        count: i8,
+    }
+
+    #[derive(NbtCompound, Debug, PartialEq)]
+    struct Chest {
+        #[nbt(rename = "Items")]
+        items: Vec<Item>,
+        #[nbt(rename = "Lock")]
+        lock: Option<String>,
+        #[nbt(rename = "Pos")]
+        pos: ListTag<i32>,
+        r#type: Item,
+        #[nbt(skip)]
+        opened: bool,
+    }
+
+    let mut item = CompoundTag::new();
+    item.insert_str("id", "minecraft:stone");
+
+    let mut compound_tag = CompoundTag::new();
+    compound_tag.insert_compound_tag_vec("Items", vec![item.clone()]);
+    compound_tag.insert("Pos", Tag::List(vec![Tag::Int(1), Tag::Int(2)]));
+    compound_tag.insert_compound_tag("type", item);
+
+    let chest = Chest::from_compound_tag(&compound_tag).unwrap();
+    let stone = Item {
+        id: "minecraft:stone".to_string(),
+        count: 0,
+    };
+
+    assert_eq!(
+        chest,
+        Chest {
+            items: vec![stone.clone()],
+            lock: None,
+            pos: vec![1, 2].into(),
+            r#type: stone.clone(),
+            opened: false,
+        }
+    );
+
+    let written = chest.to_compound_tag();
+    assert!(!written.contains_key("Lock"));
+    assert!(!written.contains_key("opened"));
+    assert_eq!(Chest::from_compound_tag(&written).unwrap(), chest);
+
+    // Errors of nested tags are reported as is, missing nested tags are not defaulted.
+    let mut broken = written.clone();
+    broken.insert_compound_tag("type", CompoundTag::new());
+    assert!(matches!(
+        Chest::from_compound_tag(&broken),
+        Err(CompoundTagError::TagNotFound { name: "id" })
+    ));
+
+    let mut broken = written;
+    broken.insert("Pos", Tag::List(vec![Tag::Int(1), Tag::Long(2)]));
+    assert_eq!(
+        Chest::from_compound_tag(&broken).unwrap_err().to_string(),
+        "Tag Pos has type TAG_Long, expected TAG_Int"
+    );
+}
@@ -1,24 +1,208 @@
-use crate::{CompoundTag, Tag};
-use byteorder::{BigEndian, WriteBytesExt};
+use crate::nbtpath::{fmt_path, NbtPath, PathSegment};
+use crate::{CompoundTag, ErrorKind, Tag, TagType};
+use byteorder::{BigEndian, ByteOrder};
 use flate2::write::{GzEncoder, ZlibEncoder};
-use std::io::{Error, Write};
+use flate2::{Compression, GzBuilder};
+use std::error::Error;
+use std::fmt::Display;
+use std::io::{self, BufWriter, Write};
+
+mod cow;
+mod sink;
+
+pub use self::cow::{
+    write_cow_compound_tag, write_cow_compound_tag_with_options, CowCompoundTag, CowTag,
+};
+pub use self::sink::{HashingSink, NbtSink};
+
+/// Result of encoding.
+pub type Result<T> = std::result::Result<T, TagEncodeError>;
+
+/// Possible types of errors while encoding tag.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum TagEncodeError {
+    /// String is longer than `u16::MAX` bytes and its length can't be written.
+    StringTooLong {
+        /// Path of tag which value or name is too long, empty for the root tag name.
+        path: NbtPath,
+        /// Actual string length in bytes.
+        length: usize,
+    },
+    /// Array or list has more than `i32::MAX` elements and its length can't be written.
+    ArrayTooLong {
+        /// Path of array or list tag.
+        path: NbtPath,
+        /// Actual number of elements.
+        length: usize,
+    },
+    /// List contains elements of different types, which can't be represented in binary format.
+    MixedList {
+        /// Path of the first element which type differs from type of the first element.
+        path: NbtPath,
+        /// Type of the first element.
+        expected_type: TagType,
+        /// Actual element type.
+        actual_type: TagType,
+    },
+    /// I/O Error which happened while were encoding.
+    IOError { io_error: io::Error },
+}
+
+/// Checks and behaviour of encoding.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct EncodeOptions {
+    /// Check that all list elements have the same type. Enabled by default.
+    pub check_list_types: bool,
+    /// Write tags of compound tags sorted by name, so output doesn't depend on insertion order.
+    /// Applies to nested compound tags too. Disabled by default.
+    pub canonical: bool,
+}
+
+impl Default for EncodeOptions {
+    fn default() -> Self {
+        EncodeOptions {
+            check_list_types: true,
+            canonical: false,
+        }
+    }
+}
+
+impl TagEncodeError {
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            TagEncodeError::StringTooLong { .. } => ErrorKind::StringTooLong,
+            TagEncodeError::ArrayTooLong { .. } => ErrorKind::ArrayTooLong,
+            TagEncodeError::MixedList { .. } => ErrorKind::MixedList,
+            TagEncodeError::IOError { .. } => ErrorKind::Io,
+        }
+    }
+
+    /// Prepends segment to the path of problematic tag, if error has one.
+    fn with_parent(mut self, segment: PathSegment) -> Self {
+        match &mut self {
+            TagEncodeError::StringTooLong { path, .. }
+            | TagEncodeError::ArrayTooLong { path, .. }
+            | TagEncodeError::MixedList { path, .. } => path.push_front(segment),
+            TagEncodeError::IOError { .. } => {}
+        }
+
+        self
+    }
+}
+
+impl From<io::Error> for TagEncodeError {
+    fn from(io_error: io::Error) -> Self {
+        TagEncodeError::IOError { io_error }
+    }
+}
+
+impl Error for TagEncodeError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            TagEncodeError::IOError { io_error } => Some(io_error),
+            _ => None,
+        }
+    }
+}
+
+impl Display for TagEncodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::StringTooLong { path, length } => {
+                write!(f, "String")?;
+                fmt_path(f, path)?;
+                write!(f, " is {} bytes long, maximum is {}", length, u16::MAX)
+            }
+            Self::ArrayTooLong { path, length } => {
+                write!(f, "Array")?;
+                fmt_path(f, path)?;
+                write!(f, " has {} elements, maximum is {}", length, i32::MAX)
+            }
+            Self::MixedList {
+                path,
+                expected_type,
+                actual_type,
+            } => write!(
+                f,
+                "List element {} has type {}, expected {}",
+                path, actual_type, expected_type
+            ),
+            Self::IOError { io_error } => write!(f, "IO Error: {}", io_error),
+        }
+    }
+}
 
 /// Write a compound tag to writer using gzip compression.
-pub fn write_gzip_compound_tag<W: Write>(
+pub fn write_gzip_compound_tag<W: Write>(writer: &mut W, compound_tag: &CompoundTag) -> Result<()> {
+    write_gzip_compound_tag_with_options(writer, compound_tag, &GzipOptions::default())
+}
+
+/// Gzip header fields and compression level.
+///
+/// Header doesn't depend on time or platform by default, so the same tag is always compressed
+/// into the same bytes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct GzipOptions {
+    /// Compression level from 0 to 9. Default is 6.
+    pub level: u32,
+    /// Modification time in the header as Unix timestamp. Default is 0, meaning not available.
+    pub mtime: u32,
+    /// Operating system byte in the header. Default is 255, meaning unknown.
+    pub operating_system: u8,
+}
+
+impl Default for GzipOptions {
+    fn default() -> Self {
+        GzipOptions {
+            level: 6,
+            mtime: 0,
+            operating_system: 255,
+        }
+    }
+}
+
+/// Write a compound tag to writer using gzip compression with provided header and level.
+///
+/// # Example
+/// ```
+/// use nbt::encode::{write_gzip_compound_tag_with_options, GzipOptions};
+/// use nbt::CompoundTag;
+///
+/// let mut root_tag = CompoundTag::new();
+/// root_tag.insert_i32("x", 1);
+///
+/// let mut options = GzipOptions::default();
+/// options.level = 9;
+///
+/// let mut a = Vec::new();
+/// write_gzip_compound_tag_with_options(&mut a, &root_tag, &options).unwrap();
+///
+/// let mut b = Vec::new();
+/// write_gzip_compound_tag_with_options(&mut b, &root_tag, &options).unwrap();
+///
+/// assert_eq!(a, b);
+/// ```
+pub fn write_gzip_compound_tag_with_options<W: Write>(
     writer: &mut W,
     compound_tag: &CompoundTag,
-) -> Result<(), Error> {
-    write_compound_tag(
-        &mut GzEncoder::new(writer, Default::default()),
-        compound_tag,
-    )
+    options: &GzipOptions,
+) -> Result<()> {
+    let mut encoder = GzBuilder::new()
+        .mtime(options.mtime)
+        .operating_system(options.operating_system)
+        .write(writer, Compression::new(options.level));
+
+    write_compound_tag(&mut encoder, compound_tag)?;
+    encoder.finish()?;
+
+    Ok(())
 }
 
 /// Write a compound tag to writer using zlib compression.
-pub fn write_zlib_compound_tag<W: Write>(
-    writer: &mut W,
-    compound_tag: &CompoundTag,
-) -> Result<(), Error> {
+pub fn write_zlib_compound_tag<W: Write>(writer: &mut W, compound_tag: &CompoundTag) -> Result<()> {
     write_compound_tag(
         &mut ZlibEncoder::new(writer, Default::default()),
         compound_tag,
@@ -27,6 +211,9 @@ pub fn write_zlib_compound_tag<W: Write>(
 
 /// Write a compound tag to writer.
 ///
+/// Tag is written with many small writes, so unbuffered writers like `File` or `TcpStream`
+/// should be wrapped into `BufWriter` or passed to [`write_compound_tag_buffered`].
+///
 /// # Example
 /// ```
 /// use nbt::encode::write_compound_tag;
@@ -47,89 +234,521 @@ pub fn write_zlib_compound_tag<W: Write>(
 /// let mut vec = Vec::new();
 /// write_compound_tag(&mut vec, &root_tag).unwrap();
 /// ```
-pub fn write_compound_tag<W: Write>(
+pub fn write_compound_tag<W: NbtSink>(writer: &mut W, compound_tag: &CompoundTag) -> Result<()> {
+    write_compound_tag_with_options(writer, compound_tag, &EncodeOptions::default())
+}
+
+/// Write a compound tag to writer using provided options.
+pub fn write_compound_tag_with_options<W: NbtSink>(
     writer: &mut W,
     compound_tag: &CompoundTag,
-) -> Result<(), Error> {
+    options: &EncodeOptions,
+) -> Result<()> {
     // Tag id
-    writer.write_u8(Tag::Compound(CompoundTag::new()).type_id())?;
+    writer.write_bytes(&[Tag::Compound(CompoundTag::new()).type_id()])?;
 
     write_string(writer, compound_tag.name.as_deref().unwrap_or(""))?;
 
-    write_inner_compound_tag(writer, compound_tag)
+    encode_compound_tag(writer, compound_tag, options)
+}
+
+/// Write a compound tag to writer through an internal buffer and flush it.
+///
+/// Makes a few large writes instead of many small ones, which matters for unbuffered writers.
+///
+/// # Example
+/// ```no_run
+/// use nbt::encode::write_compound_tag_buffered;
+/// use nbt::CompoundTag;
+/// use std::fs::File;
+///
+/// let mut root_tag = CompoundTag::new();
+/// root_tag.insert_i32("x", 1);
+///
+/// let mut file = File::create("level.dat").unwrap();
+/// write_compound_tag_buffered(&mut file, &root_tag).unwrap();
+/// ```
+pub fn write_compound_tag_buffered<W: Write>(
+    writer: &mut W,
+    compound_tag: &CompoundTag,
+) -> Result<()> {
+    let capacity = compound_tag.serialized_size().min(BUFFER_SIZE);
+    let mut writer = BufWriter::with_capacity(capacity, writer);
+
+    write_compound_tag(&mut writer, compound_tag)?;
+    writer.flush()?;
+
+    Ok(())
+}
+
+/// Maximum size of internal buffer of [`write_compound_tag_buffered`].
+const BUFFER_SIZE: usize = 64 * 1024;
+
+/// Append a compound tag to a vector, reserving exact [`CompoundTag::serialized_size`] beforehand,
+/// so vector is reallocated at most once.
+///
+/// On error vector is truncated back to its original length.
+///
+/// # Example
+/// ```
+/// use nbt::encode::write_compound_tag_to_vec;
+/// use nbt::CompoundTag;
+///
+/// let mut root_tag = CompoundTag::new();
+/// root_tag.insert_i32("x", 1);
+///
+/// // Packet id followed by tag.
+/// let mut packet = vec![0x1a];
+/// write_compound_tag_to_vec(&mut packet, &root_tag).unwrap();
+///
+/// assert_eq!(packet.len(), 1 + root_tag.serialized_size());
+/// ```
+pub fn write_compound_tag_to_vec(vec: &mut Vec<u8>, compound_tag: &CompoundTag) -> Result<()> {
+    let length = vec.len();
+    vec.reserve_exact(compound_tag.serialized_size());
+
+    let result = write_compound_tag(vec, compound_tag);
+
+    if result.is_err() {
+        vec.truncate(length);
+    }
+
+    result
+}
+
+/// Write a compound tag directly into a buffer, e.g. frame of a protocol built on `bytes`.
+///
+/// Fails without writing anything if buffer has less than [`CompoundTag::serialized_size`]
+/// bytes of capacity left.
+///
+/// # Example
+/// ```
+/// use bytes::{BufMut, BytesMut};
+/// use nbt::encode::write_compound_tag_buf;
+/// use nbt::CompoundTag;
+///
+/// let mut root_tag = CompoundTag::new();
+/// root_tag.insert_i32("x", 1);
+///
+/// let mut frame = BytesMut::new();
+/// frame.put_u8(0x1a);
+/// write_compound_tag_buf(&mut frame, &root_tag).unwrap();
+///
+/// assert_eq!(frame.len(), 1 + root_tag.serialized_size());
+/// ```
+#[cfg(feature = "bytes")]
+pub fn write_compound_tag_buf<B: bytes::BufMut>(
+    buf: &mut B,
+    compound_tag: &CompoundTag,
+) -> Result<()> {
+    if buf.remaining_mut() < compound_tag.serialized_size() {
+        return Err(io::Error::from(io::ErrorKind::WriteZero).into());
+    }
+
+    write_compound_tag(&mut bytes::BufMut::writer(buf), compound_tag)
 }
 
-pub fn write_inner_compound_tag<W: Write>(
+pub fn write_inner_compound_tag<W: NbtSink>(
     writer: &mut W,
     compound_tag: &CompoundTag,
-) -> Result<(), Error> {
-    for (name, tag) in &compound_tag.tags {
-        writer.write_u8(tag.type_id())?;
-        write_string(writer, name)?;
-        write_tag(writer, tag)?;
+) -> Result<()> {
+    encode_compound_tag(writer, compound_tag, &EncodeOptions::default())
+}
+
+/// Write a single tag payload to writer.
+///
+/// Unlike [`write_compound_tag`] neither type id nor name are written, only the payload itself.
+///
+/// # Example
+/// ```
+/// use nbt::encode::write_tag;
+/// use nbt::Tag;
+///
+/// let mut vec = Vec::new();
+/// write_tag(&mut vec, &Tag::IntArray(vec![1, 2])).unwrap();
+///
+/// assert_eq!(vec, vec![0, 0, 0, 2, 0, 0, 0, 1, 0, 0, 0, 2]);
+/// ```
+pub fn write_tag<W: NbtSink>(writer: &mut W, tag: &Tag) -> Result<()> {
+    write_tag_with_options(writer, tag, &EncodeOptions::default())
+}
+
+/// Write a single tag payload to writer using provided options.
+pub fn write_tag_with_options<W: NbtSink>(
+    writer: &mut W,
+    tag: &Tag,
+    options: &EncodeOptions,
+) -> Result<()> {
+    encode_tag(writer, tag, options)
+}
+
+/// Payload of a tag, borrowed from either [`Tag`] or [`CowTag`] so both are encoded the same way.
+enum Payload<'a, T: EncodeTag> {
+    Byte(i8),
+    Short(i16),
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    ByteArray(&'a [i8]),
+    String(&'a str),
+    List(&'a [T]),
+    Compound(&'a T::Compound),
+    IntArray(&'a [i32]),
+    LongArray(&'a [i64]),
+    Unknown(&'a [u8]),
+}
+
+/// Tag which can be encoded.
+trait EncodeTag: Sized {
+    type Compound: EncodeCompound<Tag = Self>;
+
+    fn tag_type(&self) -> TagType;
+
+    fn payload(&self) -> Payload<'_, Self>;
+}
+
+/// Compound tag which can be encoded.
+trait EncodeCompound {
+    type Tag: EncodeTag;
+
+    /// Calls `f` for each tag in insertion order, stopping at the first error.
+    fn try_for_each_entry<F>(&self, f: F) -> Result<()>
+    where
+        F: FnMut(&str, &Self::Tag) -> Result<()>;
+
+    /// Tags sorted by name, for canonical encoding.
+    fn sorted_entries(&self) -> Vec<(&str, &Self::Tag)>;
+}
+
+impl EncodeTag for Tag {
+    type Compound = CompoundTag;
+
+    fn tag_type(&self) -> TagType {
+        Tag::tag_type(self)
+    }
+
+    fn payload(&self) -> Payload<'_, Self> {
+        match self {
+            Tag::Byte(value) => Payload::Byte(*value),
+            Tag::Short(value) => Payload::Short(*value),
+            Tag::Int(value) => Payload::Int(*value),
+            Tag::Long(value) => Payload::Long(*value),
+            Tag::Float(value) => Payload::Float(*value),
+            Tag::Double(value) => Payload::Double(*value),
+            Tag::ByteArray(value) => Payload::ByteArray(value),
+            Tag::String(value) => Payload::String(value),
+            Tag::List(value) => Payload::List(value),
+            Tag::Compound(value) => Payload::Compound(value),
+            Tag::IntArray(value) => Payload::IntArray(value),
+            Tag::LongArray(value) => Payload::LongArray(value),
+            Tag::Unknown { bytes, .. } => Payload::Unknown(bytes),
+        }
+    }
+}
+
+impl EncodeCompound for CompoundTag {
+    type Tag = Tag;
+
+    fn try_for_each_entry<F>(&self, mut f: F) -> Result<()>
+    where
+        F: FnMut(&str, &Tag) -> Result<()>,
+    {
+        for (name, tag) in self.tags.iter() {
+            f(name, tag)?;
+        }
+
+        Ok(())
+    }
+
+    fn sorted_entries(&self) -> Vec<(&str, &Tag)> {
+        CompoundTag::sorted_entries(self)
+    }
+}
+
+fn encode_compound_tag<W: NbtSink, C: EncodeCompound>(
+    writer: &mut W,
+    compound_tag: &C,
+    options: &EncodeOptions,
+) -> Result<()> {
+    let mut encode_entry = |name: &str, tag: &C::Tag| {
+        let result = writer
+            .write_bytes(&[tag.tag_type().id()])
+            .map_err(TagEncodeError::from)
+            .and_then(|_| write_string(writer, name))
+            .and_then(|_| encode_tag(writer, tag, options));
+
+        result.map_err(|e| e.with_parent(PathSegment::Key(name.to_string())))
+    };
+
+    // Only canonical encoding needs to collect tags, the rest are written straight from the map.
+    if options.canonical {
+        for (name, tag) in compound_tag.sorted_entries() {
+            encode_entry(name, tag)?;
+        }
+    } else {
+        compound_tag.try_for_each_entry(encode_entry)?;
     }
 
     // To mark compound tag end.
-    writer.write_u8(0)
-}
-
-fn write_tag<W: Write>(writer: &mut W, tag: &Tag) -> Result<(), Error> {
-    match tag {
-        Tag::Byte(value) => writer.write_i8(*value)?,
-        Tag::Short(value) => writer.write_i16::<BigEndian>(*value)?,
-        Tag::Int(value) => writer.write_i32::<BigEndian>(*value)?,
-        Tag::Long(value) => writer.write_i64::<BigEndian>(*value)?,
-        Tag::Float(value) => writer.write_f32::<BigEndian>(*value)?,
-        Tag::Double(value) => writer.write_f64::<BigEndian>(*value)?,
-        Tag::ByteArray(value) => {
-            writer.write_u32::<BigEndian>(value.len() as u32)?;
-
-            for v in value {
-                writer.write_i8(*v)?;
-            }
+    writer.write_bytes(&[0])?;
+
+    Ok(())
+}
+
+fn encode_tag<W: NbtSink, T: EncodeTag>(
+    writer: &mut W,
+    tag: &T,
+    options: &EncodeOptions,
+) -> Result<()> {
+    match tag.payload() {
+        Payload::Byte(value) => writer.write_bytes(&value.to_be_bytes())?,
+        Payload::Short(value) => writer.write_bytes(&value.to_be_bytes())?,
+        Payload::Int(value) => writer.write_bytes(&value.to_be_bytes())?,
+        Payload::Long(value) => writer.write_bytes(&value.to_be_bytes())?,
+        Payload::Float(value) => writer.write_bytes(&value.to_be_bytes())?,
+        Payload::Double(value) => writer.write_bytes(&value.to_be_bytes())?,
+        Payload::ByteArray(value) => {
+            write_length(writer, value.len())?;
+            writer.write_bytes(i8_slice_as_bytes(value))?;
         }
-        Tag::String(value) => write_string(writer, value)?,
-        Tag::List(value) => {
+        Payload::String(value) => write_string(writer, value)?,
+        Payload::List(value) => {
+            if options.check_list_types {
+                check_list_types(value)?;
+            }
+
             if !value.is_empty() {
-                writer.write_u8(value[0].type_id())?;
+                writer.write_bytes(&[value[0].tag_type().id()])?;
             } else {
                 // Empty list type.
-                writer.write_u8(0)?;
+                writer.write_bytes(&[0])?;
             }
 
-            writer.write_u32::<BigEndian>(value.len() as u32)?;
+            write_length(writer, value.len())?;
 
-            for tag in value {
-                write_tag(writer, tag)?;
+            for (index, tag) in value.iter().enumerate() {
+                encode_tag(writer, tag, options)
+                    .map_err(|e| e.with_parent(PathSegment::Index(index)))?;
             }
         }
-        Tag::Compound(value) => write_inner_compound_tag(writer, value)?,
-        Tag::IntArray(value) => {
-            writer.write_u32::<BigEndian>(value.len() as u32)?;
+        Payload::Compound(value) => encode_compound_tag(writer, value, options)?,
+        Payload::IntArray(value) => {
+            write_length(writer, value.len())?;
+            write_array(writer, value, 4, BigEndian::write_i32_into)?;
+        }
+        Payload::LongArray(value) => {
+            write_length(writer, value.len())?;
+            write_array(writer, value, 8, BigEndian::write_i64_into)?;
+        }
+        Payload::Unknown(bytes) => writer.write_bytes(bytes)?,
+    }
 
-            for v in value {
-                writer.write_i32::<BigEndian>(*v)?;
-            }
+    Ok(())
+}
+
+/// Checks that all list elements have the same type.
+fn check_list_types<T: EncodeTag>(tags: &[T]) -> Result<()> {
+    let expected_type = match tags.first() {
+        Some(tag) => tag.tag_type(),
+        None => return Ok(()),
+    };
+
+    for (index, tag) in tags.iter().enumerate() {
+        let actual_type = tag.tag_type();
+
+        if actual_type != expected_type {
+            return Err(TagEncodeError::MixedList {
+                path: NbtPath::new().join_index(index),
+                expected_type,
+                actual_type,
+            });
         }
-        Tag::LongArray(value) => {
-            writer.write_u32::<BigEndian>(value.len() as u32)?;
+    }
 
-            for v in value {
-                writer.write_i64::<BigEndian>(*v)?;
-            }
+    Ok(())
+}
+
+impl Tag {
+    /// Returns exact number of bytes [`write_tag`] would write for this tag payload.
+    pub fn serialized_size(&self) -> usize {
+        match self {
+            Tag::Byte(_) => 1,
+            Tag::Short(_) => 2,
+            Tag::Int(_) => 4,
+            Tag::Long(_) => 8,
+            Tag::Float(_) => 4,
+            Tag::Double(_) => 8,
+            Tag::ByteArray(value) => 4 + value.len(),
+            Tag::String(value) => string_serialized_size(value),
+            Tag::List(value) => 1 + 4 + value.iter().map(Tag::serialized_size).sum::<usize>(),
+            Tag::Compound(value) => value.inner_serialized_size(),
+            Tag::IntArray(value) => 4 + value.len() * 4,
+            Tag::LongArray(value) => 4 + value.len() * 8,
+            Tag::Unknown { bytes, .. } => bytes.len(),
         }
     }
+}
+
+impl CompoundTag {
+    /// Returns exact number of bytes [`write_compound_tag`] would write for this compound tag.
+    ///
+    /// # Example
+    /// ```
+    /// use nbt::encode::write_compound_tag;
+    /// use nbt::CompoundTag;
+    ///
+    /// let mut compound_tag = CompoundTag::named("hello world");
+    /// compound_tag.insert_str("name", "Bananrama");
+    ///
+    /// let mut vec = Vec::new();
+    /// write_compound_tag(&mut vec, &compound_tag).unwrap();
+    ///
+    /// assert_eq!(compound_tag.serialized_size(), vec.len());
+    /// ```
+    pub fn serialized_size(&self) -> usize {
+        1 + string_serialized_size(self.name.as_deref().unwrap_or(""))
+            + self.inner_serialized_size()
+    }
+
+    fn inner_serialized_size(&self) -> usize {
+        let tags_size: usize = self
+            .tags
+            .iter()
+            .map(|(name, tag)| 1 + string_serialized_size(name) + tag.serialized_size())
+            .sum();
+
+        // Size of tags and compound tag end mark.
+        tags_size + 1
+    }
+}
+
+impl CompoundTag {
+    /// Returns encoded compound tag, same as written by [`write_compound_tag`].
+    ///
+    /// # Example
+    /// ```
+    /// use nbt::CompoundTag;
+    ///
+    /// let mut compound_tag = CompoundTag::named("hello world");
+    /// compound_tag.insert_str("name", "Bananrama");
+    ///
+    /// let vec = compound_tag.to_vec().unwrap();
+    /// assert_eq!(vec, include_bytes!("../test/binary/hello_world.dat").to_vec());
+    /// ```
+    pub fn to_vec(&self) -> Result<Vec<u8>> {
+        let mut vec = Vec::new();
+        write_compound_tag_to_vec(&mut vec, self)?;
+
+        Ok(vec)
+    }
+
+    /// Returns encoded compound tag compressed with gzip.
+    pub fn to_gzip_vec(&self) -> Result<Vec<u8>> {
+        let mut encoder = GzEncoder::new(Vec::new(), Default::default());
+        write_compound_tag(&mut encoder, self)?;
+
+        Ok(encoder.finish()?)
+    }
+
+    /// Returns encoded compound tag compressed with zlib.
+    pub fn to_zlib_vec(&self) -> Result<Vec<u8>> {
+        let mut encoder = ZlibEncoder::new(Vec::new(), Default::default());
+        write_compound_tag(&mut encoder, self)?;
+
+        Ok(encoder.finish()?)
+    }
+}
+
+fn string_serialized_size(value: &str) -> usize {
+    2 + value.len()
+}
+
+fn write_string<W: NbtSink>(writer: &mut W, value: &str) -> Result<()> {
+    if value.len() > u16::MAX as usize {
+        return Err(TagEncodeError::StringTooLong {
+            path: NbtPath::new(),
+            length: value.len(),
+        });
+    }
+
+    writer.write_bytes(&(value.len() as u16).to_be_bytes())?;
+    writer.write_bytes(value.as_bytes())?;
 
     Ok(())
 }
 
-fn write_string<W: Write>(writer: &mut W, value: &str) -> Result<(), Error> {
-    writer.write_u16::<BigEndian>(value.len() as u16)?;
-    writer.write_all(value.as_bytes())?;
+fn write_length<W: NbtSink>(writer: &mut W, length: usize) -> Result<()> {
+    if length > i32::MAX as usize {
+        return Err(TagEncodeError::ArrayTooLong {
+            path: NbtPath::new(),
+            length,
+        });
+    }
+
+    writer.write_bytes(&(length as i32).to_be_bytes())?;
+
+    Ok(())
+}
+
+/// Size of scratch buffer used to convert arrays to big-endian.
+const ARRAY_CHUNK_SIZE: usize = 4096;
+
+/// Converts array in chunks through a scratch buffer, so writer is called once per chunk.
+fn write_array<W: NbtSink, T>(
+    writer: &mut W,
+    value: &[T],
+    element_size: usize,
+    write_into: fn(&[T], &mut [u8]),
+) -> Result<()> {
+    let mut buf = [0; ARRAY_CHUNK_SIZE];
+
+    for chunk in value.chunks(ARRAY_CHUNK_SIZE / element_size) {
+        let bytes = &mut buf[..chunk.len() * element_size];
+        write_into(chunk, bytes);
+        writer.write_bytes(bytes)?;
+    }
 
     Ok(())
 }
 
+fn i8_slice_as_bytes(value: &[i8]) -> &[u8] {
+    // SAFETY: `i8` and `u8` have the same size and alignment, and every bit pattern is valid for both.
+    unsafe { std::slice::from_raw_parts(value.as_ptr() as *const u8, value.len()) }
+}
+
+#[test]
+fn test_write_tag() {
+    let tag = Tag::List(vec![Tag::Short(1), Tag::Short(2)]);
+
+    let mut vec = Vec::new();
+    write_tag(&mut vec, &tag).unwrap();
+
+    assert_eq!(vec, vec![2, 0, 0, 0, 2, 0, 1, 0, 2]);
+
+    let mut vec = Vec::new();
+    write_tag(&mut vec, &Tag::ByteArray(vec![-1, 0, 127, -128])).unwrap();
+
+    assert_eq!(vec, vec![0, 0, 0, 4, 255, 0, 127, 128]);
+
+    let mut vec = Vec::new();
+    write_tag(&mut vec, &Tag::IntArray(vec![1, -2])).unwrap();
+
+    assert_eq!(vec, vec![0, 0, 0, 2, 0, 0, 0, 1, 255, 255, 255, 254]);
+
+    // Spans several chunks.
+    let value: Vec<i64> = (0..1500).map(|v| v * 0x0102_0304_0506).collect();
+    let mut vec = Vec::new();
+    write_tag(&mut vec, &Tag::LongArray(value.clone())).unwrap();
+
+    let mut expected = vec![0, 0, 0x05, 0xdc];
+    for v in value {
+        expected.extend_from_slice(&v.to_be_bytes());
+    }
+
+    assert_eq!(vec, expected);
+}
+
 #[test]
 fn test_hello_world_write() {
     let mut hello_world = CompoundTag::named("hello world");
@@ -162,3 +781,251 @@ fn test_servers_write() {
 
     assert_eq!(vec, include_bytes!("../test/binary/servers.dat").to_vec());
 }
+
+#[test]
+fn test_serialized_size() {
+    use crate::decode::read_gzip_compound_tag;
+    use std::io::Cursor;
+
+    let mut cursor = Cursor::new(include_bytes!("../test/binary/bigtest.dat").to_vec());
+    let root_tag = read_gzip_compound_tag(&mut cursor).unwrap();
+
+    let mut vec = Vec::new();
+    write_compound_tag(&mut vec, &root_tag).unwrap();
+
+    assert_eq!(root_tag.serialized_size(), vec.len());
+
+    let tag = Tag::List(vec![Tag::IntArray(vec![1, 2]), Tag::IntArray(vec![])]);
+
+    let mut vec = Vec::new();
+    write_tag(&mut vec, &tag).unwrap();
+
+    assert_eq!(tag.serialized_size(), vec.len());
+}
+
+#[test]
+fn test_to_vec() {
+    use crate::decode::{read_gzip_compound_tag, read_zlib_compound_tag};
+    use std::io::Cursor;
+
+    let mut root_tag = CompoundTag::named("hello world");
+    root_tag.insert_str("name", "Bananrama");
+
+    let vec = root_tag.to_gzip_vec().unwrap();
+    let decoded = read_gzip_compound_tag(&mut Cursor::new(vec)).unwrap();
+    assert_eq!(decoded, root_tag);
+
+    let vec = root_tag.to_zlib_vec().unwrap();
+    let decoded = read_zlib_compound_tag(&mut Cursor::new(vec)).unwrap();
+    assert_eq!(decoded, root_tag);
+}
+
+#[test]
+fn test_write_compound_tag_to_vec() {
+    let mut root_tag = CompoundTag::new();
+    root_tag.insert_str("name", "Bananrama");
+
+    let mut vec = vec![1, 2];
+    write_compound_tag_to_vec(&mut vec, &root_tag).unwrap();
+
+    assert_eq!(vec.len(), 2 + root_tag.serialized_size());
+    assert!(vec.capacity() >= vec.len());
+    assert_eq!(&vec[2..], &root_tag.to_vec().unwrap()[..]);
+
+    root_tag.insert("list", Tag::List(vec![Tag::Byte(1), Tag::Short(2)]));
+
+    let mut vec = vec![1, 2];
+    assert!(write_compound_tag_to_vec(&mut vec, &root_tag).is_err());
+    assert_eq!(vec, vec![1, 2]);
+}
+
+#[test]
+#[cfg(feature = "bytes")]
+fn test_write_compound_tag_buf() {
+    let mut root_tag = CompoundTag::named("hello world");
+    root_tag.insert_str("name", "Bananrama");
+
+    let mut frame = bytes::BytesMut::new();
+    write_compound_tag_buf(&mut frame, &root_tag).unwrap();
+    assert_eq!(
+        &frame[..],
+        &include_bytes!("../test/binary/hello_world.dat")[..]
+    );
+
+    let mut array = [0; 8];
+    let error = write_compound_tag_buf(&mut &mut array[..], &root_tag).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::Io);
+    assert_eq!(array, [0; 8]);
+}
+
+#[test]
+fn test_write_compound_tag_buffered() {
+    struct CountingWriter {
+        vec: Vec<u8>,
+        writes: usize,
+    }
+
+    impl Write for CountingWriter {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.writes += 1;
+            self.vec.write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let mut root_tag = CompoundTag::new();
+    for i in 0..100 {
+        root_tag.insert_i32(format!("tag{}", i), i);
+    }
+
+    let mut writer = CountingWriter {
+        vec: Vec::new(),
+        writes: 0,
+    };
+    write_compound_tag_buffered(&mut writer, &root_tag).unwrap();
+
+    assert_eq!(writer.vec, root_tag.to_vec().unwrap());
+    assert_eq!(writer.writes, 1);
+}
+
+#[test]
+fn test_write_gzip_compound_tag_with_options() {
+    use crate::decode::read_gzip_compound_tag;
+    use std::io::Cursor;
+
+    let mut root_tag = CompoundTag::new();
+    root_tag.insert_str("name", "Bananrama");
+
+    let mut vec = Vec::new();
+    write_gzip_compound_tag(&mut vec, &root_tag).unwrap();
+
+    let mut default = Vec::new();
+    write_gzip_compound_tag_with_options(&mut default, &root_tag, &GzipOptions::default()).unwrap();
+
+    assert_eq!(default, vec);
+
+    let options = GzipOptions {
+        level: 0,
+        mtime: 0x0102_0304,
+        operating_system: 3,
+    };
+
+    let mut vec = Vec::new();
+    write_gzip_compound_tag_with_options(&mut vec, &root_tag, &options).unwrap();
+
+    assert_eq!(&vec[4..10], &[4, 3, 2, 1, 4, 3]);
+    assert!(vec.len() > default.len());
+    assert_eq!(
+        read_gzip_compound_tag(&mut Cursor::new(vec)).unwrap(),
+        root_tag
+    );
+}
+
+#[test]
+fn test_canonical() {
+    let mut inner = CompoundTag::new();
+    inner.insert_i8("y", 1);
+    inner.insert_i8("x", 2);
+
+    let mut a = CompoundTag::new();
+    a.insert_i8("b", 1);
+    a.insert_compound_tag_vec("a", vec![inner]);
+
+    let mut inner = CompoundTag::new();
+    inner.insert_i8("x", 2);
+    inner.insert_i8("y", 1);
+
+    let mut b = CompoundTag::new();
+    b.insert_compound_tag_vec("a", vec![inner]);
+    b.insert_i8("b", 1);
+
+    let options = EncodeOptions {
+        canonical: true,
+        ..Default::default()
+    };
+
+    let encode = |compound_tag: &CompoundTag, options: &EncodeOptions| {
+        let mut vec = Vec::new();
+        write_compound_tag_with_options(&mut vec, compound_tag, options).unwrap();
+        vec
+    };
+
+    assert_ne!(
+        encode(&a, &EncodeOptions::default()),
+        encode(&b, &EncodeOptions::default())
+    );
+    assert_eq!(encode(&a, &options), encode(&b, &options));
+    assert_eq!(encode(&a, &options), encode(&b, &EncodeOptions::default()));
+}
+
+#[test]
+fn test_mixed_list() {
+    let mut item = CompoundTag::new();
+    item.insert("Lore", Tag::List(vec![Tag::from("a"), Tag::Int(1)]));
+
+    let mut root_tag = CompoundTag::new();
+    root_tag.insert_compound_tag_vec("Inventory", vec![CompoundTag::new(), item]);
+
+    let mut vec = Vec::new();
+    let error = write_compound_tag(&mut vec, &root_tag).unwrap_err();
+
+    assert_eq!(error.kind(), ErrorKind::MixedList);
+    assert_eq!(
+        error.to_string(),
+        "List element Inventory[1].Lore[1] has type TAG_Int, expected TAG_String"
+    );
+
+    let options = EncodeOptions {
+        check_list_types: false,
+        ..Default::default()
+    };
+
+    let mut vec = Vec::new();
+    assert!(write_compound_tag_with_options(&mut vec, &root_tag, &options).is_ok());
+}
+
+#[test]
+fn test_string_too_long() {
+    let mut compound_tag = CompoundTag::new();
+    compound_tag.insert_str("str", "a".repeat(u16::MAX as usize + 1));
+
+    let mut vec = Vec::new();
+    let error = write_compound_tag(&mut vec, &compound_tag).unwrap_err();
+
+    assert_eq!(error.kind(), ErrorKind::StringTooLong);
+    assert_eq!(
+        error.to_string(),
+        "String str is 65536 bytes long, maximum is 65535"
+    );
+
+    let mut nested = CompoundTag::new();
+    nested.insert_str("a".repeat(u16::MAX as usize + 1), "");
+
+    let mut root_tag = CompoundTag::named("a".repeat(u16::MAX as usize + 1));
+    root_tag.insert_compound_tag_vec("list", vec![nested]);
+
+    let mut vec = Vec::new();
+    let error = write_compound_tag(&mut vec, &root_tag).unwrap_err();
+
+    match error {
+        TagEncodeError::StringTooLong { path, .. } => assert!(path.is_empty()),
+        _ => panic!(),
+    }
+
+    root_tag.name = None;
+
+    let mut vec = Vec::new();
+    let error = write_compound_tag(&mut vec, &root_tag).unwrap_err();
+
+    match error {
+        TagEncodeError::StringTooLong { path, length } => {
+            assert_eq!(path.len(), 3);
+            assert!(path.to_string().starts_with("list[0].aaa"));
+            assert_eq!(length, u16::MAX as usize + 1);
+        }
+        _ => panic!(),
+    }
+}
@@ -0,0 +1,299 @@
+//! Tags which borrow their values, to encode data owned elsewhere without copying it.
+use super::{
+    encode_compound_tag, write_string, EncodeCompound, EncodeOptions, EncodeTag, NbtSink, Payload,
+    Result,
+};
+use crate::{CompoundTag, Tag, TagType};
+use std::borrow::Cow;
+
+/// Tag which either borrows or owns its payload.
+///
+/// Only used for encoding, values are written the same way as [`Tag`] ones.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CowTag<'a> {
+    Byte(i8),
+    Short(i16),
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    ByteArray(Cow<'a, [i8]>),
+    String(Cow<'a, str>),
+    List(Vec<CowTag<'a>>),
+    Compound(CowCompoundTag<'a>),
+    IntArray(Cow<'a, [i32]>),
+    LongArray(Cow<'a, [i64]>),
+    Unknown { id: u8, bytes: Cow<'a, [u8]> },
+}
+
+impl<'a> CowTag<'a> {
+    pub fn tag_type(&self) -> TagType {
+        match self {
+            CowTag::Byte(_) => TagType::Byte,
+            CowTag::Short(_) => TagType::Short,
+            CowTag::Int(_) => TagType::Int,
+            CowTag::Long(_) => TagType::Long,
+            CowTag::Float(_) => TagType::Float,
+            CowTag::Double(_) => TagType::Double,
+            CowTag::ByteArray(_) => TagType::ByteArray,
+            CowTag::String(_) => TagType::String,
+            CowTag::List(_) => TagType::List,
+            CowTag::Compound(_) => TagType::Compound,
+            CowTag::IntArray(_) => TagType::IntArray,
+            CowTag::LongArray(_) => TagType::LongArray,
+            CowTag::Unknown { id, .. } => TagType::Unknown(*id),
+        }
+    }
+}
+
+macro_rules! impl_from {
+    ($type: ty, $tag: ident) => {
+        impl<'a> From<$type> for CowTag<'a> {
+            fn from(value: $type) -> Self {
+                CowTag::$tag(value.into())
+            }
+        }
+    };
+}
+
+impl_from!(i8, Byte);
+impl_from!(i16, Short);
+impl_from!(i32, Int);
+impl_from!(i64, Long);
+impl_from!(f32, Float);
+impl_from!(f64, Double);
+impl_from!(&'a [i8], ByteArray);
+impl_from!(Vec<i8>, ByteArray);
+impl_from!(&'a str, String);
+impl_from!(String, String);
+impl_from!(Vec<CowTag<'a>>, List);
+impl_from!(CowCompoundTag<'a>, Compound);
+impl_from!(&'a [i32], IntArray);
+impl_from!(Vec<i32>, IntArray);
+impl_from!(&'a [i64], LongArray);
+impl_from!(Vec<i64>, LongArray);
+
+/// Borrows payload of a tag.
+impl<'a> From<&'a Tag> for CowTag<'a> {
+    fn from(tag: &'a Tag) -> Self {
+        match tag {
+            Tag::Byte(value) => CowTag::Byte(*value),
+            Tag::Short(value) => CowTag::Short(*value),
+            Tag::Int(value) => CowTag::Int(*value),
+            Tag::Long(value) => CowTag::Long(*value),
+            Tag::Float(value) => CowTag::Float(*value),
+            Tag::Double(value) => CowTag::Double(*value),
+            Tag::ByteArray(value) => CowTag::ByteArray(Cow::Borrowed(value)),
+            Tag::String(value) => CowTag::String(Cow::Borrowed(value)),
+            Tag::List(value) => CowTag::List(value.iter().map(CowTag::from).collect()),
+            Tag::Compound(value) => CowTag::Compound(value.into()),
+            Tag::IntArray(value) => CowTag::IntArray(Cow::Borrowed(value)),
+            Tag::LongArray(value) => CowTag::LongArray(Cow::Borrowed(value)),
+            Tag::Unknown { id, bytes } => CowTag::Unknown {
+                id: *id,
+                bytes: Cow::Borrowed(bytes),
+            },
+        }
+    }
+}
+
+/// Compound tag which either borrows or owns its names and tags.
+///
+/// # Example
+/// ```
+/// use nbt::encode::{write_cow_compound_tag, CowCompoundTag};
+/// use nbt::decode::read_compound_tag;
+/// use std::io::Cursor;
+///
+/// // Heights are owned by the caller and written without copying.
+/// let heights = vec![64i64; 37];
+///
+/// let mut root_tag = CowCompoundTag::new();
+/// root_tag.insert("MOTION_BLOCKING", &heights[..]);
+/// root_tag.insert("Status", "full");
+///
+/// let mut vec = Vec::new();
+/// write_cow_compound_tag(&mut vec, &root_tag).unwrap();
+///
+/// let decoded = read_compound_tag(&mut Cursor::new(vec)).unwrap();
+/// assert_eq!(decoded.get_i64_vec("MOTION_BLOCKING").unwrap(), &heights);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CowCompoundTag<'a> {
+    pub name: Option<Cow<'a, str>>,
+    tags: Vec<(Cow<'a, str>, CowTag<'a>)>,
+}
+
+impl<'a> CowCompoundTag<'a> {
+    pub fn new() -> Self {
+        CowCompoundTag::default()
+    }
+
+    pub fn named(name: impl Into<Cow<'a, str>>) -> Self {
+        CowCompoundTag {
+            name: Some(name.into()),
+            tags: Vec::new(),
+        }
+    }
+
+    /// Inserts tag at the end. Existing tag with the same name is removed.
+    ///
+    /// Tags are searched linearly and removal shifts the following ones, so building
+    /// a compound tag of `n` tags this way takes `O(n²)`. Use [`push`](Self::push)
+    /// if names are known to be distinct.
+    pub fn insert(&mut self, name: impl Into<Cow<'a, str>>, tag: impl Into<CowTag<'a>>) {
+        let name = name.into();
+
+        if let Some(index) = self.tags.iter().position(|(tag_name, _)| *tag_name == name) {
+            self.tags.remove(index);
+        }
+
+        self.tags.push((name, tag.into()));
+    }
+
+    /// Appends tag without checking for an existing tag with the same name.
+    ///
+    /// If there is one, both are written, so decoders keep only one of them.
+    pub fn push(&mut self, name: impl Into<Cow<'a, str>>, tag: impl Into<CowTag<'a>>) {
+        self.tags.push((name.into(), tag.into()));
+    }
+
+    pub fn get(&self, name: &str) -> Option<&CowTag<'a>> {
+        self.tags
+            .iter()
+            .find(|(tag_name, _)| tag_name == name)
+            .map(|(_, tag)| tag)
+    }
+
+    pub fn len(&self) -> usize {
+        self.tags.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tags.is_empty()
+    }
+
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = (&str, &CowTag<'a>)> {
+        self.tags.iter().map(|(name, tag)| (name.as_ref(), tag))
+    }
+}
+
+/// Borrows names and tags of a compound tag.
+impl<'a> From<&'a CompoundTag> for CowCompoundTag<'a> {
+    fn from(compound_tag: &'a CompoundTag) -> Self {
+        CowCompoundTag {
+            name: compound_tag.name.as_deref().map(Cow::Borrowed),
+            tags: compound_tag
+                .iter()
+                .map(|(name, tag)| (Cow::Borrowed(name), tag.into()))
+                .collect(),
+        }
+    }
+}
+
+/// Write a borrowing compound tag to writer.
+pub fn write_cow_compound_tag<W: NbtSink>(
+    writer: &mut W,
+    compound_tag: &CowCompoundTag,
+) -> Result<()> {
+    write_cow_compound_tag_with_options(writer, compound_tag, &EncodeOptions::default())
+}
+
+/// Write a borrowing compound tag to writer using provided options.
+pub fn write_cow_compound_tag_with_options<W: NbtSink>(
+    writer: &mut W,
+    compound_tag: &CowCompoundTag,
+    options: &EncodeOptions,
+) -> Result<()> {
+    writer.write_bytes(&[TagType::Compound.id()])?;
+    write_string(writer, compound_tag.name.as_deref().unwrap_or(""))?;
+
+    encode_compound_tag(writer, compound_tag, options)
+}
+
+impl<'a> EncodeTag for CowTag<'a> {
+    type Compound = CowCompoundTag<'a>;
+
+    fn tag_type(&self) -> TagType {
+        CowTag::tag_type(self)
+    }
+
+    fn payload(&self) -> Payload<'_, Self> {
+        match self {
+            CowTag::Byte(value) => Payload::Byte(*value),
+            CowTag::Short(value) => Payload::Short(*value),
+            CowTag::Int(value) => Payload::Int(*value),
+            CowTag::Long(value) => Payload::Long(*value),
+            CowTag::Float(value) => Payload::Float(*value),
+            CowTag::Double(value) => Payload::Double(*value),
+            CowTag::ByteArray(value) => Payload::ByteArray(value),
+            CowTag::String(value) => Payload::String(value),
+            CowTag::List(value) => Payload::List(value),
+            CowTag::Compound(value) => Payload::Compound(value),
+            CowTag::IntArray(value) => Payload::IntArray(value),
+            CowTag::LongArray(value) => Payload::LongArray(value),
+            CowTag::Unknown { bytes, .. } => Payload::Unknown(bytes),
+        }
+    }
+}
+
+impl<'a> EncodeCompound for CowCompoundTag<'a> {
+    type Tag = CowTag<'a>;
+
+    fn try_for_each_entry<F>(&self, mut f: F) -> Result<()>
+    where
+        F: FnMut(&str, &CowTag<'a>) -> Result<()>,
+    {
+        for (name, tag) in self.iter() {
+            f(name, tag)?;
+        }
+
+        Ok(())
+    }
+
+    fn sorted_entries(&self) -> Vec<(&str, &CowTag<'a>)> {
+        let mut entries: Vec<_> = self.iter().collect();
+        entries.sort_by_key(|(name, _)| *name);
+
+        entries
+    }
+}
+
+#[test]
+fn test_write_cow_compound_tag() {
+    use crate::decode::read_gzip_compound_tag;
+    use crate::encode::write_compound_tag;
+    use crate::ErrorKind;
+    use std::io::Cursor;
+
+    let mut cursor = Cursor::new(include_bytes!("../../test/binary/bigtest.dat").to_vec());
+    let root_tag = read_gzip_compound_tag(&mut cursor).unwrap();
+
+    let mut expected = Vec::new();
+    write_compound_tag(&mut expected, &root_tag).unwrap();
+
+    let mut vec = Vec::new();
+    write_cow_compound_tag(&mut vec, &CowCompoundTag::from(&root_tag)).unwrap();
+
+    assert_eq!(vec, expected);
+
+    let mut compound_tag = CowCompoundTag::new();
+    compound_tag.insert("a", 1i8);
+    compound_tag.insert("b", vec![CowTag::from(1i32), CowTag::from("x")]);
+    compound_tag.insert("a", 2i8);
+
+    assert_eq!(compound_tag.len(), 2);
+    assert_eq!(compound_tag.get("a"), Some(&CowTag::Byte(2)));
+
+    let mut pushed = CowCompoundTag::new();
+    pushed.push("b", vec![CowTag::from(1i32), CowTag::from("x")]);
+    pushed.push("a", 2i8);
+    assert_eq!(pushed, compound_tag);
+
+    let error = write_cow_compound_tag(&mut Vec::new(), &compound_tag).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::MixedList);
+    assert_eq!(
+        error.to_string(),
+        "List element b[1] has type TAG_String, expected TAG_Int"
+    );
+}
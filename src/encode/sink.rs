@@ -0,0 +1,108 @@
+//! Outputs which encoder writes through.
+use std::hash::Hasher;
+use std::io::{self, Write};
+
+/// Output of encoded bytes.
+///
+/// Implemented for every [`Write`], including `Vec<u8>`, so custom outputs only need
+/// an implementation if they can't be writers.
+pub trait NbtSink {
+    /// Writes all provided bytes.
+    fn write_bytes(&mut self, bytes: &[u8]) -> io::Result<()>;
+}
+
+impl<W: Write + ?Sized> NbtSink for W {
+    fn write_bytes(&mut self, bytes: &[u8]) -> io::Result<()> {
+        self.write_all(bytes)
+    }
+}
+
+/// Sink which feeds encoded bytes into a hasher and counts them, without buffering the output.
+///
+/// # Example
+/// ```
+/// use nbt::encode::{write_compound_tag, HashingSink};
+/// use nbt::CompoundTag;
+/// use std::collections::hash_map::DefaultHasher;
+/// use std::hash::Hasher;
+///
+/// let mut root_tag = CompoundTag::new();
+/// root_tag.insert_i32("x", 1);
+///
+/// let mut sink = HashingSink::new(DefaultHasher::new());
+/// write_compound_tag(&mut sink, &root_tag).unwrap();
+///
+/// assert_eq!(sink.bytes_written(), root_tag.serialized_size() as u64);
+///
+/// let mut hasher = DefaultHasher::new();
+/// hasher.write(&root_tag.to_vec().unwrap());
+/// assert_eq!(sink.finish(), hasher.finish());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct HashingSink<H> {
+    hasher: H,
+    bytes_written: u64,
+}
+
+impl<H: Hasher> HashingSink<H> {
+    pub fn new(hasher: H) -> Self {
+        HashingSink {
+            hasher,
+            bytes_written: 0,
+        }
+    }
+
+    /// Number of bytes written so far.
+    pub fn bytes_written(&self) -> u64 {
+        self.bytes_written
+    }
+
+    /// Hash of bytes written so far.
+    pub fn finish(&self) -> u64 {
+        self.hasher.finish()
+    }
+
+    pub fn into_inner(self) -> H {
+        self.hasher
+    }
+}
+
+impl<H: Hasher> NbtSink for HashingSink<H> {
+    fn write_bytes(&mut self, bytes: &[u8]) -> io::Result<()> {
+        self.hasher.write(bytes);
+        self.bytes_written += bytes.len() as u64;
+
+        Ok(())
+    }
+}
+
+#[test]
+fn test_hashing_sink() {
+    use crate::encode::{write_compound_tag_with_options, EncodeOptions};
+    use crate::CompoundTag;
+    use std::collections::hash_map::DefaultHasher;
+
+    let hash = |compound_tag: &CompoundTag| {
+        let options = EncodeOptions {
+            canonical: true,
+            ..Default::default()
+        };
+
+        let mut sink = HashingSink::new(DefaultHasher::new());
+        write_compound_tag_with_options(&mut sink, compound_tag, &options).unwrap();
+        sink.finish()
+    };
+
+    let mut a = CompoundTag::new();
+    a.insert_i8("a", 1);
+    a.insert_i8("b", 2);
+
+    let mut b = CompoundTag::new();
+    b.insert_i8("b", 2);
+    b.insert_i8("a", 1);
+
+    assert_eq!(hash(&a), hash(&b));
+
+    b.insert_i8("a", 3);
+    assert_ne!(hash(&a), hash(&b));
+}
@@ -0,0 +1,287 @@
+use crate::decode::TagDecodeError;
+use crate::encode::TagEncodeError;
+use crate::nbtpath::{NbtPath, PathError, PathParseError};
+use crate::CompoundTagError;
+use std::fmt::{self, Display, Formatter};
+use std::io;
+
+/// Result with crate level error by default.
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// Stable classification of errors, which doesn't depend on error payloads.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// Root of tag must be compound tag.
+    RootMustBeCompoundTag,
+    /// Tag type not recognized.
+    UnknownTagType,
+    /// Reader has data left after the root tag.
+    TrailingData,
+    /// Tags are nested too deep.
+    DepthLimitExceeded,
+    /// Input is too large.
+    SizeLimitExceeded,
+    /// List or array has too many elements.
+    ElementLimitExceeded,
+    /// String is too long to be decoded.
+    StringLimitExceeded,
+    /// List or array has negative length.
+    NegativeLength,
+    /// List header is malformed.
+    MalformedList,
+    /// Compound tag contains duplicate names.
+    DuplicateKey,
+    /// String is not valid UTF-8.
+    InvalidString,
+    /// Root tag name is not empty.
+    RootNameNotEmpty,
+    /// Length claims more bytes than input has left.
+    LengthExceedsInput,
+    /// I/O error.
+    Io,
+    /// String is too long to be encoded.
+    StringTooLong,
+    /// Array or list is too long to be encoded.
+    ArrayTooLong,
+    /// List contains elements of different types.
+    MixedList,
+    /// Tag not found.
+    TagNotFound,
+    /// Tag actual type not match expected.
+    TagWrongType,
+    /// Path string is malformed.
+    InvalidPath,
+}
+
+/// Any error which can happen while working with tags.
+///
+/// Useful in applications which decode, query and encode tags in one place.
+/// [`CompoundTagError`](crate::CompoundTagError) borrows from the tag, so it must be converted
+/// into [`PathError`] with [`with_path`](crate::CompoundTagError::with_path) first.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// Error while decoding tag.
+    Decode(TagDecodeError),
+    /// Error while encoding tag.
+    Encode(TagEncodeError),
+    /// Error while resolving tag by path.
+    Path(PathError),
+    /// Error while parsing path from string.
+    ParsePath(PathParseError),
+}
+
+impl Error {
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Error::Decode(error) => error.kind(),
+            Error::Encode(error) => error.kind(),
+            Error::Path(error) => error.kind(),
+            Error::ParsePath(error) => error.kind(),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Decode(error) => Some(error),
+            Error::Encode(error) => Some(error),
+            Error::Path(error) => Some(error),
+            Error::ParsePath(error) => Some(error),
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Decode(error) => write!(f, "Decode error: {}", error),
+            Error::Encode(error) => write!(f, "Encode error: {}", error),
+            Error::Path(error) => Display::fmt(error, f),
+            Error::ParsePath(error) => write!(f, "Invalid path: {}", error),
+        }
+    }
+}
+
+impl From<TagDecodeError> for Error {
+    fn from(error: TagDecodeError) -> Self {
+        Error::Decode(error)
+    }
+}
+
+impl From<TagEncodeError> for Error {
+    fn from(error: TagEncodeError) -> Self {
+        Error::Encode(error)
+    }
+}
+
+impl From<PathError> for Error {
+    fn from(error: PathError) -> Self {
+        Error::Path(error)
+    }
+}
+
+impl From<PathParseError> for Error {
+    fn from(error: PathParseError) -> Self {
+        Error::ParsePath(error)
+    }
+}
+
+impl<'a, 'b> From<CompoundTagError<'a, 'b>> for Error {
+    fn from(error: CompoundTagError<'a, 'b>) -> Self {
+        Error::Path(error.with_path(&NbtPath::new()))
+    }
+}
+
+impl From<PathError> for io::Error {
+    fn from(error: PathError) -> Self {
+        let kind = match error {
+            PathError::TagNotFound { .. } => io::ErrorKind::NotFound,
+            PathError::TagWrongType { .. } => io::ErrorKind::InvalidData,
+        };
+
+        io::Error::new(kind, error)
+    }
+}
+
+impl<'a, 'b> From<CompoundTagError<'a, 'b>> for io::Error {
+    fn from(error: CompoundTagError<'a, 'b>) -> Self {
+        error.with_path(&NbtPath::new()).into()
+    }
+}
+
+impl From<TagDecodeError> for io::Error {
+    /// I/O errors keep their kind, the original error stays available as source.
+    fn from(error: TagDecodeError) -> Self {
+        let kind = match &error {
+            TagDecodeError::IOError { io_error, .. } => io_error.kind(),
+            _ => io::ErrorKind::InvalidData,
+        };
+
+        io::Error::new(kind, error)
+    }
+}
+
+impl From<TagEncodeError> for io::Error {
+    fn from(error: TagEncodeError) -> Self {
+        match error {
+            TagEncodeError::IOError { io_error } => io_error,
+            error => io::Error::new(io::ErrorKind::InvalidInput, error),
+        }
+    }
+}
+
+impl From<Error> for io::Error {
+    fn from(error: Error) -> Self {
+        match error {
+            Error::Decode(error) => error.into(),
+            Error::Encode(error) => error.into(),
+            Error::Path(error) => error.into(),
+            Error::ParsePath(error) => io::Error::new(io::ErrorKind::InvalidInput, error),
+        }
+    }
+}
+
+/// Helpers to add context to getter results of [`CompoundTag`](crate::CompoundTag).
+pub trait ResultExt<T> {
+    /// Converts error into [`PathError`] with provided name of compound tag prefixed to its path.
+    ///
+    /// # Example
+    /// ```
+    /// use nbt::{CompoundTag, ResultExt};
+    /// use std::io;
+    ///
+    /// fn health(player: &CompoundTag) -> io::Result<f32> {
+    ///     Ok(player.get_f32("Health").context("Player")?)
+    /// }
+    ///
+    /// let error = health(&CompoundTag::new()).unwrap_err();
+    /// assert_eq!(error.kind(), io::ErrorKind::NotFound);
+    /// assert_eq!(error.to_string(), "Tag Player.Health not found");
+    /// ```
+    fn context(self, name: &str) -> Result<T, PathError>;
+
+    /// Converts error into [`PathError`] with provided path of compound tag prefixed to its path.
+    fn path_context(self, parent: &NbtPath) -> Result<T, PathError>;
+}
+
+impl<'a, 'b, T> ResultExt<T> for Result<T, CompoundTagError<'a, 'b>> {
+    fn context(self, name: &str) -> Result<T, PathError> {
+        self.path_context(&NbtPath::new().join_key(name))
+    }
+
+    fn path_context(self, parent: &NbtPath) -> Result<T, PathError> {
+        self.map_err(|error| error.with_path(parent))
+    }
+}
+
+#[test]
+fn test_error() {
+    use crate::decode::read_compound_tag;
+    use crate::encode::write_compound_tag;
+    use crate::CompoundTag;
+    use std::io::Cursor;
+
+    fn copy_name(src: &[u8]) -> Result<Vec<u8>> {
+        let root_tag = read_compound_tag(&mut Cursor::new(src))?;
+        let name = root_tag.get_str("name")?;
+
+        let mut copy = CompoundTag::new();
+        copy.insert_str("name", name);
+
+        let mut vec = Vec::new();
+        write_compound_tag(&mut vec, &copy)?;
+
+        Ok(vec)
+    }
+
+    assert!(copy_name(include_bytes!("../test/binary/hello_world.dat")).is_ok());
+
+    match copy_name(include_bytes!("../test/binary/servers.dat")) {
+        Err(Error::Path(error)) => assert_eq!(error.to_string(), "Tag name not found"),
+        _ => panic!(),
+    }
+
+    match copy_name(&[]) {
+        Err(error) => assert_eq!(error.kind(), ErrorKind::Io),
+        _ => panic!(),
+    }
+}
+
+#[test]
+fn test_io_error() {
+    use crate::decode::read_compound_tag;
+    use crate::CompoundTag;
+    use std::io::Cursor;
+
+    fn read_name(src: &[u8]) -> io::Result<String> {
+        let root_tag = read_compound_tag(&mut Cursor::new(src))?;
+        let name = root_tag.get_str("name")?;
+
+        Ok(name.to_string())
+    }
+
+    assert_eq!(
+        read_name(include_bytes!("../test/binary/hello_world.dat")).unwrap(),
+        "Bananrama"
+    );
+    assert_eq!(
+        read_name(&[]).unwrap_err().kind(),
+        io::ErrorKind::UnexpectedEof
+    );
+
+    let error = read_name(include_bytes!("../test/binary/servers.dat")).unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::NotFound);
+    assert!(error.get_ref().unwrap().is::<PathError>());
+
+    let mut compound_tag = CompoundTag::new();
+    compound_tag.insert_i8("name", 1);
+
+    let error = compound_tag.get_str("name").context("Root").unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Tag Root.name has type TAG_Byte, expected TAG_String"
+    );
+}
@@ -0,0 +1,256 @@
+//! Conversions from and to values of other NBT crates.
+//!
+//! Conversions into other crates return the first tag which can't be represented there
+//! as an error, e.g. [`Tag::Unknown`].
+#[cfg(feature = "hematite_nbt")]
+use crate::decode::read_compound_tag;
+#[cfg(feature = "hematite_nbt")]
+use crate::CompoundTag;
+use crate::Tag;
+use std::convert::TryFrom;
+#[cfg(feature = "hematite_nbt")]
+use std::io::{self, Cursor};
+
+#[cfg(feature = "fastnbt")]
+impl From<fastnbt::Value> for Tag {
+    /// Converts `fastnbt` value, keys of compound tags are in arbitrary order.
+    fn from(value: fastnbt::Value) -> Self {
+        use fastnbt::Value;
+
+        match value {
+            Value::Byte(value) => Tag::Byte(value),
+            Value::Short(value) => Tag::Short(value),
+            Value::Int(value) => Tag::Int(value),
+            Value::Long(value) => Tag::Long(value),
+            Value::Float(value) => Tag::Float(value),
+            Value::Double(value) => Tag::Double(value),
+            Value::String(value) => Tag::String(value),
+            Value::ByteArray(value) => Tag::ByteArray(value.into_inner()),
+            Value::IntArray(value) => Tag::IntArray(value.into_inner()),
+            Value::LongArray(value) => Tag::LongArray(value.into_inner()),
+            Value::List(value) => Tag::List(value.into_iter().map(Tag::from).collect()),
+            Value::Compound(value) => Tag::Compound(
+                value
+                    .into_iter()
+                    .map(|(name, value)| (name, Tag::from(value)))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "fastnbt")]
+impl TryFrom<Tag> for fastnbt::Value {
+    type Error = Tag;
+
+    /// Converts tag into `fastnbt` value, failing on unknown tags.
+    fn try_from(tag: Tag) -> Result<Self, Self::Error> {
+        use fastnbt::{ByteArray, IntArray, LongArray, Value};
+
+        let value = match tag {
+            Tag::Byte(value) => Value::Byte(value),
+            Tag::Short(value) => Value::Short(value),
+            Tag::Int(value) => Value::Int(value),
+            Tag::Long(value) => Value::Long(value),
+            Tag::Float(value) => Value::Float(value),
+            Tag::Double(value) => Value::Double(value),
+            Tag::ByteArray(value) => Value::ByteArray(ByteArray::new(value)),
+            Tag::String(value) => Value::String(value),
+            Tag::List(value) => Value::List(
+                value
+                    .into_iter()
+                    .map(Value::try_from)
+                    .collect::<Result<_, _>>()?,
+            ),
+            Tag::Compound(value) => Value::Compound(
+                value
+                    .into_iter()
+                    .map(|(name, tag)| Ok((name, Value::try_from(tag)?)))
+                    .collect::<Result<_, Tag>>()?,
+            ),
+            Tag::IntArray(value) => Value::IntArray(IntArray::new(value)),
+            Tag::LongArray(value) => Value::LongArray(LongArray::new(value)),
+            tag @ Tag::Unknown { .. } => return Err(tag),
+        };
+
+        Ok(value)
+    }
+}
+
+#[cfg(feature = "hematite_nbt")]
+impl From<hematite_nbt::Value> for Tag {
+    fn from(value: hematite_nbt::Value) -> Self {
+        use hematite_nbt::Value;
+
+        match value {
+            Value::Byte(value) => Tag::Byte(value),
+            Value::Short(value) => Tag::Short(value),
+            Value::Int(value) => Tag::Int(value),
+            Value::Long(value) => Tag::Long(value),
+            Value::Float(value) => Tag::Float(value),
+            Value::Double(value) => Tag::Double(value),
+            Value::ByteArray(value) => Tag::ByteArray(value),
+            Value::String(value) => Tag::String(value),
+            Value::List(value) => Tag::List(value.into_iter().map(Tag::from).collect()),
+            Value::Compound(value) => Tag::Compound(
+                value
+                    .into_iter()
+                    .map(|(name, value)| (name, Tag::from(value)))
+                    .collect(),
+            ),
+            Value::IntArray(value) => Tag::IntArray(value),
+            Value::LongArray(value) => Tag::LongArray(value),
+        }
+    }
+}
+
+#[cfg(feature = "hematite_nbt")]
+impl TryFrom<Tag> for hematite_nbt::Value {
+    type Error = Tag;
+
+    /// Converts tag into `hematite_nbt` value, failing on unknown tags and lists
+    /// with elements of different types.
+    fn try_from(tag: Tag) -> Result<Self, Self::Error> {
+        use hematite_nbt::Value;
+
+        let value = match tag {
+            Tag::Byte(value) => Value::Byte(value),
+            Tag::Short(value) => Value::Short(value),
+            Tag::Int(value) => Value::Int(value),
+            Tag::Long(value) => Value::Long(value),
+            Tag::Float(value) => Value::Float(value),
+            Tag::Double(value) => Value::Double(value),
+            Tag::ByteArray(value) => Value::ByteArray(value),
+            Tag::String(value) => Value::String(value),
+            Tag::List(value) => {
+                if value
+                    .windows(2)
+                    .any(|pair| pair[0].type_id() != pair[1].type_id())
+                {
+                    return Err(Tag::List(value));
+                }
+
+                Value::List(
+                    value
+                        .into_iter()
+                        .map(Value::try_from)
+                        .collect::<Result<_, _>>()?,
+                )
+            }
+            Tag::Compound(value) => Value::Compound(
+                value
+                    .into_iter()
+                    .map(|(name, tag)| Ok((name, Value::try_from(tag)?)))
+                    .collect::<Result<_, Tag>>()?,
+            ),
+            Tag::IntArray(value) => Value::IntArray(value),
+            Tag::LongArray(value) => Value::LongArray(value),
+            tag @ Tag::Unknown { .. } => return Err(tag),
+        };
+
+        Ok(value)
+    }
+}
+
+#[cfg(feature = "hematite_nbt")]
+impl TryFrom<CompoundTag> for hematite_nbt::Blob {
+    type Error = Tag;
+
+    /// Converts compound tag into `hematite_nbt` blob named as the compound tag.
+    fn try_from(compound_tag: CompoundTag) -> Result<Self, Self::Error> {
+        let mut blob = match &compound_tag.name {
+            Some(name) => hematite_nbt::Blob::named(name.as_str()),
+            None => hematite_nbt::Blob::new(),
+        };
+
+        for (name, tag) in compound_tag {
+            let value = hematite_nbt::Value::try_from(tag)?;
+            blob.insert(name, value).expect("Lists are checked above");
+        }
+
+        Ok(blob)
+    }
+}
+
+#[cfg(feature = "hematite_nbt")]
+impl TryFrom<&hematite_nbt::Blob> for CompoundTag {
+    type Error = hematite_nbt::Error;
+
+    /// Converts `hematite_nbt` blob by encoding and decoding it, as blob doesn't expose its tags.
+    ///
+    /// Characters outside of the Basic Multilingual Plane are replaced, because `hematite_nbt`
+    /// writes them in modified UTF-8.
+    fn try_from(blob: &hematite_nbt::Blob) -> Result<Self, Self::Error> {
+        let mut bytes = Vec::with_capacity(blob.len_bytes());
+        blob.to_writer(&mut bytes)?;
+
+        read_compound_tag(&mut Cursor::new(bytes)).map_err(|error| io::Error::from(error).into())
+    }
+}
+
+#[test]
+#[cfg(feature = "fastnbt")]
+fn test_fastnbt() {
+    use crate::CompoundTag;
+
+    let mut compound_tag = CompoundTag::new();
+    compound_tag.insert_i8("byte", 1);
+    compound_tag.insert_f32_vec("floats", vec![0.5]);
+    compound_tag.insert_i64_vec("longs", vec![1, 2]);
+    compound_tag.insert_compound_tag("compound", CompoundTag::new());
+
+    let value = fastnbt::Value::try_from(Tag::Compound(compound_tag.clone())).unwrap();
+    let bytes = fastnbt::to_bytes(&value).unwrap();
+    let value: fastnbt::Value = fastnbt::from_bytes(&bytes).unwrap();
+
+    assert!(compound_tag.equals_with(
+        &CompoundTag::try_from(Tag::from(value)).unwrap(),
+        &crate::compare::EqOptions {
+            ignore_key_order: true,
+            ..Default::default()
+        }
+    ));
+
+    let unknown = Tag::Unknown {
+        id: 13,
+        bytes: Vec::new(),
+    };
+
+    assert_eq!(
+        fastnbt::Value::try_from(Tag::List(vec![unknown.clone()])),
+        Err(unknown)
+    );
+}
+
+#[test]
+#[cfg(feature = "hematite_nbt")]
+fn test_hematite_nbt() {
+    let mut compound_tag = CompoundTag::named("root");
+    compound_tag.insert_i16("short", 1);
+    compound_tag.insert_str_vec("strings", vec!["a", "ä"]);
+    compound_tag.insert_i32_vec("ints", vec![1, 2]);
+    compound_tag.insert_compound_tag("compound", CompoundTag::new());
+
+    let blob = hematite_nbt::Blob::try_from(compound_tag.clone()).unwrap();
+    assert_eq!(blob["short"], hematite_nbt::Value::Short(1));
+
+    let actual = CompoundTag::try_from(&blob).unwrap();
+    assert_eq!(actual.name.as_deref(), Some("root"));
+    assert!(compound_tag.equals_with(
+        &actual,
+        &crate::compare::EqOptions {
+            ignore_key_order: true,
+            ..Default::default()
+        }
+    ));
+
+    let mixed = Tag::List(vec![Tag::Byte(1), Tag::Int(1)]);
+    let mut compound_tag = CompoundTag::new();
+    compound_tag.insert("mixed", mixed.clone());
+
+    assert_eq!(hematite_nbt::Blob::try_from(compound_tag), Err(mixed));
+    assert_eq!(
+        Tag::from(hematite_nbt::Value::List(vec![hematite_nbt::Value::Int(1)])),
+        Tag::List(vec![Tag::Int(1)])
+    );
+}
@@ -0,0 +1,372 @@
+//! Lossless conversion of tags to JSON and back.
+//!
+//! Strings are kept as JSON strings and compound tags as JSON objects. All other tags are
+//! wrapped into objects with type name under `__nbt` key and value under `v` key:
+//!
+//! | Tag          | JSON                                         |
+//! |--------------|----------------------------------------------|
+//! | `Byte`       | `{"__nbt":"byte","v":1}`                     |
+//! | `Short`      | `{"__nbt":"short","v":1}`                    |
+//! | `Int`        | `{"__nbt":"int","v":1}`                      |
+//! | `Long`       | `{"__nbt":"long","v":1}`                     |
+//! | `Float`      | `{"__nbt":"float","v":0.5}`                  |
+//! | `Double`     | `{"__nbt":"double","v":0.5}`                 |
+//! | `ByteArray`  | `{"__nbt":"byte_array","v":[1,2]}`           |
+//! | `IntArray`   | `{"__nbt":"int_array","v":[1,2]}`            |
+//! | `LongArray`  | `{"__nbt":"long_array","v":[1,2]}`           |
+//! | `List`       | `{"__nbt":"list","v":["a","b"]}`             |
+//! | `Unknown`    | `{"__nbt":"unknown","id":13,"v":[1,2]}`      |
+//!
+//! Floating point values which are not finite are written as `"NaN"`, `"Infinity"` and
+//! `"-Infinity"` strings. Compound tags containing `__nbt` key are wrapped as well,
+//! as `{"__nbt":"compound","v":{...}}`. Order of keys is kept, name of the root compound tag is not.
+//!
+//! # Example
+//! ```
+//! use nbt::json::{compound_tag_from_json, compound_tag_to_json};
+//! use nbt::CompoundTag;
+//!
+//! let mut compound_tag = CompoundTag::new();
+//! compound_tag.insert_i8("Count", 1);
+//! compound_tag.insert_str("id", "minecraft:stone");
+//! compound_tag.insert_i32_vec("pos", vec![1, 2, 3]);
+//!
+//! let json = compound_tag_to_json(&compound_tag).to_string();
+//! assert_eq!(
+//!     json,
+//!     r#"{"Count":{"__nbt":"byte","v":1},"id":"minecraft:stone","pos":{"__nbt":"int_array","v":[1,2,3]}}"#
+//! );
+//!
+//! let value = serde_json::from_str(&json).unwrap();
+//! assert_eq!(compound_tag_from_json(&value).unwrap(), compound_tag);
+//! ```
+use crate::nbtpath::{fmt_path, NbtPath};
+use crate::{CompoundTag, Tag, TagType};
+use serde_json::{Map, Value};
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+
+const TYPE_KEY: &str = "__nbt";
+const VALUE_KEY: &str = "v";
+
+/// Converts compound tag to JSON object.
+pub fn compound_tag_to_json(compound_tag: &CompoundTag) -> Value {
+    let mut object = Map::new();
+
+    for (name, tag) in compound_tag.iter() {
+        object.insert(name.to_string(), tag_to_json(tag));
+    }
+
+    if compound_tag.contains_key(TYPE_KEY) {
+        return typed("compound", Value::Object(object));
+    }
+
+    Value::Object(object)
+}
+
+/// Converts tag to JSON value.
+pub fn tag_to_json(tag: &Tag) -> Value {
+    match tag {
+        Tag::Byte(value) => typed("byte", Value::from(*value)),
+        Tag::Short(value) => typed("short", Value::from(*value)),
+        Tag::Int(value) => typed("int", Value::from(*value)),
+        Tag::Long(value) => typed("long", Value::from(*value)),
+        Tag::Float(value) => typed("float", float_to_json(f64::from(*value))),
+        Tag::Double(value) => typed("double", float_to_json(*value)),
+        Tag::ByteArray(value) => typed("byte_array", Value::from(value.as_slice())),
+        Tag::String(value) => Value::from(value.as_str()),
+        Tag::List(value) => typed("list", value.iter().map(tag_to_json).collect()),
+        Tag::Compound(value) => compound_tag_to_json(value),
+        Tag::IntArray(value) => typed("int_array", Value::from(value.as_slice())),
+        Tag::LongArray(value) => typed("long_array", Value::from(value.as_slice())),
+        Tag::Unknown { id, bytes } => {
+            let mut object = Map::new();
+            object.insert(TYPE_KEY.to_string(), Value::from("unknown"));
+            object.insert("id".to_string(), Value::from(*id));
+            object.insert(VALUE_KEY.to_string(), Value::from(bytes.as_slice()));
+
+            Value::Object(object)
+        }
+    }
+}
+
+/// Converts JSON object created by [`compound_tag_to_json`] back to compound tag.
+pub fn compound_tag_from_json(value: &Value) -> Result<CompoundTag, JsonError> {
+    let mut path = NbtPath::new();
+
+    match value_to_tag(value, &mut path)? {
+        Tag::Compound(compound_tag) => Ok(compound_tag),
+        _ => Err(JsonError::UnexpectedValue { path }),
+    }
+}
+
+/// Converts JSON value created by [`tag_to_json`] back to tag.
+pub fn tag_from_json(value: &Value) -> Result<Tag, JsonError> {
+    value_to_tag(value, &mut NbtPath::new())
+}
+
+fn typed(type_name: &str, value: Value) -> Value {
+    let mut object = Map::new();
+    object.insert(TYPE_KEY.to_string(), Value::from(type_name));
+    object.insert(VALUE_KEY.to_string(), value);
+
+    Value::Object(object)
+}
+
+fn float_to_json(value: f64) -> Value {
+    if value.is_nan() {
+        Value::from("NaN")
+    } else if value == f64::INFINITY {
+        Value::from("Infinity")
+    } else if value == f64::NEG_INFINITY {
+        Value::from("-Infinity")
+    } else {
+        Value::from(value)
+    }
+}
+
+fn float_from_json(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(number) => number.as_f64(),
+        Value::String(string) => match string.as_str() {
+            "NaN" => Some(f64::NAN),
+            "Infinity" => Some(f64::INFINITY),
+            "-Infinity" => Some(f64::NEG_INFINITY),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn int_from_json<T: TryFrom<i64>>(value: &Value) -> Option<T> {
+    value.as_i64().and_then(|value| T::try_from(value).ok())
+}
+
+fn ints_from_json<T: TryFrom<i64>>(value: &Value) -> Option<Vec<T>> {
+    value.as_array()?.iter().map(int_from_json).collect()
+}
+
+fn value_to_tag(value: &Value, path: &mut NbtPath) -> Result<Tag, JsonError> {
+    let object = match value {
+        Value::String(value) => return Ok(Tag::String(value.clone())),
+        Value::Object(object) => object,
+        _ => return Err(JsonError::UnexpectedValue { path: path.clone() }),
+    };
+
+    let type_name = match object.get(TYPE_KEY) {
+        Some(Value::String(type_name)) => type_name.as_str(),
+        Some(_) => return Err(JsonError::UnexpectedValue { path: path.clone() }),
+        None => return object_to_compound_tag(object, path).map(Tag::Compound),
+    };
+
+    let value = object
+        .get(VALUE_KEY)
+        .ok_or_else(|| JsonError::UnexpectedValue { path: path.clone() })?;
+
+    let (tag_type, tag) = match type_name {
+        "byte" => (TagType::Byte, int_from_json(value).map(Tag::Byte)),
+        "short" => (TagType::Short, int_from_json(value).map(Tag::Short)),
+        "int" => (TagType::Int, int_from_json(value).map(Tag::Int)),
+        "long" => (TagType::Long, value.as_i64().map(Tag::Long)),
+        "float" => (
+            TagType::Float,
+            float_from_json(value).map(|value| Tag::Float(value as f32)),
+        ),
+        "double" => (TagType::Double, float_from_json(value).map(Tag::Double)),
+        "byte_array" => (
+            TagType::ByteArray,
+            ints_from_json(value).map(Tag::ByteArray),
+        ),
+        "int_array" => (TagType::IntArray, ints_from_json(value).map(Tag::IntArray)),
+        "long_array" => (
+            TagType::LongArray,
+            ints_from_json(value).map(Tag::LongArray),
+        ),
+        "list" => {
+            let values = value.as_array().ok_or_else(|| JsonError::InvalidValue {
+                path: path.clone(),
+                tag_type: TagType::List,
+            })?;
+
+            let mut tags = Vec::with_capacity(values.len());
+
+            for (index, value) in values.iter().enumerate() {
+                path.push_index(index);
+                tags.push(value_to_tag(value, path)?);
+                path.pop();
+            }
+
+            return Ok(Tag::List(tags));
+        }
+        "compound" => {
+            let object = value.as_object().ok_or_else(|| JsonError::InvalidValue {
+                path: path.clone(),
+                tag_type: TagType::Compound,
+            })?;
+
+            return object_to_compound_tag(object, path).map(Tag::Compound);
+        }
+        "unknown" => {
+            let id = object.get("id").and_then(int_from_json);
+            let tag_type = TagType::Unknown(id.unwrap_or_default());
+            let tag = id
+                .zip(ints_from_json(value))
+                .map(|(id, bytes)| Tag::Unknown { id, bytes });
+
+            (tag_type, tag)
+        }
+        _ => {
+            return Err(JsonError::UnknownType {
+                path: path.clone(),
+                type_name: type_name.to_string(),
+            })
+        }
+    };
+
+    tag.ok_or_else(|| JsonError::InvalidValue {
+        path: path.clone(),
+        tag_type,
+    })
+}
+
+fn object_to_compound_tag(
+    object: &Map<String, Value>,
+    path: &mut NbtPath,
+) -> Result<CompoundTag, JsonError> {
+    let mut compound_tag = CompoundTag::new();
+
+    for (name, value) in object {
+        path.push_key(name.as_str());
+        compound_tag.insert(name.as_str(), value_to_tag(value, path)?);
+        path.pop();
+    }
+
+    Ok(compound_tag)
+}
+
+/// Possible types of errors while converting JSON back to tags.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JsonError {
+    /// JSON value can't be a tag, e.g. a number without type or `null`.
+    UnexpectedValue { path: NbtPath },
+    /// Type name under `__nbt` key is not recognized.
+    UnknownType { path: NbtPath, type_name: String },
+    /// Value doesn't fit into the tag type, e.g. byte out of range.
+    InvalidValue { path: NbtPath, tag_type: TagType },
+}
+
+impl JsonError {
+    /// Returns path of the problematic tag.
+    pub fn path(&self) -> &NbtPath {
+        match self {
+            JsonError::UnexpectedValue { path } => path,
+            JsonError::UnknownType { path, .. } => path,
+            JsonError::InvalidValue { path, .. } => path,
+        }
+    }
+}
+
+impl Error for JsonError {}
+
+impl Display for JsonError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            JsonError::UnexpectedValue { path } => {
+                write!(f, "Tag")?;
+                fmt_path(f, path)?;
+                write!(f, " has unexpected JSON value")
+            }
+            JsonError::UnknownType { path, type_name } => {
+                write!(f, "Tag")?;
+                fmt_path(f, path)?;
+                write!(f, " has unknown type {:?}", type_name)
+            }
+            JsonError::InvalidValue { path, tag_type } => {
+                write!(f, "Tag")?;
+                fmt_path(f, path)?;
+                write!(f, " has invalid value for {}", tag_type)
+            }
+        }
+    }
+}
+
+#[test]
+fn test_json_round_trip() {
+    use crate::decode::read_gzip_compound_tag;
+    use std::io::Cursor;
+
+    let mut cursor = Cursor::new(include_bytes!("../test/binary/bigtest.dat").to_vec());
+    let mut compound_tag = read_gzip_compound_tag(&mut cursor).unwrap();
+
+    let mut special = CompoundTag::new();
+    special.insert_str(TYPE_KEY, "not a type");
+    special.insert_f32("nan", f32::NAN);
+    special.insert_f64_vec("infinities", vec![f64::INFINITY, f64::NEG_INFINITY, -0.0]);
+    special.insert_i64("max", i64::MAX);
+    special.insert_compound_tag_vec("empty", Vec::new());
+    special.insert(
+        "unknown",
+        Tag::Unknown {
+            id: 13,
+            bytes: vec![0, 255],
+        },
+    );
+
+    compound_tag.insert_compound_tag("special", special);
+
+    let json = compound_tag_to_json(&compound_tag).to_string();
+    let value: Value = serde_json::from_str(&json).unwrap();
+    let actual = compound_tag_from_json(&value).unwrap();
+
+    let special = actual.get_compound_tag("special").unwrap();
+    assert!(special.get_f32("nan").unwrap().is_nan());
+    assert_eq!(special.get_str(TYPE_KEY).unwrap(), "not a type");
+    assert!(special.get_f64_vec("infinities").unwrap()[2].is_sign_negative());
+    assert_eq!(value["special"][TYPE_KEY], "compound");
+
+    // NaN is not equal to itself, so compare the rest of the tags.
+    let mut expected = compound_tag;
+    let mut actual = actual;
+
+    for compound_tag in [&mut expected, &mut actual] {
+        if let Some(Tag::Compound(special)) = compound_tag.tags.get_mut("special") {
+            special.tags.remove("nan");
+        }
+    }
+
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn test_json_errors() {
+    let error = |json: &str| {
+        let value: Value = serde_json::from_str(json).unwrap();
+        compound_tag_from_json(&value).unwrap_err()
+    };
+
+    assert_eq!(
+        error(r#"{"a":{"b":[1]}}"#).to_string(),
+        "Tag a.b has unexpected JSON value"
+    );
+    assert_eq!(
+        error(r#"{"a":{"__nbt":"list","v":["x",{"__nbt":"byte","v":300}]}}"#).to_string(),
+        "Tag a[1] has invalid value for TAG_Byte"
+    );
+    assert_eq!(
+        error(r#"{"a":{"__nbt":"uint","v":1}}"#),
+        JsonError::UnknownType {
+            path: "a".parse().unwrap(),
+            type_name: "uint".to_string(),
+        }
+    );
+    assert_eq!(error(r#""a""#).path(), &NbtPath::new());
+    assert_eq!(error(r#""a""#).to_string(), "Tag has unexpected JSON value");
+    assert_eq!(
+        tag_from_json(&serde_json::json!({"__nbt": "float", "v": "Inf"})),
+        Err(JsonError::InvalidValue {
+            path: NbtPath::new(),
+            tag_type: TagType::Float,
+        })
+    );
+}
@@ -49,18 +49,54 @@
 //! let mut vec = Vec::new();
 //! write_compound_tag(&mut vec, &root_tag).unwrap();
 //! ```
-use linked_hash_map::LinkedHashMap;
+// Derived code refers to `::nbt`, including in tests of this crate.
+#[cfg(all(test, feature = "derive"))]
+extern crate self as nbt;
+
+use crate::map::TagMap;
+use std::borrow::Borrow;
 use std::fmt::{Debug, Display, Formatter};
 use std::{
     convert::{TryFrom, TryInto},
     fmt,
 };
 
+#[cfg(feature = "cbor")]
+pub mod cbor;
+pub mod compare;
 pub mod decode;
+mod derive;
 pub mod encode;
+mod error;
+#[cfg(any(feature = "fastnbt", feature = "hematite_nbt"))]
+mod interop;
+#[cfg(feature = "serde_json")]
+pub mod json;
+mod list;
+mod map;
+pub mod minecraft;
+#[cfg(feature = "msgpack")]
+pub mod msgpack;
+pub mod nbtpath;
+mod stats;
+pub mod transform;
+mod walk;
+#[cfg(feature = "xml")]
+pub mod xml;
+#[cfg(feature = "yaml")]
+pub mod yaml;
+
+pub use crate::derive::{NbtCompound, NbtField};
+pub use crate::error::{Error, ErrorKind, Result, ResultExt};
+pub use crate::list::{ListElement, ListTag};
+pub use crate::stats::TagStats;
+pub use crate::walk::Walk;
+#[cfg(feature = "derive")]
+pub use nbt_derive::NbtCompound;
 
 /// Possible types of tags and they payload.
 #[derive(Debug, Clone)]
+#[non_exhaustive]
 pub enum Tag {
     Byte(i8),
     Short(i16),
@@ -74,40 +110,467 @@ pub enum Tag {
     Compound(CompoundTag),
     IntArray(Vec<i32>),
     LongArray(Vec<i64>),
+    /// Tag of type not known to this crate with its raw payload, only produced when
+    /// [`DecodeOptions::unknown_tag_reader`](crate::decode::DecodeOptions::unknown_tag_reader)
+    /// is set and written back as is.
+    Unknown {
+        id: u8,
+        bytes: Vec<u8>,
+    },
+}
+
+/// Possible types of tags.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TagType {
+    Byte,
+    Short,
+    Int,
+    Long,
+    Float,
+    Double,
+    ByteArray,
+    String,
+    List,
+    Compound,
+    IntArray,
+    LongArray,
+    /// Type not known to this crate with provided type id.
+    Unknown(u8),
+}
+
+impl TagType {
+    /// Returns type id as written in binary format.
+    pub fn id(self) -> u8 {
+        match self {
+            TagType::Byte => 1,
+            TagType::Short => 2,
+            TagType::Int => 3,
+            TagType::Long => 4,
+            TagType::Float => 5,
+            TagType::Double => 6,
+            TagType::ByteArray => 7,
+            TagType::String => 8,
+            TagType::List => 9,
+            TagType::Compound => 10,
+            TagType::IntArray => 11,
+            TagType::LongArray => 12,
+            TagType::Unknown(id) => id,
+        }
+    }
+
+    /// Returns tag type for provided type id or `None` if it is not recognized.
+    ///
+    /// [`TagType::Unknown`] is never returned.
+    pub fn from_id(id: u8) -> Option<TagType> {
+        match id {
+            1 => Some(TagType::Byte),
+            2 => Some(TagType::Short),
+            3 => Some(TagType::Int),
+            4 => Some(TagType::Long),
+            5 => Some(TagType::Float),
+            6 => Some(TagType::Double),
+            7 => Some(TagType::ByteArray),
+            8 => Some(TagType::String),
+            9 => Some(TagType::List),
+            10 => Some(TagType::Compound),
+            11 => Some(TagType::IntArray),
+            12 => Some(TagType::LongArray),
+            _ => None,
+        }
+    }
+
+    /// Returns type name as used in NBT specification (e.g. `TAG_Byte`).
+    pub fn name(self) -> &'static str {
+        match self {
+            TagType::Byte => "TAG_Byte",
+            TagType::Short => "TAG_Short",
+            TagType::Int => "TAG_Int",
+            TagType::Long => "TAG_Long",
+            TagType::Float => "TAG_Float",
+            TagType::Double => "TAG_Double",
+            TagType::ByteArray => "TAG_Byte_Array",
+            TagType::String => "TAG_String",
+            TagType::List => "TAG_List",
+            TagType::Compound => "TAG_Compound",
+            TagType::IntArray => "TAG_Int_Array",
+            TagType::LongArray => "TAG_Long_Array",
+            TagType::Unknown(_) => "TAG_Unknown",
+        }
+    }
+
+    /// Returns empty or zero value tag of this type.
+    ///
+    /// # Example
+    /// ```
+    /// use nbt::{Tag, TagType};
+    ///
+    /// assert_eq!(TagType::Int.default_tag(), Tag::Int(0));
+    /// assert_eq!(TagType::List.default_tag(), Tag::List(Vec::new()));
+    /// ```
+    pub fn default_tag(self) -> Tag {
+        match self {
+            TagType::Byte => Tag::Byte(0),
+            TagType::Short => Tag::Short(0),
+            TagType::Int => Tag::Int(0),
+            TagType::Long => Tag::Long(0),
+            TagType::Float => Tag::Float(0.0),
+            TagType::Double => Tag::Double(0.0),
+            TagType::ByteArray => Tag::ByteArray(Vec::new()),
+            TagType::String => Tag::String(String::new()),
+            TagType::List => Tag::List(Vec::new()),
+            TagType::Compound => Tag::Compound(CompoundTag::new()),
+            TagType::IntArray => Tag::IntArray(Vec::new()),
+            TagType::LongArray => Tag::LongArray(Vec::new()),
+            TagType::Unknown(id) => Tag::Unknown {
+                id,
+                bytes: Vec::new(),
+            },
+        }
+    }
+
+    /// Returns true for integer and floating point types.
+    pub fn is_numeric(self) -> bool {
+        matches!(
+            self,
+            TagType::Byte
+                | TagType::Short
+                | TagType::Int
+                | TagType::Long
+                | TagType::Float
+                | TagType::Double
+        )
+    }
+
+    /// Returns true for byte, int and long array types.
+    pub fn is_array(self) -> bool {
+        matches!(
+            self,
+            TagType::ByteArray | TagType::IntArray | TagType::LongArray
+        )
+    }
+}
+
+impl Display for TagType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
 }
 
 impl Tag {
-    fn type_id(&self) -> u8 {
+    /// Returns type of this tag.
+    pub fn tag_type(&self) -> TagType {
         match self {
-            Tag::Byte(_) => 1,
-            Tag::Short(_) => 2,
-            Tag::Int(_) => 3,
-            Tag::Long(_) => 4,
-            Tag::Float(_) => 5,
-            Tag::Double(_) => 6,
-            Tag::ByteArray(_) => 7,
-            Tag::String(_) => 8,
-            Tag::List(_) => 9,
-            Tag::Compound(_) => 10,
-            Tag::IntArray(_) => 11,
-            Tag::LongArray(_) => 12,
+            Tag::Byte(_) => TagType::Byte,
+            Tag::Short(_) => TagType::Short,
+            Tag::Int(_) => TagType::Int,
+            Tag::Long(_) => TagType::Long,
+            Tag::Float(_) => TagType::Float,
+            Tag::Double(_) => TagType::Double,
+            Tag::ByteArray(_) => TagType::ByteArray,
+            Tag::String(_) => TagType::String,
+            Tag::List(_) => TagType::List,
+            Tag::Compound(_) => TagType::Compound,
+            Tag::IntArray(_) => TagType::IntArray,
+            Tag::LongArray(_) => TagType::LongArray,
+            Tag::Unknown { id, .. } => TagType::Unknown(*id),
         }
     }
 
+    fn type_id(&self) -> u8 {
+        self.tag_type().id()
+    }
+
     fn type_name(&self) -> &'static str {
+        self.tag_type().name()
+    }
+}
+
+macro_rules! define_is (
+    ($tag: ident, $name: ident) => (
+        pub fn $name(&self) -> bool {
+            matches!(self, Tag::$tag(_))
+        }
+    );
+);
+
+/// Predicates checking tag type.
+impl Tag {
+    define_is!(Byte, is_byte);
+    define_is!(Short, is_short);
+    define_is!(Int, is_int);
+    define_is!(Long, is_long);
+    define_is!(Float, is_float);
+    define_is!(Double, is_double);
+    define_is!(ByteArray, is_byte_array);
+    define_is!(String, is_string);
+    define_is!(List, is_list);
+    define_is!(Compound, is_compound);
+    define_is!(IntArray, is_int_array);
+    define_is!(LongArray, is_long_array);
+
+    /// Returns true if tag is one of integer or floating point tags.
+    pub fn is_numeric(&self) -> bool {
+        self.tag_type().is_numeric()
+    }
+
+    /// Returns true if tag is one of byte, int or long array tags.
+    pub fn is_array(&self) -> bool {
+        self.tag_type().is_array()
+    }
+}
+
+macro_rules! define_as_copy (
+    ($type: ty, $tag: ident, $getter_name: ident, $mut_getter_name: ident) => (
+        pub fn $getter_name(&self) -> Option<$type> {
+            match self {
+                Tag::$tag(value) => Some(*value),
+                _ => None,
+            }
+        }
+
+        pub fn $mut_getter_name(&mut self) -> Option<&mut $type> {
+            match self {
+                Tag::$tag(value) => Some(value),
+                _ => None,
+            }
+        }
+    );
+);
+
+macro_rules! define_as_ref (
+    ($type: ty, $tag: ident, $getter_name: ident, $mut_getter_name: ident) => (
+        pub fn $getter_name(&self) -> Option<&$type> {
+            match self {
+                Tag::$tag(value) => Some(value),
+                _ => None,
+            }
+        }
+
+        pub fn $mut_getter_name(&mut self) -> Option<&mut $type> {
+            match self {
+                Tag::$tag(value) => Some(value),
+                _ => None,
+            }
+        }
+    );
+);
+
+/// Accessors returning tag value if tag has the expected type.
+impl Tag {
+    define_as_copy!(i8, Byte, as_i8, as_i8_mut);
+    define_as_copy!(i16, Short, as_i16, as_i16_mut);
+    define_as_copy!(i32, Int, as_i32, as_i32_mut);
+    define_as_copy!(i64, Long, as_i64, as_i64_mut);
+    define_as_copy!(f32, Float, as_f32, as_f32_mut);
+    define_as_copy!(f64, Double, as_f64, as_f64_mut);
+    define_as_ref!(Vec<i8>, ByteArray, as_i8_vec, as_i8_vec_mut);
+    define_as_ref!(Vec<Tag>, List, as_list, as_list_mut);
+    define_as_ref!(CompoundTag, Compound, as_compound, as_compound_mut);
+    define_as_ref!(Vec<i32>, IntArray, as_i32_vec, as_i32_vec_mut);
+    define_as_ref!(Vec<i64>, LongArray, as_i64_vec, as_i64_vec_mut);
+
+    pub fn as_bool(&self) -> Option<bool> {
+        self.as_i8().map(|value| value == 1)
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Tag::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn as_string_mut(&mut self) -> Option<&mut String> {
         match self {
-            Tag::Byte(_) => "TAG_Byte",
-            Tag::Short(_) => "TAG_Short",
-            Tag::Int(_) => "TAG_Int",
-            Tag::Long(_) => "TAG_Long",
-            Tag::Float(_) => "TAG_Float",
-            Tag::Double(_) => "TAG_Double",
-            Tag::ByteArray(_) => "TAG_Byte_Array",
-            Tag::String(_) => "TAG_String",
-            Tag::List(_) => "TAG_List",
-            Tag::Compound(_) => "TAG_Compound",
-            Tag::IntArray(_) => "TAG_Int_Array",
-            Tag::LongArray(_) => "TAG_Long_Array",
+            Tag::String(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+/// Numeric coercions.
+impl Tag {
+    /// Returns value of any numeric tag as `i64`, or `None` if tag isn't numeric.
+    ///
+    /// Floating point values are truncated toward zero and saturate at `i64` bounds,
+    /// same as `as` casts.
+    pub fn to_i64(&self) -> Option<i64> {
+        match self {
+            Tag::Byte(value) => Some(i64::from(*value)),
+            Tag::Short(value) => Some(i64::from(*value)),
+            Tag::Int(value) => Some(i64::from(*value)),
+            Tag::Long(value) => Some(*value),
+            Tag::Float(value) => Some(*value as i64),
+            Tag::Double(value) => Some(*value as i64),
+            _ => None,
+        }
+    }
+
+    /// Returns value of any numeric tag as `f64`, or `None` if tag isn't numeric.
+    ///
+    /// Long values beyond 2^53 lose precision.
+    pub fn to_f64(&self) -> Option<f64> {
+        match self {
+            Tag::Byte(value) => Some(f64::from(*value)),
+            Tag::Short(value) => Some(f64::from(*value)),
+            Tag::Int(value) => Some(f64::from(*value)),
+            Tag::Long(value) => Some(*value as f64),
+            Tag::Float(value) => Some(f64::from(*value)),
+            Tag::Double(value) => Some(*value),
+            _ => None,
+        }
+    }
+}
+
+/// Navigation through compound and list tags.
+impl Tag {
+    /// Returns tag with provided name if this tag is a compound tag.
+    pub fn get<Q: Borrow<str> + ?Sized>(&self, name: &Q) -> Option<&Tag> {
+        match self {
+            Tag::Compound(value) => value.tags.get(name.borrow()),
+            _ => None,
+        }
+    }
+
+    /// Returns mutable tag with provided name if this tag is a compound tag.
+    pub fn get_mut<Q: Borrow<str> + ?Sized>(&mut self, name: &Q) -> Option<&mut Tag> {
+        match self {
+            Tag::Compound(value) => value.tags.get_mut(name.borrow()),
+            _ => None,
+        }
+    }
+
+    /// Returns tag at provided position if this tag is a list tag.
+    pub fn get_index(&self, index: usize) -> Option<&Tag> {
+        match self {
+            Tag::List(value) => value.get(index),
+            _ => None,
+        }
+    }
+
+    /// Returns mutable tag at provided position if this tag is a list tag.
+    pub fn get_index_mut(&mut self, index: usize) -> Option<&mut Tag> {
+        match self {
+            Tag::List(value) => value.get_mut(index),
+            _ => None,
+        }
+    }
+}
+
+impl Tag {
+    /// Compares tags in a deterministic canonical order.
+    ///
+    /// Tags are ordered by type id first and then by value. Floating point values use
+    /// IEEE 754 total order, lists and arrays are compared lexicographically and compound
+    /// tags are compared by their entries sorted by name, so insertion order doesn't matter.
+    ///
+    /// # Example
+    /// ```
+    /// use nbt::Tag;
+    ///
+    /// let mut tags = vec![Tag::String("b".to_string()), Tag::Int(2), Tag::Byte(3), Tag::Int(1)];
+    /// tags.sort_by(Tag::cmp_canonical);
+    ///
+    /// assert_eq!(tags, vec![Tag::Byte(3), Tag::Int(1), Tag::Int(2), Tag::String("b".to_string())]);
+    /// ```
+    pub fn cmp_canonical(&self, other: &Tag) -> std::cmp::Ordering {
+        match (self, other) {
+            (Tag::Byte(a), Tag::Byte(b)) => a.cmp(b),
+            (Tag::Short(a), Tag::Short(b)) => a.cmp(b),
+            (Tag::Int(a), Tag::Int(b)) => a.cmp(b),
+            (Tag::Long(a), Tag::Long(b)) => a.cmp(b),
+            (Tag::Float(a), Tag::Float(b)) => a.total_cmp(b),
+            (Tag::Double(a), Tag::Double(b)) => a.total_cmp(b),
+            (Tag::ByteArray(a), Tag::ByteArray(b)) => a.cmp(b),
+            (Tag::String(a), Tag::String(b)) => a.cmp(b),
+            (Tag::List(a), Tag::List(b)) => a
+                .iter()
+                .zip(b)
+                .map(|(a, b)| a.cmp_canonical(b))
+                .find(|ordering| ordering.is_ne())
+                .unwrap_or_else(|| a.len().cmp(&b.len())),
+            (Tag::Compound(a), Tag::Compound(b)) => a.cmp_canonical(b),
+            (Tag::IntArray(a), Tag::IntArray(b)) => a.cmp(b),
+            (Tag::LongArray(a), Tag::LongArray(b)) => a.cmp(b),
+            (Tag::Unknown { id: a, bytes: x }, Tag::Unknown { id: b, bytes: y }) => {
+                a.cmp(b).then_with(|| x.cmp(y))
+            }
+            (a, b) => a.type_id().cmp(&b.type_id()),
+        }
+    }
+}
+
+impl Tag {
+    /// Returns approximate memory footprint of this tag in bytes, including everything
+    /// it owns on the heap (strings, vectors, nested tags and map overhead).
+    ///
+    /// The result is an estimate intended for cache budgets, not an exact allocator measurement.
+    pub fn deep_size_of(&self) -> usize {
+        std::mem::size_of::<Tag>() + self.heap_size_of()
+    }
+
+    fn heap_size_of(&self) -> usize {
+        match self {
+            Tag::ByteArray(value) => value.capacity() * std::mem::size_of::<i8>(),
+            Tag::String(value) => value.capacity(),
+            Tag::List(value) => {
+                value.capacity() * std::mem::size_of::<Tag>()
+                    + value.iter().map(Tag::heap_size_of).sum::<usize>()
+            }
+            Tag::Compound(value) => value.heap_size_of(),
+            Tag::IntArray(value) => value.capacity() * std::mem::size_of::<i32>(),
+            Tag::LongArray(value) => value.capacity() * std::mem::size_of::<i64>(),
+            Tag::Unknown { bytes, .. } => bytes.capacity(),
+            _ => 0,
+        }
+    }
+}
+
+/// Floating point values are compared by their bit patterns, so `NaN` equals itself
+/// and `0.0` differs from `-0.0`, which keeps equality consistent with [`Hash`].
+impl PartialEq for Tag {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Tag::Byte(a), Tag::Byte(b)) => a == b,
+            (Tag::Short(a), Tag::Short(b)) => a == b,
+            (Tag::Int(a), Tag::Int(b)) => a == b,
+            (Tag::Long(a), Tag::Long(b)) => a == b,
+            (Tag::Float(a), Tag::Float(b)) => a.to_bits() == b.to_bits(),
+            (Tag::Double(a), Tag::Double(b)) => a.to_bits() == b.to_bits(),
+            (Tag::ByteArray(a), Tag::ByteArray(b)) => a == b,
+            (Tag::String(a), Tag::String(b)) => a == b,
+            (Tag::List(a), Tag::List(b)) => a == b,
+            (Tag::Compound(a), Tag::Compound(b)) => a == b,
+            (Tag::IntArray(a), Tag::IntArray(b)) => a == b,
+            (Tag::LongArray(a), Tag::LongArray(b)) => a == b,
+            (Tag::Unknown { id: a, bytes: x }, Tag::Unknown { id: b, bytes: y }) => {
+                a == b && x == y
+            }
+            _ => false,
+        }
+    }
+}
+
+impl Eq for Tag {}
+
+impl std::hash::Hash for Tag {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        state.write_u8(self.type_id());
+
+        match self {
+            Tag::Byte(value) => value.hash(state),
+            Tag::Short(value) => value.hash(state),
+            Tag::Int(value) => value.hash(state),
+            Tag::Long(value) => value.hash(state),
+            Tag::Float(value) => value.to_bits().hash(state),
+            Tag::Double(value) => value.to_bits().hash(state),
+            Tag::ByteArray(value) => value.hash(state),
+            Tag::String(value) => value.hash(state),
+            Tag::List(value) => value.hash(state),
+            Tag::Compound(value) => value.hash(state),
+            Tag::IntArray(value) => value.hash(state),
+            Tag::LongArray(value) => value.hash(state),
+            Tag::Unknown { bytes, .. } => bytes.hash(state),
         }
     }
 }
@@ -120,6 +583,17 @@ macro_rules! impl_from_for_copy {
             }
         }
 
+        impl TryFrom<Tag> for $type {
+            type Error = Tag;
+
+            fn try_from(tag: Tag) -> Result<Self, Self::Error> {
+                match tag {
+                    Tag::$tag(value) => Ok(value),
+                    actual_tag => Err(actual_tag),
+                }
+            }
+        }
+
         impl<'a> TryFrom<&'a Tag> for $type {
             // Using a &'static str (tag name) of i8 (tag id) as Error would have fit better,
             // but we need the tag as ref so we can construct a CompoundTagError
@@ -137,7 +611,7 @@ macro_rules! impl_from_for_copy {
         impl<'a> TryFrom<&'a mut Tag> for &'a mut $type {
             type Error = &'a Tag;
 
-            fn try_from(tag: &'a mut Tag) -> Result<&mut$type, Self::Error> {
+            fn try_from(tag: &'a mut Tag) -> Result<&'a mut $type, Self::Error> {
                 match tag {
                     Tag::$tag(value) => Ok(value),
                     actual_tag => Err(actual_tag),
@@ -155,10 +629,21 @@ macro_rules! impl_from_for_ref {
             }
         }
 
+        impl TryFrom<Tag> for $type {
+            type Error = Tag;
+
+            fn try_from(tag: Tag) -> Result<Self, Self::Error> {
+                match tag {
+                    Tag::$tag(value) => Ok(value),
+                    actual_tag => Err(actual_tag),
+                }
+            }
+        }
+
         impl<'a> TryFrom<&'a Tag> for &'a $type {
             type Error = &'a Tag;
 
-            fn try_from(tag: &'a Tag) -> Result<&$type, Self::Error> {
+            fn try_from(tag: &'a Tag) -> Result<&'a $type, Self::Error> {
                 match tag {
                     Tag::$tag(value) => Ok(value),
                     actual_tag => Err(actual_tag),
@@ -169,7 +654,7 @@ macro_rules! impl_from_for_ref {
         impl<'a> TryFrom<&'a mut Tag> for &'a mut $type {
             type Error = &'a Tag;
 
-            fn try_from(tag: &'a mut Tag) -> Result<&mut$type, Self::Error> {
+            fn try_from(tag: &'a mut Tag) -> Result<&'a mut $type, Self::Error> {
                 match tag {
                     Tag::$tag(value) => Ok(value),
                     actual_tag => Err(actual_tag),
@@ -192,10 +677,124 @@ impl_from_for_ref!(CompoundTag, Compound);
 impl_from_for_ref!(Vec<i32>, IntArray);
 impl_from_for_ref!(Vec<i64>, LongArray);
 
+impl From<bool> for Tag {
+    fn from(data: bool) -> Self {
+        Tag::Byte(data as i8)
+    }
+}
+
+impl From<&str> for Tag {
+    fn from(data: &str) -> Self {
+        Tag::String(data.to_owned())
+    }
+}
+
+impl From<&String> for Tag {
+    fn from(data: &String) -> Self {
+        Tag::String(data.clone())
+    }
+}
+
+impl From<Vec<u8>> for Tag {
+    fn from(data: Vec<u8>) -> Self {
+        Tag::ByteArray(data.into_iter().map(|value| value as i8).collect())
+    }
+}
+
+impl From<&[u8]> for Tag {
+    fn from(data: &[u8]) -> Self {
+        Tag::ByteArray(data.iter().map(|value| *value as i8).collect())
+    }
+}
+
+impl From<&[i8]> for Tag {
+    fn from(data: &[i8]) -> Self {
+        Tag::ByteArray(data.to_vec())
+    }
+}
+
+impl From<&[i32]> for Tag {
+    fn from(data: &[i32]) -> Self {
+        Tag::IntArray(data.to_vec())
+    }
+}
+
+impl From<&[i64]> for Tag {
+    fn from(data: &[i64]) -> Self {
+        Tag::LongArray(data.to_vec())
+    }
+}
+
+impl From<Vec<String>> for Tag {
+    fn from(data: Vec<String>) -> Self {
+        Tag::List(data.into_iter().map(Tag::String).collect())
+    }
+}
+
+impl From<&[String]> for Tag {
+    fn from(data: &[String]) -> Self {
+        Tag::List(data.iter().map(Tag::from).collect())
+    }
+}
+
+impl From<&[&str]> for Tag {
+    fn from(data: &[&str]) -> Self {
+        Tag::List(data.iter().map(|value| Tag::from(*value)).collect())
+    }
+}
+
+impl From<Vec<CompoundTag>> for Tag {
+    fn from(data: Vec<CompoundTag>) -> Self {
+        Tag::List(data.into_iter().map(Tag::Compound).collect())
+    }
+}
+
+/// UUID is stored as an int array of 4 elements, see [`CompoundTag::insert_uuid`].
+#[cfg(feature = "uuid")]
+impl From<uuid::Uuid> for Tag {
+    fn from(data: uuid::Uuid) -> Self {
+        Tag::IntArray(uuid_to_ints(data.as_u128()).to_vec())
+    }
+}
+
+#[cfg(feature = "uuid")]
+impl<'a> TryFrom<&'a Tag> for uuid::Uuid {
+    type Error = &'a Tag;
+
+    fn try_from(tag: &'a Tag) -> Result<Self, Self::Error> {
+        match tag {
+            Tag::IntArray(value) => ints_to_uuid(value).map(uuid::Uuid::from_u128).ok_or(tag),
+            actual_tag => Err(actual_tag),
+        }
+    }
+}
+
+/// Splits UUID into ints, most significant first.
+fn uuid_to_ints(value: u128) -> [i32; 4] {
+    [
+        (value >> 96) as i32,
+        (value >> 64) as i32,
+        (value >> 32) as i32,
+        value as i32,
+    ]
+}
+
+fn ints_to_uuid(ints: &[i32]) -> Option<u128> {
+    match ints {
+        [a, b, c, d] => Some(
+            (*a as u32 as u128) << 96
+                | (*b as u32 as u128) << 64
+                | (*c as u32 as u128) << 32
+                | *d as u32 as u128,
+        ),
+        _ => None,
+    }
+}
+
 #[derive(Clone, Default)]
 pub struct CompoundTag {
     pub name: Option<String>,
-    tags: LinkedHashMap<String, Tag>,
+    tags: TagMap,
 }
 
 /// Possible types of errors while trying to get value from compound tag.
@@ -212,6 +811,8 @@ pub enum CompoundTagError<'a, 'b> {
         name: &'b str,
         /// Actual tag.
         actual_tag: &'a Tag,
+        /// Expected tag type if known.
+        expected_type: Option<TagType>,
     },
 }
 
@@ -225,8 +826,17 @@ impl<'a, 'b> Display for CompoundTagError<'a, 'b> {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self {
             CompoundTagError::TagNotFound { name } => write!(f, "Tag {} not found", name),
-            CompoundTagError::TagWrongType { name, actual_tag } => {
-                write!(f, "Tag {} has type {}", name, actual_tag.type_name())
+            CompoundTagError::TagWrongType {
+                name,
+                actual_tag,
+                expected_type,
+            } => {
+                write!(f, "Tag {} has type {}", name, actual_tag.type_name())?;
+
+                match expected_type {
+                    Some(expected_type) => write!(f, ", expected {}", expected_type),
+                    None => Ok(()),
+                }
             }
         }
     }
@@ -234,15 +844,21 @@ impl<'a, 'b> Display for CompoundTagError<'a, 'b> {
 
 macro_rules! define_primitive_type (
     ($type: ident, $tag: ident, $getter_name: ident, $setter_name: ident) => (
-        pub fn $setter_name(&mut self, name: impl ToString, value: $type) {
-            self.tags.insert(name.to_string(), Tag::$tag(value));
+        pub fn $setter_name(&mut self, name: impl Into<String>, value: $type) {
+            self.tags.insert(name.into(), Tag::$tag(value));
         }
 
-        pub fn $getter_name<'a, 'b>(&'a self, name: &'b str) -> Result<$type, CompoundTagError<'a, 'b>> {
+        pub fn $getter_name<'a, 'b, Q: Borrow<str> + ?Sized>(&'a self, name: &'b Q) -> Result<$type, CompoundTagError<'a, 'b>> {
+            let name = name.borrow();
+
             match self.tags.get(name) {
                 Some(tag) => match tag {
                     Tag::$tag(value) => Ok(*value),
-                    actual_tag => Err(CompoundTagError::TagWrongType { name, actual_tag }),
+                    actual_tag => Err(CompoundTagError::TagWrongType {
+                        name,
+                        actual_tag,
+                        expected_type: Some(TagType::$tag),
+                    }),
                 },
                 None => Err(CompoundTagError::TagNotFound { name }),
             }
@@ -252,15 +868,21 @@ macro_rules! define_primitive_type (
 
 macro_rules! define_array_type (
     ($type: ident, $tag: ident, $getter_name: ident, $setter_name: ident) => (
-        pub fn $setter_name(&mut self, name: impl ToString, value: Vec<$type>) {
-            self.tags.insert(name.to_string(), Tag::$tag(value));
+        pub fn $setter_name(&mut self, name: impl Into<String>, value: Vec<$type>) {
+            self.tags.insert(name.into(), Tag::$tag(value));
         }
 
-        pub fn $getter_name<'a, 'b>(&'a self, name: &'b str) -> Result<&Vec<$type>, CompoundTagError<'a, 'b>> {
+        pub fn $getter_name<'a, 'b, Q: Borrow<str> + ?Sized>(&'a self, name: &'b Q) -> Result<&'a Vec<$type>, CompoundTagError<'a, 'b>> {
+            let name = name.borrow();
+
             match self.tags.get(name) {
                 Some(tag) => match tag {
                     Tag::$tag(value) => Ok(value),
-                    actual_tag => Err(CompoundTagError::TagWrongType { name, actual_tag }),
+                    actual_tag => Err(CompoundTagError::TagWrongType {
+                        name,
+                        actual_tag,
+                        expected_type: Some(TagType::$tag),
+                    }),
                 },
                 None => Err(CompoundTagError::TagNotFound { name }),
             }
@@ -270,24 +892,29 @@ macro_rules! define_array_type (
 
 macro_rules! define_list_type (
     ($type: ident, $tag: ident, $getter_name: ident, $setter_name: ident) => (
-        pub fn $setter_name(&mut self, name: impl ToString, vec: impl IntoIterator<Item=$type>) {
+        pub fn $setter_name(&mut self, name: impl Into<String>, vec: impl IntoIterator<Item=$type>) {
             let mut tags = Vec::new();
 
             for value in vec {
                 tags.push(Tag::$tag(value));
             }
 
-            self.tags.insert(name.to_string(), Tag::List(tags));
+            self.tags.insert(name.into(), Tag::List(tags));
         }
 
-        pub fn $getter_name<'a, 'b>(&'a self, name: &'b str) -> Result<Vec<$type>, CompoundTagError<'a, 'b>> {
+        pub fn $getter_name<'a, 'b, Q: Borrow<str> + ?Sized>(&'a self, name: &'b Q) -> Result<Vec<$type>, CompoundTagError<'a, 'b>> {
+            let name = name.borrow();
             let tags = self.get_vec(name)?;
             let mut vec = Vec::new();
 
             for tag in tags {
                 match tag {
                     Tag::$tag(value) => vec.push(*value),
-                    actual_tag => return Err(CompoundTagError::TagWrongType { name, actual_tag }),
+                    actual_tag => return Err(CompoundTagError::TagWrongType {
+                        name,
+                        actual_tag,
+                        expected_type: Some(TagType::$tag),
+                    }),
                 }
             }
 
@@ -301,10 +928,10 @@ impl CompoundTag {
         CompoundTag::default()
     }
 
-    pub fn named(name: impl ToString) -> Self {
+    pub fn named(name: impl Into<String>) -> Self {
         CompoundTag {
-            name: Some(name.to_string()),
-            tags: LinkedHashMap::new(),
+            name: Some(name.into()),
+            tags: TagMap::new(),
         }
     }
 
@@ -312,12 +939,12 @@ impl CompoundTag {
         self.tags.is_empty()
     }
 
-    pub fn contains_key(&self, name: &str) -> bool {
-        self.tags.contains_key(name)
+    pub fn contains_key<Q: Borrow<str> + ?Sized>(&self, name: &Q) -> bool {
+        self.tags.contains_key(name.borrow())
     }
 
-    pub fn insert(&mut self, name: impl ToString, tag: impl Into<Tag>) {
-        self.tags.insert(name.to_string(), tag.into());
+    pub fn insert(&mut self, name: impl Into<String>, tag: impl Into<Tag>) {
+        self.tags.insert(name.into(), tag.into());
     }
 
     pub fn get<'a, 'b, T: TryFrom<&'a Tag>>(
@@ -330,13 +957,14 @@ impl CompoundTag {
                 Err(..) => Err(CompoundTagError::TagWrongType {
                     name,
                     actual_tag: tag,
+                    expected_type: None,
                 }),
             },
             None => Err(CompoundTagError::TagNotFound { name }),
         }
     }
 
-    pub fn get_mut<'a, 'b, T>(&'a mut self, name: &'b str) -> Result<T, CompoundTagError>
+    pub fn get_mut<'a, 'b, T>(&'a mut self, name: &'b str) -> Result<T, CompoundTagError<'a, 'b>>
     where
         'b: 'a,
         T: TryFrom<&'a mut Tag, Error = &'a Tag>,
@@ -344,7 +972,11 @@ impl CompoundTag {
         match self.tags.get_mut(name) {
             Some(tag) => match tag.try_into() {
                 Ok(value) => Ok(value),
-                Err(actual_tag) => Err(CompoundTagError::TagWrongType { name, actual_tag }),
+                Err(actual_tag) => Err(CompoundTagError::TagWrongType {
+                    name,
+                    actual_tag,
+                    expected_type: None,
+                }),
             },
             None => Err(CompoundTagError::TagNotFound { name }),
         }
@@ -363,7 +995,7 @@ impl CompoundTag {
     define_list_type!(f32, Float, get_f32_vec, insert_f32_vec);
     define_list_type!(f64, Double, get_f64_vec, insert_f64_vec);
 
-    pub fn insert_bool(&mut self, name: &str, value: bool) {
+    pub fn insert_bool(&mut self, name: impl Into<String>, value: bool) {
         if value {
             self.insert_i8(name, 1);
         } else {
@@ -371,47 +1003,127 @@ impl CompoundTag {
         }
     }
 
-    pub fn get_bool<'a, 'b>(&'a self, name: &'b str) -> Result<bool, CompoundTagError<'a, 'b>> {
+    pub fn get_bool<'a, 'b, Q: Borrow<str> + ?Sized>(
+        &'a self,
+        name: &'b Q,
+    ) -> Result<bool, CompoundTagError<'a, 'b>> {
+        let name = name.borrow();
+
         Ok(self.get_i8(name)? == 1)
     }
 
-    pub fn insert_str(&mut self, name: impl ToString, value: impl ToString) {
+    /// Inserts UUID as an int array of 4 elements, most significant first, as used since 1.16.
+    pub fn insert_uuid(&mut self, name: impl Into<String>, value: u128) {
         self.tags
-            .insert(name.to_string(), Tag::String(value.to_string()));
+            .insert(name.into(), Tag::IntArray(uuid_to_ints(value).to_vec()));
     }
 
-    pub fn get_str<'a, 'b>(&'a self, name: &'b str) -> Result<&str, CompoundTagError<'a, 'b>> {
+    /// Returns UUID stored as an int array of 4 elements.
+    ///
+    /// If tag is missing, falls back to `{name}Most` and `{name}Least` long tags used before 1.16.
+    ///
+    /// # Example
+    /// ```
+    /// use nbt::CompoundTag;
+    ///
+    /// let mut entity = CompoundTag::new();
+    /// entity.insert_i64("UUIDMost", 0x0123_4567_89ab_cdef);
+    /// entity.insert_i64("UUIDLeast", -1);
+    ///
+    /// let uuid = entity.get_uuid("UUID").unwrap();
+    /// assert_eq!(uuid, 0x0123_4567_89ab_cdef_ffff_ffff_ffff_ffff);
+    ///
+    /// entity.insert_uuid("UUID", uuid);
+    /// assert_eq!(entity.get_i32_vec("UUID").unwrap(), &vec![0x0123_4567, -0x7654_3211, -1, -1]);
+    /// ```
+    pub fn get_uuid<'a, 'b, Q: Borrow<str> + ?Sized>(
+        &'a self,
+        name: &'b Q,
+    ) -> Result<u128, CompoundTagError<'a, 'b>> {
+        let name = name.borrow();
+
+        match self.tags.get(name) {
+            Some(tag) => match tag {
+                Tag::IntArray(value) => ints_to_uuid(value).ok_or(CompoundTagError::TagWrongType {
+                    name,
+                    actual_tag: tag,
+                    expected_type: None,
+                }),
+                actual_tag => Err(CompoundTagError::TagWrongType {
+                    name,
+                    actual_tag,
+                    expected_type: Some(TagType::IntArray),
+                }),
+            },
+            None => {
+                let most = self.tags.get(&format!("{}Most", name));
+                let least = self.tags.get(&format!("{}Least", name));
+
+                match (most, least) {
+                    (Some(Tag::Long(most)), Some(Tag::Long(least))) => {
+                        Ok((*most as u64 as u128) << 64 | *least as u64 as u128)
+                    }
+                    _ => Err(CompoundTagError::TagNotFound { name }),
+                }
+            }
+        }
+    }
+
+    pub fn insert_str(&mut self, name: impl Into<String>, value: impl Into<String>) {
+        self.tags.insert(name.into(), Tag::String(value.into()));
+    }
+
+    pub fn get_str<'a, 'b, Q: Borrow<str> + ?Sized>(
+        &'a self,
+        name: &'b Q,
+    ) -> Result<&'a str, CompoundTagError<'a, 'b>> {
+        let name = name.borrow();
+
         match self.tags.get(name) {
             Some(tag) => match tag {
                 Tag::String(value) => Ok(value),
-                actual_tag => Err(CompoundTagError::TagWrongType { name, actual_tag }),
+                actual_tag => Err(CompoundTagError::TagWrongType {
+                    name,
+                    actual_tag,
+                    expected_type: Some(TagType::String),
+                }),
             },
             None => Err(CompoundTagError::TagNotFound { name }),
         }
     }
 
-    pub fn insert_compound_tag(&mut self, name: impl ToString, value: CompoundTag) {
-        self.tags.insert(name.to_string(), Tag::Compound(value));
+    pub fn insert_compound_tag(&mut self, name: impl Into<String>, value: CompoundTag) {
+        self.tags.insert(name.into(), Tag::Compound(value));
     }
 
-    pub fn get_compound_tag<'a, 'b>(
+    pub fn get_compound_tag<'a, 'b, Q: Borrow<str> + ?Sized>(
         &'a self,
-        name: &'b str,
-    ) -> Result<&CompoundTag, CompoundTagError<'a, 'b>> {
+        name: &'b Q,
+    ) -> Result<&'a CompoundTag, CompoundTagError<'a, 'b>> {
+        let name = name.borrow();
+
         match self.tags.get(name) {
             Some(tag) => match tag {
                 Tag::Compound(value) => Ok(value),
-                actual_tag => Err(CompoundTagError::TagWrongType { name, actual_tag }),
+                actual_tag => Err(CompoundTagError::TagWrongType {
+                    name,
+                    actual_tag,
+                    expected_type: Some(TagType::Compound),
+                }),
             },
             None => Err(CompoundTagError::TagNotFound { name }),
         }
     }
 
-    fn get_vec<'a, 'b>(&'a self, name: &'b str) -> Result<&Vec<Tag>, CompoundTagError<'a, 'b>> {
+    fn get_vec<'a, 'b>(&'a self, name: &'b str) -> Result<&'a Vec<Tag>, CompoundTagError<'a, 'b>> {
         match self.tags.get(name) {
             Some(tag) => match tag {
                 Tag::List(value) => Ok(value),
-                actual_tag => Err(CompoundTagError::TagWrongType { name, actual_tag }),
+                actual_tag => Err(CompoundTagError::TagWrongType {
+                    name,
+                    actual_tag,
+                    expected_type: Some(TagType::List),
+                }),
             },
             None => Err(CompoundTagError::TagNotFound { name }),
         }
@@ -419,29 +1131,37 @@ impl CompoundTag {
 
     pub fn insert_str_vec(
         &mut self,
-        name: impl ToString,
-        vec: impl IntoIterator<Item = impl ToString>,
+        name: impl Into<String>,
+        vec: impl IntoIterator<Item = impl Into<String>>,
     ) {
         let mut tags = Vec::new();
 
         for value in vec {
-            tags.push(Tag::String(value.to_string()));
+            tags.push(Tag::String(value.into()));
         }
 
-        self.tags.insert(name.to_string(), Tag::List(tags));
+        self.tags.insert(name.into(), Tag::List(tags));
     }
 
-    pub fn get_str_vec<'a, 'b>(
+    pub fn get_str_vec<'a, 'b, Q: Borrow<str> + ?Sized>(
         &'a self,
-        name: &'b str,
-    ) -> Result<Vec<&str>, CompoundTagError<'a, 'b>> {
+        name: &'b Q,
+    ) -> Result<Vec<&'a str>, CompoundTagError<'a, 'b>> {
+        let name = name.borrow();
+
         let tags = self.get_vec(name)?;
         let mut vec = Vec::new();
 
         for tag in tags {
             match tag {
                 Tag::String(value) => vec.push(value.as_str()),
-                actual_tag => return Err(CompoundTagError::TagWrongType { name, actual_tag }),
+                actual_tag => {
+                    return Err(CompoundTagError::TagWrongType {
+                        name,
+                        actual_tag,
+                        expected_type: Some(TagType::String),
+                    })
+                }
             }
         }
 
@@ -450,7 +1170,7 @@ impl CompoundTag {
 
     pub fn insert_compound_tag_vec(
         &mut self,
-        name: impl ToString,
+        name: impl Into<String>,
         vec: impl IntoIterator<Item = CompoundTag>,
     ) {
         let mut tags = Vec::new();
@@ -459,36 +1179,94 @@ impl CompoundTag {
             tags.push(Tag::Compound(value));
         }
 
-        self.tags.insert(name.to_string(), Tag::List(tags));
+        self.tags.insert(name.into(), Tag::List(tags));
     }
 
-    pub fn get_compound_tag_vec<'a, 'b>(
+    pub fn get_compound_tag_vec<'a, 'b, Q: Borrow<str> + ?Sized>(
         &'a self,
-        name: &'b str,
-    ) -> Result<Vec<&CompoundTag>, CompoundTagError<'a, 'b>> {
+        name: &'b Q,
+    ) -> Result<Vec<&'a CompoundTag>, CompoundTagError<'a, 'b>> {
+        let name = name.borrow();
+
         let tags = self.get_vec(name)?;
         let mut vec = Vec::new();
 
         for tag in tags {
             match tag {
                 Tag::Compound(value) => vec.push(value),
-                actual_tag => return Err(CompoundTagError::TagWrongType { name, actual_tag }),
+                actual_tag => {
+                    return Err(CompoundTagError::TagWrongType {
+                        name,
+                        actual_tag,
+                        expected_type: Some(TagType::Compound),
+                    })
+                }
             }
         }
 
         Ok(vec)
     }
 
-    pub fn iter(&self) -> impl DoubleEndedIterator<Item = (&String, &Tag)> {
+    /// Compares compound tags by their entries sorted by name.
+    ///
+    /// See [`Tag::cmp_canonical`] for details.
+    pub fn cmp_canonical(&self, other: &CompoundTag) -> std::cmp::Ordering {
+        let a = self.sorted_entries();
+        let b = other.sorted_entries();
+
+        a.iter()
+            .zip(&b)
+            .map(|((a_name, a_tag), (b_name, b_tag))| {
+                a_name.cmp(b_name).then_with(|| a_tag.cmp_canonical(b_tag))
+            })
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or_else(|| a.len().cmp(&b.len()))
+    }
+
+    /// Returns approximate memory footprint of this compound tag in bytes.
+    ///
+    /// See [`Tag::deep_size_of`] for details.
+    pub fn deep_size_of(&self) -> usize {
+        std::mem::size_of::<CompoundTag>() + self.heap_size_of()
+    }
+
+    fn heap_size_of(&self) -> usize {
+        // Every map entry is a heap node holding key, value and two links
+        // plus a hash table slot pointing to it.
+        let entry_size = std::mem::size_of::<map::Key>()
+            + std::mem::size_of::<Tag>()
+            + 3 * std::mem::size_of::<usize>();
+
+        let name_size = self.name.as_ref().map_or(0, String::capacity);
+        let tags_size: usize = self
+            .tags
+            .iter()
+            .map(|(_, tag)| entry_size + tag.heap_size_of())
+            .sum();
+
+        name_size + self.tags.names_heap_size() + tags_size
+    }
+
+    fn sorted_entries(&self) -> Vec<(&str, &Tag)> {
+        let mut entries: Vec<_> = self.tags.iter().collect();
+        entries.sort_by_key(|(name, _)| *name);
+
+        entries
+    }
+
+    /// Iterates over tags in insertion order.
+    ///
+    /// Names are `&str` regardless of how they are stored, so features don't change item types.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = (&str, &Tag)> {
         self.tags.iter()
     }
 
-    pub fn iter_mut(&mut self) -> impl DoubleEndedIterator<Item = (&String, &mut Tag)> {
+    pub fn iter_mut(&mut self) -> impl DoubleEndedIterator<Item = (&str, &mut Tag)> {
         self.tags.iter_mut()
     }
 }
 
-pub struct IntoIter(linked_hash_map::IntoIter<String, Tag>);
+pub struct IntoIter(map::IntoIter);
 
 impl Iterator for IntoIter {
     type Item = (String, Tag);
@@ -534,6 +1312,70 @@ impl<'a> std::iter::FromIterator<(&'a str, Tag)> for CompoundTag {
     }
 }
 
+/// Compound tags are equal when they contain equal tags in the same order.
+/// Name is not taken into account.
+impl PartialEq for CompoundTag {
+    fn eq(&self, other: &Self) -> bool {
+        self.tags == other.tags
+    }
+}
+
+impl Eq for CompoundTag {}
+
+impl std::hash::Hash for CompoundTag {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.tags.hash(state);
+    }
+}
+
+impl std::ops::Index<&str> for CompoundTag {
+    type Output = Tag;
+
+    /// Returns tag with provided name.
+    ///
+    /// # Panics
+    ///
+    /// Panics if tag with provided name not found.
+    fn index(&self, name: &str) -> &Tag {
+        match self.tags.get(name) {
+            Some(tag) => tag,
+            None => panic!("Tag {} not found", name),
+        }
+    }
+}
+
+impl std::ops::Index<&str> for Tag {
+    type Output = Tag;
+
+    /// Returns tag with provided name from compound tag.
+    ///
+    /// # Panics
+    ///
+    /// Panics if tag is not a compound tag or tag with provided name not found.
+    fn index(&self, name: &str) -> &Tag {
+        match self {
+            Tag::Compound(value) => &value[name],
+            tag => panic!("Tag {} can't be indexed by name", tag.type_name()),
+        }
+    }
+}
+
+impl std::ops::Index<usize> for Tag {
+    type Output = Tag;
+
+    /// Returns tag at provided position from list tag.
+    ///
+    /// # Panics
+    ///
+    /// Panics if tag is not a list tag or position is out of bounds.
+    fn index(&self, index: usize) -> &Tag {
+        match self {
+            Tag::List(value) => &value[index],
+            tag => panic!("Tag {} can't be indexed by position", tag.type_name()),
+        }
+    }
+}
+
 impl Debug for CompoundTag {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
         let name_ref = self.name.as_deref();
@@ -578,8 +1420,8 @@ fn fmt_tag(
 
             fmt_list_start(f, type_name, name, length)?;
 
-            for (name, tag) in &value.tags {
-                fmt_tag(f, Some(name.as_str()), tag, indent + 2)?;
+            for (name, tag) in value.tags.iter() {
+                fmt_tag(f, Some(name), tag, indent + 2)?;
             }
 
             if length > 0 {
@@ -588,6 +1430,7 @@ fn fmt_tag(
         }
         Tag::IntArray(value) => fmt_array_tag(f, type_name, name, value)?,
         Tag::LongArray(value) => fmt_array_tag(f, type_name, name, value)?,
+        Tag::Unknown { bytes, .. } => fmt_array_tag(f, type_name, name, bytes)?,
     };
 
     Ok(())
@@ -636,10 +1479,7 @@ fn fmt_indent(f: &mut Formatter, indent: usize) -> Result<(), fmt::Error> {
 }
 
 fn fmt_str_opt(name: Option<&str>) -> &str {
-    match name {
-        Some(value) => value,
-        None => "",
-    }
+    name.unwrap_or_default()
 }
 
 impl Display for CompoundTag {
@@ -648,7 +1488,7 @@ impl Display for CompoundTag {
         // We can't use f.debug_struct() because that would use child Debug, not Display
         write!(f, "{{")?;
         let mut first = true;
-        for (name, value) in &self.tags {
+        for (name, value) in self.tags.iter() {
             write!(f, "{}{:?}:{}", if first { "" } else { "," }, name, value)?;
             first = false;
         }
@@ -685,6 +1525,10 @@ impl Display for Tag {
             Tag::Compound(data) => write!(f, "{}", data),
             Tag::IntArray(data) => format_list(f, "I;", data),
             Tag::LongArray(data) => format_list(f, "L;", data),
+            // SNBT has no notation for unknown tags, so raw payload is shown as a byte array.
+            Tag::Unknown { bytes, .. } => {
+                format_list(f, "B;", &bytes.iter().map(|v| *v as i8).collect::<Vec<_>>())
+            }
         }
     }
 }
@@ -916,6 +1760,261 @@ fn test_level_fmt() {
     );
 }
 
+#[test]
+fn test_tag_as() {
+    let mut tag = Tag::Int(3);
+
+    assert_eq!(tag.as_i32(), Some(3));
+    assert_eq!(tag.as_i64(), None);
+    assert_eq!(tag.as_str(), None);
+
+    *tag.as_i32_mut().unwrap() = 4;
+    assert_eq!(tag.as_i32(), Some(4));
+
+    let mut tag = Tag::String("hello".to_string());
+    tag.as_string_mut().unwrap().push_str(" world");
+
+    assert_eq!(tag.as_str(), Some("hello world"));
+
+    assert_eq!(Tag::Byte(1).as_bool(), Some(true));
+
+    let mut tag = Tag::Compound(CompoundTag::new());
+    tag.as_compound_mut().unwrap().insert_i8("i8", 1);
+
+    assert_eq!(tag.as_compound().unwrap().get_i8("i8").unwrap(), 1);
+    assert!(tag.as_list().is_none());
+}
+
+#[test]
+fn test_tag_is() {
+    assert!(Tag::Byte(1).is_byte());
+    assert!(Tag::Byte(1).is_numeric());
+    assert!(!Tag::Byte(1).is_array());
+    assert!(Tag::Double(1.0).is_numeric());
+    assert!(Tag::LongArray(vec![]).is_array());
+    assert!(!Tag::List(vec![]).is_array());
+    assert!(Tag::Compound(CompoundTag::new()).is_compound());
+    assert!(!Tag::String("".to_string()).is_numeric());
+}
+
+#[test]
+fn test_index() {
+    use crate::decode::read_gzip_compound_tag;
+    use std::io::Cursor;
+
+    let mut cursor = Cursor::new(include_bytes!("../test/binary/bigtest.dat").to_vec());
+    let root_tag = read_gzip_compound_tag(&mut cursor).unwrap();
+
+    assert_eq!(root_tag["intTest"].as_i32(), Some(i32::MAX));
+    assert_eq!(
+        root_tag["listTest (compound)"][1]["name"].as_str(),
+        Some("Compound tag #1")
+    );
+    assert_eq!(
+        root_tag["nested compound test"]["egg"]["value"].as_f32(),
+        Some(0.5)
+    );
+}
+
+#[test]
+#[should_panic]
+fn test_index_not_found() {
+    let compound_tag = CompoundTag::new();
+    let _ = &compound_tag["test"];
+}
+
+#[test]
+#[should_panic]
+fn test_index_wrong_type() {
+    let tag = Tag::Int(1);
+    let _ = &tag[0];
+}
+
+#[test]
+fn test_tag_get() {
+    fn get_id(tag: &Tag) -> Option<&str> {
+        tag.get("Inventory")?.get_index(0)?.get("id")?.as_str()
+    }
+
+    let mut item = CompoundTag::new();
+    item.insert_str("id", "minecraft:stone");
+
+    let mut root_tag = CompoundTag::new();
+    root_tag.insert_compound_tag_vec("Inventory", vec![item]);
+
+    let mut tag = Tag::Compound(root_tag);
+    assert_eq!(get_id(&tag), Some("minecraft:stone"));
+
+    assert!(tag.get("Inventory").unwrap().get_index(1).is_none());
+    assert!(tag.get("Missing").is_none());
+    assert!(tag.get_index(0).is_none());
+
+    *tag.get_mut("Inventory")
+        .and_then(|tag| tag.get_index_mut(0))
+        .and_then(|tag| tag.get_mut("id"))
+        .unwrap() = Tag::String("minecraft:dirt".to_string());
+
+    assert_eq!(get_id(&tag), Some("minecraft:dirt"));
+}
+
+#[test]
+fn test_tag_from() {
+    let mut compound_tag = CompoundTag::new();
+    compound_tag.insert("str", "hello world");
+    compound_tag.insert("bool", true);
+    compound_tag.insert("u8_vec", vec![255u8, 1]);
+    compound_tag.insert("str_vec", &["a", "b"][..]);
+    compound_tag.insert("i32_vec", &[1, 2][..]);
+
+    assert_eq!(compound_tag.get_str("str").unwrap(), "hello world");
+    assert!(compound_tag.get_bool("bool").unwrap());
+    assert_eq!(compound_tag.get_i8_vec("u8_vec").unwrap(), &vec![-1, 1]);
+    assert_eq!(compound_tag.get_str_vec("str_vec").unwrap(), vec!["a", "b"]);
+    assert_eq!(compound_tag.get_i32_vec("i32_vec").unwrap(), &vec![1, 2]);
+}
+
+#[test]
+fn test_tag_try_from_value() {
+    let tag = Tag::String("hello world".to_string());
+    let value: String = tag.try_into().unwrap();
+
+    assert_eq!(value, "hello world");
+
+    let tag = Tag::Int(1);
+    let value: Result<CompoundTag, Tag> = tag.try_into();
+
+    assert_eq!(value.unwrap_err().as_i32(), Some(1));
+    assert_eq!(i32::try_from(Tag::Int(2)).unwrap(), 2);
+}
+
+#[test]
+fn test_tag_hash() {
+    use std::collections::HashSet;
+
+    let mut compound_tag_1 = CompoundTag::named("first");
+    compound_tag_1.insert_str("id", "minecraft:chest");
+    compound_tag_1.insert_f32("value", f32::NAN);
+
+    let mut compound_tag_2 = CompoundTag::named("second");
+    compound_tag_2.insert_str("id", "minecraft:chest");
+    compound_tag_2.insert_f32("value", f32::NAN);
+
+    let mut set = HashSet::new();
+    set.insert(Tag::Compound(compound_tag_1));
+    set.insert(Tag::Compound(compound_tag_2));
+    set.insert(Tag::Float(0.0));
+    set.insert(Tag::Float(-0.0));
+    set.insert(Tag::Double(0.0));
+
+    assert_eq!(set.len(), 4);
+    assert_ne!(Tag::Int(1), Tag::Long(1));
+}
+
+#[test]
+fn test_cmp_canonical() {
+    use std::cmp::Ordering;
+
+    let mut compound_tag_1 = CompoundTag::new();
+    compound_tag_1.insert_i32("a", 1);
+    compound_tag_1.insert_i32("b", 2);
+
+    let mut compound_tag_2 = CompoundTag::new();
+    compound_tag_2.insert_i32("b", 2);
+    compound_tag_2.insert_i32("a", 1);
+
+    assert_eq!(
+        compound_tag_1.cmp_canonical(&compound_tag_2),
+        Ordering::Equal
+    );
+
+    compound_tag_2.insert_i32("a", 0);
+    assert_eq!(
+        compound_tag_1.cmp_canonical(&compound_tag_2),
+        Ordering::Greater
+    );
+
+    assert_eq!(
+        Tag::Float(-0.0).cmp_canonical(&Tag::Float(0.0)),
+        Ordering::Less
+    );
+    assert_eq!(
+        Tag::List(vec![Tag::Int(1)]).cmp_canonical(&Tag::List(vec![Tag::Int(1), Tag::Int(0)])),
+        Ordering::Less
+    );
+    assert_eq!(
+        Tag::Long(0).cmp_canonical(&Tag::IntArray(vec![])),
+        Ordering::Less
+    );
+}
+
+#[test]
+fn test_deep_size_of() {
+    use std::mem::size_of;
+
+    assert_eq!(Tag::Int(1).deep_size_of(), size_of::<Tag>());
+    assert_eq!(
+        Tag::LongArray(Vec::with_capacity(4)).deep_size_of(),
+        size_of::<Tag>() + 4 * size_of::<i64>()
+    );
+
+    let mut compound_tag = CompoundTag::new();
+    let empty_size = compound_tag.deep_size_of();
+
+    compound_tag.insert_i8_vec("i8_vec", vec![0; 1000]);
+    assert!(compound_tag.deep_size_of() >= empty_size + 1000);
+
+    let tag = Tag::List(vec![Tag::Compound(compound_tag.clone())]);
+    assert!(tag.deep_size_of() > compound_tag.deep_size_of());
+}
+
+#[test]
+fn test_default_tag() {
+    for id in 1..=12 {
+        let tag_type = TagType::from_id(id).unwrap();
+        let tag = tag_type.default_tag();
+
+        assert_eq!(tag.tag_type(), tag_type);
+        assert_eq!(tag.type_id(), id);
+    }
+
+    assert!(TagType::from_id(0).is_none());
+    assert!(TagType::from_id(13).is_none());
+}
+
+#[test]
+fn test_numeric_coercion() {
+    assert_eq!(Tag::Byte(-1).to_i64(), Some(-1));
+    assert_eq!(Tag::Int(7).to_f64(), Some(7.0));
+    assert_eq!(Tag::Double(-2.7).to_i64(), Some(-2));
+    assert_eq!(Tag::Float(f32::MAX).to_i64(), Some(i64::MAX));
+    assert_eq!(Tag::Float(0.5).to_f64(), Some(0.5));
+    assert_eq!(Tag::String("1".to_string()).to_i64(), None);
+    assert_eq!(Tag::IntArray(vec![1]).to_f64(), None);
+}
+
+#[test]
+fn test_compound_tag_wrong_type() {
+    let mut compound_tag = CompoundTag::new();
+    compound_tag.insert_str("Health", "20");
+    compound_tag.insert_f32_vec("Rotation", vec![0.0, 1.0]);
+
+    let error = compound_tag.get_f32("Health").unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Tag Health has type TAG_String, expected TAG_Float"
+    );
+
+    match compound_tag.get_f64_vec("Rotation").unwrap_err() {
+        CompoundTagError::TagWrongType { expected_type, .. } => {
+            assert_eq!(expected_type, Some(TagType::Double))
+        }
+        _ => panic!(),
+    }
+
+    let error = compound_tag.get::<i32>("Health").unwrap_err();
+    assert_eq!(error.to_string(), "Tag Health has type TAG_String");
+}
+
 #[test]
 fn test_is_empty() {
     let mut compound_tag = CompoundTag::new();
@@ -935,6 +2034,34 @@ fn test_contains_key() {
     assert!(!compound_tag.contains_key("test2"));
 }
 
+#[test]
+fn test_borrowed_names() {
+    use std::borrow::Cow;
+
+    let mut compound_tag = CompoundTag::new();
+    compound_tag.insert_i32(String::from("owned"), 1);
+    compound_tag.insert_str(Cow::Borrowed("cow"), String::from("value"));
+    compound_tag.insert_bool('c', true);
+
+    let owned = String::from("owned");
+    let cow: Cow<str> = Cow::Owned(String::from("cow"));
+
+    assert_eq!(compound_tag.get_i32(&owned).unwrap(), 1);
+    assert_eq!(compound_tag.get_str(&cow).unwrap(), "value");
+    assert!(compound_tag.get_bool("c").unwrap());
+
+    // Names of any type borrowing as `str` are looked up and reported without copying.
+    let boxed: Box<str> = Box::from("cow");
+    assert!(compound_tag.contains_key(&owned));
+    assert!(matches!(
+        compound_tag.get_i32(&boxed),
+        Err(CompoundTagError::TagWrongType { name: "cow", .. })
+    ));
+
+    let tag = Tag::Compound(compound_tag);
+    assert_eq!(tag.get(&owned), Some(&Tag::Int(1)));
+}
+
 #[test]
 fn test_iter() {
     // Test from_iter
@@ -950,7 +2077,7 @@ fn test_iter() {
     {
         let mut iter = compound.iter().map(|(name, tag)| {
             (
-                name.as_str(),
+                name,
                 match tag {
                     Tag::Int(value) => *value,
                     _ => panic!(),
@@ -990,3 +2117,46 @@ fn test_iter() {
         assert_eq!(iter.next(), None);
     }
 }
+
+#[test]
+fn test_uuid() {
+    let uuid = 0x069a_79f4_44e9_4726_a5be_fca9_0e38_aaf5;
+
+    let mut compound_tag = CompoundTag::new();
+    compound_tag.insert_uuid("UUID", uuid);
+
+    assert_eq!(
+        compound_tag.get_i32_vec("UUID").unwrap(),
+        &vec![0x069a_79f4, 0x44e9_4726, -0x5a41_0357, 0x0e38_aaf5]
+    );
+    assert_eq!(compound_tag.get_uuid("UUID").unwrap(), uuid);
+
+    compound_tag.insert_i64("OwnerMost", (uuid >> 64) as i64);
+    compound_tag.insert_i64("OwnerLeast", uuid as i64);
+    assert_eq!(compound_tag.get_uuid("Owner").unwrap(), uuid);
+
+    compound_tag.insert_i32_vec("Short", vec![1, 2, 3]);
+    compound_tag.insert_i64("TargetMost", 1);
+
+    assert!(matches!(
+        compound_tag.get_uuid("Short"),
+        Err(CompoundTagError::TagWrongType {
+            expected_type: None,
+            ..
+        })
+    ));
+    assert!(matches!(
+        compound_tag.get_uuid("Target"),
+        Err(CompoundTagError::TagNotFound { name: "Target" })
+    ));
+
+    #[cfg(feature = "uuid")]
+    {
+        let value = uuid::Uuid::from_u128(uuid);
+        let tag = Tag::from(value);
+
+        assert_eq!(compound_tag["UUID"], tag);
+        assert_eq!(uuid::Uuid::try_from(&tag), Ok(value));
+        assert!(uuid::Uuid::try_from(&Tag::Int(1)).is_err());
+    }
+}
@@ -0,0 +1,164 @@
+use crate::{CompoundTag, Tag, TagType};
+use std::convert::TryFrom;
+use std::ops::Deref;
+
+/// Type which can be stored as an element of [`ListTag`].
+pub trait ListElement: Into<Tag> + TryFrom<Tag, Error = Tag> {
+    /// Tag type of list elements.
+    const TAG_TYPE: TagType;
+}
+
+macro_rules! impl_list_element {
+    ($type: ty, $tag_type: ident) => {
+        impl ListElement for $type {
+            const TAG_TYPE: TagType = TagType::$tag_type;
+        }
+    };
+}
+
+impl_list_element!(i8, Byte);
+impl_list_element!(i16, Short);
+impl_list_element!(i32, Int);
+impl_list_element!(i64, Long);
+impl_list_element!(f32, Float);
+impl_list_element!(f64, Double);
+impl_list_element!(Vec<i8>, ByteArray);
+impl_list_element!(String, String);
+impl_list_element!(Vec<Tag>, List);
+impl_list_element!(CompoundTag, Compound);
+impl_list_element!(Vec<i32>, IntArray);
+impl_list_element!(Vec<i64>, LongArray);
+
+/// List tag which elements are statically guaranteed to have the same type.
+///
+/// # Example
+/// ```
+/// use nbt::{ListTag, Tag};
+/// use std::convert::TryFrom;
+///
+/// let mut motion = ListTag::new();
+/// motion.push(0.0f64);
+/// motion.push(-0.08f64);
+/// motion.push(0.0f64);
+///
+/// let tag = Tag::from(motion);
+/// let motion = ListTag::<f64>::try_from(tag).unwrap();
+/// assert_eq!(motion[1], -0.08);
+///
+/// let mixed = Tag::List(vec![Tag::Double(0.0), Tag::Float(0.0)]);
+/// assert!(ListTag::<f64>::try_from(mixed).is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ListTag<T> {
+    values: Vec<T>,
+}
+
+impl<T: ListElement> ListTag<T> {
+    pub fn new() -> Self {
+        ListTag { values: Vec::new() }
+    }
+
+    pub fn push(&mut self, value: T) {
+        self.values.push(value);
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        self.values.pop()
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        &mut self.values
+    }
+
+    pub fn into_vec(self) -> Vec<T> {
+        self.values
+    }
+}
+
+impl<T: ListElement> Default for ListTag<T> {
+    fn default() -> Self {
+        ListTag::new()
+    }
+}
+
+impl<T> Deref for ListTag<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        &self.values
+    }
+}
+
+impl<T: ListElement> From<Vec<T>> for ListTag<T> {
+    fn from(values: Vec<T>) -> Self {
+        ListTag { values }
+    }
+}
+
+impl<T: ListElement> std::iter::FromIterator<T> for ListTag<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        ListTag {
+            values: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl<T: ListElement> IntoIterator for ListTag<T> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.values.into_iter()
+    }
+}
+
+impl<T: ListElement> From<ListTag<T>> for Tag {
+    fn from(list: ListTag<T>) -> Self {
+        Tag::List(list.values.into_iter().map(Into::into).collect())
+    }
+}
+
+impl<T: ListElement> TryFrom<Tag> for ListTag<T> {
+    type Error = Tag;
+
+    /// Converts list tag if all its elements have the expected type,
+    /// otherwise the original tag is returned back.
+    fn try_from(tag: Tag) -> Result<Self, Self::Error> {
+        match tag {
+            Tag::List(tags) if tags.iter().all(|tag| tag.tag_type() == T::TAG_TYPE) => {
+                let mut values = Vec::with_capacity(tags.len());
+
+                for tag in tags {
+                    values.push(T::try_from(tag)?);
+                }
+
+                Ok(ListTag { values })
+            }
+            actual_tag => Err(actual_tag),
+        }
+    }
+}
+
+#[test]
+fn test_list_tag() {
+    let list: ListTag<String> = vec!["a".to_string(), "b".to_string()].into();
+    let tag = Tag::from(list.clone());
+
+    assert_eq!(
+        tag,
+        Tag::List(vec![
+            Tag::String("a".to_string()),
+            Tag::String("b".to_string())
+        ])
+    );
+    assert_eq!(ListTag::<String>::try_from(tag.clone()).unwrap(), list);
+
+    let tag = ListTag::<i32>::try_from(tag).unwrap_err();
+    assert!(tag.is_list());
+
+    let empty = ListTag::<CompoundTag>::try_from(Tag::List(Vec::new())).unwrap();
+    assert!(empty.is_empty());
+
+    let not_list = ListTag::<i32>::try_from(Tag::Int(1)).unwrap_err();
+    assert_eq!(not_list, Tag::Int(1));
+}
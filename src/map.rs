@@ -0,0 +1,325 @@
+//! Ordered map of tags inside of a compound tag.
+//!
+//! Backed by `linked-hash-map` by default or by `indexmap` with the feature of the same name,
+//! both behave the same way. Names are hashed with the std hasher, or with faster `ahash`
+//! with the feature of the same name. With `compact_str` feature short names are stored inline.
+//! With `shared` feature maps are reference counted and copied on write, so clones of
+//! compound tags share their contents until modified.
+use crate::Tag;
+use std::borrow::Borrow;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+
+#[cfg(not(feature = "ahash"))]
+type BuildHasher = std::collections::hash_map::RandomState;
+#[cfg(feature = "ahash")]
+type BuildHasher = ahash::RandomState;
+
+#[cfg(not(feature = "compact_str"))]
+type OwnedKey = String;
+#[cfg(feature = "compact_str")]
+type OwnedKey = compact_str::CompactString;
+
+/// Name of a tag as stored in the map, either owned or shared by an interner.
+#[derive(Debug, Clone)]
+pub(crate) enum Key {
+    Owned(OwnedKey),
+    Shared(Arc<str>),
+}
+
+impl Key {
+    fn as_str(&self) -> &str {
+        match self {
+            Key::Owned(name) => name.as_str(),
+            Key::Shared(name) => name,
+        }
+    }
+}
+
+impl PartialEq for Key {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl Eq for Key {}
+
+/// Hashed as `str`, so map can be searched by `&str`.
+impl Hash for Key {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state);
+    }
+}
+
+impl Borrow<str> for Key {
+    fn borrow(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl From<String> for Key {
+    // Conversion is needed with `compact_str` feature.
+    #[allow(clippy::useless_conversion)]
+    fn from(name: String) -> Self {
+        Key::Owned(name.into())
+    }
+}
+
+impl From<&str> for Key {
+    fn from(name: &str) -> Self {
+        Key::Owned(name.into())
+    }
+}
+
+impl From<Arc<str>> for Key {
+    fn from(name: Arc<str>) -> Self {
+        Key::Shared(name)
+    }
+}
+
+#[cfg(not(feature = "indexmap"))]
+type Inner = linked_hash_map::LinkedHashMap<Key, Tag, BuildHasher>;
+#[cfg(feature = "indexmap")]
+type Inner = indexmap::IndexMap<Key, Tag, BuildHasher>;
+
+#[cfg(not(feature = "indexmap"))]
+type InnerIntoIter = linked_hash_map::IntoIter<Key, Tag>;
+#[cfg(feature = "indexmap")]
+type InnerIntoIter = indexmap::map::IntoIter<Key, Tag>;
+
+#[cfg(not(feature = "shared"))]
+type Storage = Inner;
+#[cfg(feature = "shared")]
+type Storage = Arc<Inner>;
+
+/// Map which keeps tags in insertion order.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct TagMap(Storage);
+
+impl TagMap {
+    pub(crate) fn new() -> Self {
+        TagMap(Storage::default())
+    }
+
+    #[cfg(not(feature = "shared"))]
+    fn inner_mut(&mut self) -> &mut Inner {
+        &mut self.0
+    }
+
+    /// Copies map if it is shared with other compound tags.
+    #[cfg(feature = "shared")]
+    fn inner_mut(&mut self) -> &mut Inner {
+        Arc::make_mut(&mut self.0)
+    }
+
+    #[cfg(not(feature = "shared"))]
+    fn into_inner(self) -> Inner {
+        self.0
+    }
+
+    #[cfg(feature = "shared")]
+    fn into_inner(self) -> Inner {
+        Arc::try_unwrap(self.0).unwrap_or_else(|inner| (*inner).clone())
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub(crate) fn contains_key(&self, name: &str) -> bool {
+        self.0.contains_key(name)
+    }
+
+    pub(crate) fn get(&self, name: &str) -> Option<&Tag> {
+        self.0.get(name)
+    }
+
+    pub(crate) fn get_mut(&mut self, name: &str) -> Option<&mut Tag> {
+        self.inner_mut().get_mut(name)
+    }
+
+    /// Inserts tag at the end, existing tag with the same name is replaced and moved to the end.
+    #[cfg(not(feature = "indexmap"))]
+    pub(crate) fn insert(&mut self, name: impl Into<Key>, tag: Tag) -> Option<Tag> {
+        self.inner_mut().insert(name.into(), tag)
+    }
+
+    /// Inserts tag at the end, existing tag with the same name is replaced and moved to the end.
+    #[cfg(feature = "indexmap")]
+    pub(crate) fn insert(&mut self, name: impl Into<Key>, tag: Tag) -> Option<Tag> {
+        let inner = self.inner_mut();
+        let (index, previous) = inner.insert_full(name.into(), tag);
+
+        if previous.is_some() {
+            inner.move_index(index, inner.len() - 1);
+        }
+
+        previous
+    }
+
+    /// Removes tag keeping order of the rest.
+    #[cfg(not(feature = "indexmap"))]
+    pub(crate) fn remove(&mut self, name: &str) -> Option<Tag> {
+        self.inner_mut().remove(name)
+    }
+
+    /// Removes tag keeping order of the rest.
+    #[cfg(feature = "indexmap")]
+    pub(crate) fn remove(&mut self, name: &str) -> Option<Tag> {
+        self.inner_mut().shift_remove(name)
+    }
+
+    /// Number of bytes taken by names on the heap.
+    pub(crate) fn names_heap_size(&self) -> usize {
+        self.0.keys().map(key_heap_size).sum()
+    }
+
+    pub(crate) fn iter(&self) -> impl DoubleEndedIterator<Item = (&str, &Tag)> {
+        self.0.iter().map(|(name, tag)| (name.as_str(), tag))
+    }
+
+    pub(crate) fn iter_mut(&mut self) -> impl DoubleEndedIterator<Item = (&str, &mut Tag)> {
+        self.inner_mut()
+            .iter_mut()
+            .map(|(name, tag)| (name.as_str(), tag))
+    }
+}
+
+/// Shared names are owned by the interner and not counted.
+#[cfg(not(feature = "compact_str"))]
+fn key_heap_size(name: &Key) -> usize {
+    match name {
+        Key::Owned(name) => name.capacity(),
+        Key::Shared(_) => 0,
+    }
+}
+
+/// Shared names are owned by the interner and not counted.
+#[cfg(feature = "compact_str")]
+fn key_heap_size(name: &Key) -> usize {
+    match name {
+        Key::Owned(name) if name.is_heap_allocated() => name.capacity(),
+        _ => 0,
+    }
+}
+
+#[cfg(not(feature = "compact_str"))]
+fn key_into_string(name: Key) -> String {
+    match name {
+        Key::Owned(name) => name,
+        Key::Shared(name) => name.to_string(),
+    }
+}
+
+#[cfg(feature = "compact_str")]
+fn key_into_string(name: Key) -> String {
+    match name {
+        Key::Owned(name) => name.into_string(),
+        Key::Shared(name) => name.to_string(),
+    }
+}
+
+/// Owning iterator which converts names back into strings.
+pub(crate) struct IntoIter(InnerIntoIter);
+
+impl Iterator for IntoIter {
+    type Item = (String, Tag);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0
+            .next()
+            .map(|(name, tag)| (key_into_string(name), tag))
+    }
+}
+
+impl DoubleEndedIterator for IntoIter {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0
+            .next_back()
+            .map(|(name, tag)| (key_into_string(name), tag))
+    }
+}
+
+impl IntoIterator for TagMap {
+    type Item = (String, Tag);
+    type IntoIter = IntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter(self.into_inner().into_iter())
+    }
+}
+
+impl std::iter::FromIterator<(String, Tag)> for TagMap {
+    fn from_iter<T: IntoIterator<Item = (String, Tag)>>(iter: T) -> Self {
+        let mut map = TagMap::new();
+
+        for (name, tag) in iter {
+            map.insert(name, tag);
+        }
+
+        map
+    }
+}
+
+/// Maps are equal when they contain equal tags in the same order.
+impl PartialEq for TagMap {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+impl Hash for TagMap {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        for entry in self.iter() {
+            entry.hash(state);
+        }
+    }
+}
+
+#[test]
+fn test_tag_map() {
+    let mut map: TagMap = vec![
+        ("a".to_string(), Tag::Byte(1)),
+        ("b".to_string(), Tag::Byte(2)),
+        ("c".to_string(), Tag::Byte(3)),
+    ]
+    .into_iter()
+    .collect();
+
+    assert_eq!(
+        map.insert("a".to_string(), Tag::Byte(4)),
+        Some(Tag::Byte(1))
+    );
+    assert_eq!(map.remove("b"), Some(Tag::Byte(2)));
+
+    let names: Vec<_> = map.iter().map(|(name, _)| name).collect();
+    assert_eq!(names, vec!["c", "a"]);
+
+    let other: TagMap = vec![
+        ("a".to_string(), Tag::Byte(4)),
+        ("c".to_string(), Tag::Byte(3)),
+    ]
+    .into_iter()
+    .collect();
+
+    assert_ne!(map, other);
+}
+
+#[test]
+fn test_tag_map_clone() {
+    let mut map = TagMap::new();
+    map.insert("a", Tag::Byte(1));
+
+    let mut clone = map.clone();
+    clone.insert("b", Tag::Byte(2));
+    *clone.get_mut("a").unwrap() = Tag::Byte(3);
+
+    assert_eq!(map.get("a"), Some(&Tag::Byte(1)));
+    assert!(!map.contains_key("b"));
+    assert_eq!(map.clone().into_iter().count(), 1);
+    assert_eq!(clone.len(), 2);
+}
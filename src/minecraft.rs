@@ -0,0 +1,26 @@
+//! Helpers for data layouts which Minecraft stores inside of tags.
+mod heightmap;
+mod id;
+mod item;
+mod packed;
+mod palette;
+mod player;
+mod pos;
+mod structure;
+#[cfg(feature = "serde_json")]
+mod text;
+mod version;
+
+pub use self::heightmap::{pack_heightmap, unpack_heightmap, HEIGHTMAP_BITS, HEIGHTMAP_LEN};
+pub use self::id::{validate_resource_location, IdError, ResourceLocationError};
+pub use self::item::ItemStack;
+pub use self::packed::{PackedBitArray, PackedLayout};
+pub use self::palette::{BlockStates, PaletteError, SECTION_VOLUME};
+pub use self::player::PlayerData;
+pub use self::pos::{pack_block_pos, unpack_block_pos};
+pub use self::structure::{
+    BlockGrid, StructureBlock, StructureEntity, StructureError, StructureTemplate,
+};
+#[cfg(feature = "serde_json")]
+pub use self::text::{TextComponent, TextComponentError};
+pub use self::version::{detect_version, DataVersion};
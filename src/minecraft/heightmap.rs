@@ -0,0 +1,69 @@
+//! Conversion of chunk heightmaps to and from packed long arrays.
+use super::{PackedBitArray, PackedLayout};
+
+/// Number of columns in a chunk.
+pub const HEIGHTMAP_LEN: usize = 256;
+
+/// Bits per height in `Heightmaps` of a chunk.
+pub const HEIGHTMAP_BITS: u8 = 9;
+
+/// Packs heights of chunk columns, indexed by `x + z * 16`, into a long array
+/// as stored in `Heightmaps` of a chunk.
+///
+/// # Panics
+/// Panics if a height doesn't fit into 9 bits.
+///
+/// # Example
+/// ```
+/// use nbt::minecraft::{pack_heightmap, unpack_heightmap, PackedLayout};
+///
+/// let mut heights = [64; 256];
+/// heights[17] = 80;
+///
+/// let data = pack_heightmap(&heights, PackedLayout::Padded);
+/// assert_eq!(data.len(), 37);
+/// assert_eq!(unpack_heightmap(&data, PackedLayout::Padded), Some(heights));
+/// ```
+pub fn pack_heightmap(heights: &[u16; HEIGHTMAP_LEN], layout: PackedLayout) -> Vec<i64> {
+    let mut array = PackedBitArray::new(HEIGHTMAP_LEN, HEIGHTMAP_BITS, layout);
+
+    for (index, height) in heights.iter().enumerate() {
+        array.set(index, *height as u64);
+    }
+
+    array.into_longs()
+}
+
+/// Unpacks heights of chunk columns from a long array, see [`pack_heightmap`].
+///
+/// Returns `None` if array length doesn't match the layout.
+pub fn unpack_heightmap(data: &[i64], layout: PackedLayout) -> Option<[u16; HEIGHTMAP_LEN]> {
+    let array = PackedBitArray::from_longs(data.to_vec(), HEIGHTMAP_LEN, HEIGHTMAP_BITS, layout)?;
+    let mut heights = [0; HEIGHTMAP_LEN];
+
+    for (height, value) in heights.iter_mut().zip(array.iter()) {
+        *height = value as u16;
+    }
+
+    Some(heights)
+}
+
+#[test]
+fn test_heightmap() {
+    let mut heights = [0; HEIGHTMAP_LEN];
+
+    for (index, height) in heights.iter_mut().enumerate() {
+        *height = (index * 3 % 512) as u16;
+    }
+
+    for layout in [PackedLayout::Compact, PackedLayout::Padded] {
+        let data = pack_heightmap(&heights, layout);
+        assert_eq!(unpack_heightmap(&data, layout), Some(heights));
+    }
+
+    let data = pack_heightmap(&heights, PackedLayout::Compact);
+    assert_eq!(data.len(), 36);
+    assert_eq!(data[0] & 0x1ff, 0);
+    assert_eq!(data[0] >> 9 & 0x1ff, 3);
+    assert_eq!(unpack_heightmap(&data, PackedLayout::Padded), None);
+}
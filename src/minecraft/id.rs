@@ -0,0 +1,230 @@
+//! Namespaced identifiers of blocks, items, entities and other registry entries.
+use crate::{CompoundTag, CompoundTagError};
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+
+/// Checks syntax of a resource location like `minecraft:stone`.
+///
+/// Namespace may contain `a-z`, `0-9`, `_`, `-` and `.`, path may also contain `/`.
+/// Namespace with the colon may be omitted, in which case the game uses `minecraft`.
+///
+/// # Example
+/// ```
+/// use nbt::minecraft::{validate_resource_location, ResourceLocationError};
+///
+/// assert!(validate_resource_location("minecraft:oak_log").is_ok());
+/// assert!(validate_resource_location("my_pack:structures/tower.v2").is_ok());
+/// assert!(validate_resource_location("stone").is_ok());
+///
+/// assert_eq!(
+///     validate_resource_location("minecraft:Oak_Log"),
+///     Err(ResourceLocationError::InvalidChar { index: 10, character: 'O' })
+/// );
+/// ```
+pub fn validate_resource_location(value: &str) -> Result<(), ResourceLocationError> {
+    let (namespace, path, path_start) = match value.find(':') {
+        Some(index) => (Some(&value[..index]), &value[index + 1..], index + 1),
+        None => (None, value, 0),
+    };
+
+    if let Some(namespace) = namespace {
+        if namespace.is_empty() {
+            return Err(ResourceLocationError::EmptyNamespace);
+        }
+
+        check_chars(namespace, 0, is_namespace_char)?;
+    }
+
+    if path.is_empty() {
+        return Err(ResourceLocationError::EmptyPath);
+    }
+
+    check_chars(path, path_start, |c| is_namespace_char(c) || c == '/')
+}
+
+fn is_namespace_char(c: char) -> bool {
+    matches!(c, 'a'..='z' | '0'..='9' | '_' | '-' | '.')
+}
+
+fn check_chars(
+    value: &str,
+    offset: usize,
+    is_valid: impl Fn(char) -> bool,
+) -> Result<(), ResourceLocationError> {
+    match value.char_indices().find(|(_, c)| !is_valid(*c)) {
+        Some((index, character)) => Err(ResourceLocationError::InvalidChar {
+            index: offset + index,
+            character,
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Possible types of errors while validating resource location.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResourceLocationError {
+    /// Colon is present but namespace before it is empty.
+    EmptyNamespace,
+    EmptyPath,
+    /// Character is not allowed, e.g. an upper case letter or a second colon in path.
+    InvalidChar {
+        /// Byte offset of the character.
+        index: usize,
+        character: char,
+    },
+}
+
+impl Error for ResourceLocationError {}
+
+impl Display for ResourceLocationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ResourceLocationError::EmptyNamespace => write!(f, "Namespace is empty"),
+            ResourceLocationError::EmptyPath => write!(f, "Path is empty"),
+            ResourceLocationError::InvalidChar { index, character } => {
+                write!(f, "Invalid character {:?} at {}", character, index)
+            }
+        }
+    }
+}
+
+/// Possible types of errors while reading identifier from a string tag.
+#[derive(Debug)]
+pub enum IdError<'a, 'b> {
+    /// String tag is missing or has wrong type.
+    Tag(CompoundTagError<'a, 'b>),
+    /// String is not a valid resource location.
+    InvalidId {
+        name: &'b str,
+        id: &'a str,
+        error: ResourceLocationError,
+    },
+}
+
+impl<'a, 'b> From<CompoundTagError<'a, 'b>> for IdError<'a, 'b> {
+    fn from(error: CompoundTagError<'a, 'b>) -> Self {
+        IdError::Tag(error)
+    }
+}
+
+impl<'a, 'b> Error for IdError<'a, 'b> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            IdError::InvalidId { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl<'a, 'b> Display for IdError<'a, 'b> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            IdError::Tag(error) => Display::fmt(error, f),
+            IdError::InvalidId { name, id, error } => {
+                write!(f, "Tag {} has invalid id {:?}: {}", name, id, error)
+            }
+        }
+    }
+}
+
+impl CompoundTag {
+    /// Returns string tag after checking that it's a valid resource location,
+    /// see [`validate_resource_location`](crate::minecraft::validate_resource_location).
+    ///
+    /// # Example
+    /// ```
+    /// use nbt::CompoundTag;
+    ///
+    /// let mut item = CompoundTag::new();
+    /// item.insert_str("id", "minecraft:diamond_sword");
+    ///
+    /// assert_eq!(item.get_id("id").unwrap(), "minecraft:diamond_sword");
+    ///
+    /// item.insert_str("id", "minecraft:diamond sword");
+    ///
+    /// assert_eq!(
+    ///     item.get_id("id").unwrap_err().to_string(),
+    ///     "Tag id has invalid id \"minecraft:diamond sword\": Invalid character ' ' at 17"
+    /// );
+    /// ```
+    pub fn get_id<'a, 'b>(&'a self, name: &'b str) -> Result<&'a str, IdError<'a, 'b>> {
+        let id = self.get_str(name)?;
+
+        match validate_resource_location(id) {
+            Ok(()) => Ok(id),
+            Err(error) => Err(IdError::InvalidId { name, id, error }),
+        }
+    }
+}
+
+#[test]
+fn test_validate_resource_location() {
+    assert!(validate_resource_location("a-b_c.d:e/f-g_h.0").is_ok());
+
+    for (value, error) in [
+        ("", ResourceLocationError::EmptyPath),
+        ("minecraft:", ResourceLocationError::EmptyPath),
+        (":stone", ResourceLocationError::EmptyNamespace),
+        (
+            "my/pack:stone",
+            ResourceLocationError::InvalidChar {
+                index: 2,
+                character: '/',
+            },
+        ),
+        (
+            "minecraft:stone:1",
+            ResourceLocationError::InvalidChar {
+                index: 15,
+                character: ':',
+            },
+        ),
+        (
+            "minecraft:stoné",
+            ResourceLocationError::InvalidChar {
+                index: 14,
+                character: 'é',
+            },
+        ),
+    ] {
+        assert_eq!(validate_resource_location(value), Err(error), "{}", value);
+    }
+}
+
+#[test]
+fn test_get_id() {
+    use crate::{Tag, TagType};
+
+    let mut entity = CompoundTag::new();
+    entity.insert_str("id", "Zombie");
+    entity.insert_i32("Age", 0);
+
+    let error = entity.get_id("id").unwrap_err();
+    assert!(error.source().is_some());
+    assert!(matches!(
+        error,
+        IdError::InvalidId {
+            name: "id",
+            id: "Zombie",
+            error: ResourceLocationError::InvalidChar {
+                index: 0,
+                character: 'Z'
+            },
+        }
+    ));
+
+    assert!(matches!(
+        entity.get_id("Age"),
+        Err(IdError::Tag(CompoundTagError::TagWrongType {
+            actual_tag: Tag::Int(0),
+            expected_type: Some(TagType::String),
+            ..
+        }))
+    ));
+    assert!(matches!(
+        entity.get_id("Motion"),
+        Err(IdError::Tag(CompoundTagError::TagNotFound {
+            name: "Motion"
+        }))
+    ));
+}
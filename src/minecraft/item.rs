@@ -0,0 +1,161 @@
+//! Item stacks of inventories and containers.
+use super::DataVersion;
+use crate::{CompoundTag, CompoundTagError};
+
+/// First data version storing item count as `count` int and item data in `components`, 1.20.5.
+const COMPONENTS_VERSION: DataVersion = DataVersion(3837);
+
+/// Item stack as stored in `Inventory`, `Items` and similar lists.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ItemStack {
+    /// Slot inside of the inventory, not stored for single items, e.g. of item entities.
+    pub slot: Option<i8>,
+    /// Namespaced item id, e.g. `minecraft:stone`.
+    pub id: String,
+    pub count: i32,
+    /// Item data, `tag` before 1.20.5 and `components` since.
+    pub nbt: Option<CompoundTag>,
+}
+
+impl ItemStack {
+    pub fn new(id: impl Into<String>, count: i32) -> Self {
+        ItemStack {
+            slot: None,
+            id: id.into(),
+            count,
+            nbt: None,
+        }
+    }
+
+    /// Reads item stack in layout of any version.
+    ///
+    /// Count is read from `count` int or `Count` byte, and defaults to 1 if missing
+    /// as since 1.20.5.
+    ///
+    /// # Example
+    /// ```
+    /// use nbt::minecraft::ItemStack;
+    /// use nbt::CompoundTag;
+    ///
+    /// let mut item = CompoundTag::new();
+    /// item.insert_i8("Slot", 3);
+    /// item.insert_str("id", "minecraft:stone");
+    /// item.insert_i8("Count", 64);
+    ///
+    /// let item_stack = ItemStack::from_compound_tag(&item).unwrap();
+    ///
+    /// assert_eq!(item_stack.slot, Some(3));
+    /// assert_eq!(item_stack.id, "minecraft:stone");
+    /// assert_eq!(item_stack.count, 64);
+    /// ```
+    pub fn from_compound_tag(
+        compound_tag: &CompoundTag,
+    ) -> Result<ItemStack, CompoundTagError<'_, 'static>> {
+        let slot = if compound_tag.contains_key("Slot") {
+            Some(compound_tag.get_i8("Slot")?)
+        } else {
+            None
+        };
+
+        let count = if compound_tag.contains_key("count") {
+            compound_tag.get_i32("count")?
+        } else if compound_tag.contains_key("Count") {
+            compound_tag.get_i8("Count")? as i32
+        } else {
+            1
+        };
+
+        let nbt = ["components", "tag"]
+            .iter()
+            .find(|name| compound_tag.contains_key(*name))
+            .map(|name| compound_tag.get_compound_tag(name).cloned())
+            .transpose()?;
+
+        Ok(ItemStack {
+            slot,
+            id: compound_tag.get_str("id")?.to_string(),
+            count,
+            nbt,
+        })
+    }
+
+    /// Writes item stack in layout of provided version.
+    ///
+    /// Before 1.20.5 count is stored as a byte, larger counts are saturated.
+    ///
+    /// # Example
+    /// ```
+    /// use nbt::minecraft::{DataVersion, ItemStack};
+    ///
+    /// let item_stack = ItemStack::new("minecraft:stone", 64);
+    ///
+    /// let item = item_stack.to_compound_tag(DataVersion(3465));
+    /// assert_eq!(item.to_string(), r#"{"id":"minecraft:stone","Count":64b}"#);
+    ///
+    /// let item = item_stack.to_compound_tag(DataVersion(3953));
+    /// assert_eq!(item.to_string(), r#"{"id":"minecraft:stone","count":64}"#);
+    /// ```
+    pub fn to_compound_tag(&self, version: DataVersion) -> CompoundTag {
+        let has_components = version >= COMPONENTS_VERSION;
+        let mut compound_tag = CompoundTag::new();
+
+        if let Some(slot) = self.slot {
+            compound_tag.insert_i8("Slot", slot);
+        }
+
+        compound_tag.insert_str("id", self.id.as_str());
+
+        if has_components {
+            compound_tag.insert_i32("count", self.count);
+        } else {
+            let count = self.count.clamp(i8::MIN as i32, i8::MAX as i32);
+            compound_tag.insert_i8("Count", count as i8);
+        }
+
+        if let Some(nbt) = &self.nbt {
+            let name = if has_components { "components" } else { "tag" };
+            compound_tag.insert_compound_tag(name, nbt.clone());
+        }
+
+        compound_tag
+    }
+}
+
+#[test]
+fn test_item_stack() {
+    let mut components = CompoundTag::new();
+    components.insert_i32("minecraft:damage", 3);
+
+    let mut item_stack = ItemStack::new("minecraft:diamond_sword", 1);
+    item_stack.slot = Some(-106);
+    item_stack.nbt = Some(components);
+
+    for version in [DataVersion(3465), DataVersion(3837)] {
+        let item = item_stack.to_compound_tag(version);
+        assert_eq!(ItemStack::from_compound_tag(&item).unwrap(), item_stack);
+    }
+
+    let item = item_stack.to_compound_tag(DataVersion(3700));
+    assert!(item.contains_key("tag"));
+    assert_eq!(item.get_i8("Count").unwrap(), 1);
+
+    let mut stack = ItemStack::new("minecraft:stone", 300);
+    let item = stack.to_compound_tag(DataVersion(3700));
+    assert_eq!(item.get_i8("Count").unwrap(), 127);
+
+    stack.count = 1;
+    let mut item = stack.to_compound_tag(DataVersion(3953));
+    item.tags.remove("count");
+    assert_eq!(ItemStack::from_compound_tag(&item).unwrap(), stack);
+
+    item.insert_i16("Slot", 1);
+    assert!(matches!(
+        ItemStack::from_compound_tag(&item),
+        Err(CompoundTagError::TagWrongType { name: "Slot", .. })
+    ));
+
+    assert!(matches!(
+        ItemStack::from_compound_tag(&CompoundTag::new()),
+        Err(CompoundTagError::TagNotFound { name: "id" })
+    ));
+}
@@ -0,0 +1,243 @@
+//! Entries of fixed bit width packed into a long array, as in block states and heightmaps.
+use crate::Tag;
+
+/// How entries are packed into longs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PackedLayout {
+    /// Entries follow each other and may span two longs, used before 1.16.
+    Compact,
+    /// Entries never span two longs, remaining high bits of each long are unused. Used since 1.16.
+    Padded,
+}
+
+impl PackedLayout {
+    /// Returns number of longs needed for provided number of entries.
+    pub fn longs_len(self, len: usize, bits_per_entry: u8) -> usize {
+        let bits_per_entry = bits_per_entry as usize;
+
+        match self {
+            PackedLayout::Compact => (len * bits_per_entry).div_ceil(64),
+            PackedLayout::Padded => len.div_ceil(64 / bits_per_entry),
+        }
+    }
+}
+
+/// Fixed number of unsigned entries of the same bit width stored in a `TAG_Long_Array`.
+///
+/// # Example
+/// ```
+/// use nbt::minecraft::{PackedBitArray, PackedLayout};
+///
+/// // Palette indices of a chunk section with up to 32 block states.
+/// let mut block_states = PackedBitArray::new(4096, 5, PackedLayout::Padded);
+/// block_states.set(1, 17);
+///
+/// assert_eq!(block_states.get(1), Some(17));
+/// assert_eq!(block_states.as_longs().len(), 342);
+///
+/// let data = block_states.into_longs();
+/// let block_states = PackedBitArray::from_longs(data, 4096, 5, PackedLayout::Padded).unwrap();
+/// assert_eq!(block_states.get(1), Some(17));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PackedBitArray {
+    data: Vec<i64>,
+    len: usize,
+    bits_per_entry: u8,
+    layout: PackedLayout,
+}
+
+impl PackedBitArray {
+    /// Creates array of provided number of zero entries.
+    ///
+    /// # Panics
+    /// Panics if bits per entry is not between 1 and 64.
+    pub fn new(len: usize, bits_per_entry: u8, layout: PackedLayout) -> Self {
+        check_bits_per_entry(bits_per_entry);
+
+        PackedBitArray {
+            data: vec![0; layout.longs_len(len, bits_per_entry)],
+            len,
+            bits_per_entry,
+            layout,
+        }
+    }
+
+    /// Wraps longs of a `TAG_Long_Array` holding provided number of entries.
+    ///
+    /// Returns `None` if number of longs doesn't match the number of entries.
+    ///
+    /// # Panics
+    /// Panics if bits per entry is not between 1 and 64.
+    pub fn from_longs(
+        data: Vec<i64>,
+        len: usize,
+        bits_per_entry: u8,
+        layout: PackedLayout,
+    ) -> Option<Self> {
+        check_bits_per_entry(bits_per_entry);
+
+        if data.len() != layout.longs_len(len, bits_per_entry) {
+            return None;
+        }
+
+        Some(PackedBitArray {
+            data,
+            len,
+            bits_per_entry,
+            layout,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn bits_per_entry(&self) -> u8 {
+        self.bits_per_entry
+    }
+
+    pub fn layout(&self) -> PackedLayout {
+        self.layout
+    }
+
+    pub fn as_longs(&self) -> &[i64] {
+        &self.data
+    }
+
+    pub fn into_longs(self) -> Vec<i64> {
+        self.data
+    }
+
+    /// Returns entry at provided position or `None` if it's out of bounds.
+    pub fn get(&self, index: usize) -> Option<u64> {
+        if index >= self.len {
+            return None;
+        }
+
+        let (long, offset) = self.position(index);
+        let mut value = self.data[long] as u64 >> offset;
+
+        // Compact entry continues in the next long.
+        if offset + self.bits_per_entry as usize > 64 {
+            value |= (self.data[long + 1] as u64) << (64 - offset);
+        }
+
+        Some(value & self.mask())
+    }
+
+    /// Replaces entry at provided position.
+    ///
+    /// # Panics
+    /// Panics if position is out of bounds or value doesn't fit into bits per entry.
+    pub fn set(&mut self, index: usize, value: u64) {
+        assert!(
+            index < self.len,
+            "index {} out of bounds of {}",
+            index,
+            self.len
+        );
+        assert!(
+            value & !self.mask() == 0,
+            "value {} doesn't fit into {} bits",
+            value,
+            self.bits_per_entry
+        );
+
+        let (long, offset) = self.position(index);
+        let mask = self.mask();
+
+        let first = self.data[long] as u64 & !(mask << offset) | value << offset;
+        self.data[long] = first as i64;
+
+        if offset + self.bits_per_entry as usize > 64 {
+            let shift = 64 - offset;
+            let second = self.data[long + 1] as u64 & !(mask >> shift) | value >> shift;
+            self.data[long + 1] = second as i64;
+        }
+    }
+
+    /// Returns iterator over all entries in order.
+    pub fn iter(&self) -> impl Iterator<Item = u64> + '_ {
+        (0..self.len).map(move |index| self.get(index).expect("Index is in bounds"))
+    }
+
+    /// Index of the long containing the first bit of an entry and offset of that bit.
+    fn position(&self, index: usize) -> (usize, usize) {
+        let bits_per_entry = self.bits_per_entry as usize;
+
+        match self.layout {
+            PackedLayout::Compact => {
+                let bit = index * bits_per_entry;
+                (bit / 64, bit % 64)
+            }
+            PackedLayout::Padded => {
+                let per_long = 64 / bits_per_entry;
+                (index / per_long, index % per_long * bits_per_entry)
+            }
+        }
+    }
+
+    fn mask(&self) -> u64 {
+        u64::MAX >> (64 - self.bits_per_entry)
+    }
+}
+
+impl From<PackedBitArray> for Tag {
+    fn from(data: PackedBitArray) -> Self {
+        Tag::LongArray(data.into_longs())
+    }
+}
+
+fn check_bits_per_entry(bits_per_entry: u8) {
+    assert!(
+        (1..=64).contains(&bits_per_entry),
+        "bits per entry {} not between 1 and 64",
+        bits_per_entry
+    );
+}
+
+#[test]
+fn test_packed_bit_array() {
+    let values: Vec<u64> = (0..14).map(|i| i * 7 % 32).collect();
+
+    for (layout, expected) in [
+        (PackedLayout::Compact, vec![5087933477912492256, 55]),
+        (PackedLayout::Padded, vec![476247459485104352, 884]),
+    ] {
+        let mut array = PackedBitArray::new(values.len(), 5, layout);
+
+        for (index, value) in values.iter().enumerate() {
+            array.set(index, *value);
+        }
+
+        assert_eq!(array.as_longs(), &expected[..]);
+        assert_eq!(array.iter().collect::<Vec<_>>(), values);
+        assert_eq!(array.get(14), None);
+
+        // Overwriting keeps neighbours, including entry spanning two longs.
+        array.set(12, 31);
+        array.set(12, 20);
+        assert_eq!(array.as_longs(), &expected[..]);
+
+        let array = PackedBitArray::from_longs(expected, values.len(), 5, layout).unwrap();
+        assert_eq!(array.iter().collect::<Vec<_>>(), values);
+    }
+
+    assert!(PackedBitArray::from_longs(vec![0; 3], 14, 5, PackedLayout::Compact).is_none());
+
+    let mut array = PackedBitArray::new(2, 64, PackedLayout::Compact);
+    array.set(1, u64::MAX);
+    assert_eq!(array.as_longs(), &[0, -1]);
+    assert_eq!(Tag::from(array), Tag::LongArray(vec![0, -1]));
+}
+
+#[test]
+#[should_panic(expected = "value 32 doesn't fit into 5 bits")]
+fn test_packed_bit_array_overflow() {
+    PackedBitArray::new(1, 5, PackedLayout::Padded).set(0, 32);
+}
@@ -0,0 +1,267 @@
+//! Block states of chunk sections stored as a palette and packed indices.
+use super::{PackedBitArray, PackedLayout};
+use crate::{CompoundTag, CompoundTagError};
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+
+/// Number of blocks in a chunk section.
+pub const SECTION_VOLUME: usize = 4096;
+
+/// Block states of a chunk section, combining palette with packed indices into it.
+///
+/// Both `block_states` compound tag used since 1.18 and `Palette` with `BlockStates`
+/// tags of 1.13 to 1.17 are supported. Layout of packed indices is detected by the array length.
+///
+/// # Example
+/// ```
+/// use nbt::minecraft::{BlockStates, PackedBitArray, PackedLayout};
+/// use nbt::CompoundTag;
+///
+/// let palette: Vec<CompoundTag> = ["minecraft:air", "minecraft:stone"]
+///     .iter()
+///     .map(|name| {
+///         let mut block_state = CompoundTag::new();
+///         block_state.insert_str("Name", *name);
+///         block_state
+///     })
+///     .collect();
+///
+/// let mut data = PackedBitArray::new(4096, 4, PackedLayout::Padded);
+/// data.set(0, 1);
+///
+/// let mut block_states = CompoundTag::new();
+/// block_states.insert_compound_tag_vec("palette", palette);
+/// block_states.insert("data", data);
+///
+/// let mut section = CompoundTag::new();
+/// section.insert_compound_tag("block_states", block_states);
+///
+/// let block_states = BlockStates::from_section(&section).unwrap();
+///
+/// assert_eq!(block_states.get(0, 0, 0).get_str("Name").unwrap(), "minecraft:stone");
+///
+/// let air = block_states.palette()[0];
+/// assert_eq!(block_states.iter().filter(|state| *state == air).count(), 4095);
+/// ```
+#[derive(Debug, Clone)]
+pub struct BlockStates<'a> {
+    palette: Vec<&'a CompoundTag>,
+    /// Missing if palette has a single state.
+    data: Option<PackedBitArray>,
+}
+
+impl<'a> BlockStates<'a> {
+    /// Reads block states of a section, checking that all indices point into the palette.
+    pub fn from_section(section: &'a CompoundTag) -> Result<Self, PaletteError<'a>> {
+        let (palette_name, data_name, container) = match section.get_compound_tag("block_states") {
+            Ok(block_states) => ("palette", "data", block_states),
+            Err(_) => ("Palette", "BlockStates", section),
+        };
+
+        let palette = container.get_compound_tag_vec(palette_name)?;
+
+        if palette.is_empty() {
+            return Err(PaletteError::EmptyPalette);
+        }
+
+        if palette.len() == 1 && !container.contains_key(data_name) {
+            return Ok(BlockStates {
+                palette,
+                data: None,
+            });
+        }
+
+        let longs = container.get_i64_vec(data_name)?;
+        let bits_per_entry = bits_per_entry(palette.len());
+
+        let layout = [PackedLayout::Padded, PackedLayout::Compact]
+            .iter()
+            .copied()
+            .find(|layout| layout.longs_len(SECTION_VOLUME, bits_per_entry) == longs.len())
+            .ok_or(PaletteError::InvalidDataLength {
+                bits_per_entry,
+                actual_len: longs.len(),
+            })?;
+
+        let data =
+            PackedBitArray::from_longs(longs.clone(), SECTION_VOLUME, bits_per_entry, layout)
+                .expect("Length is checked above");
+
+        if let Some((index, value)) = data
+            .iter()
+            .enumerate()
+            .find(|(_, value)| *value as usize >= palette.len())
+        {
+            return Err(PaletteError::InvalidPaletteIndex {
+                index,
+                palette_index: value,
+            });
+        }
+
+        Ok(BlockStates {
+            palette,
+            data: Some(data),
+        })
+    }
+
+    pub fn palette(&self) -> &[&'a CompoundTag] {
+        &self.palette
+    }
+
+    /// Returns block state at provided position inside of the section, each coordinate is 0 to 15.
+    ///
+    /// # Panics
+    /// Panics if a coordinate is out of bounds.
+    pub fn get(&self, x: usize, y: usize, z: usize) -> &'a CompoundTag {
+        assert!(x < 16 && y < 16 && z < 16, "position out of section bounds");
+
+        self.get_index(y * 256 + z * 16 + x)
+    }
+
+    /// Returns iterator over block states ordered by y, z and x, same as in packed indices.
+    pub fn iter(&self) -> impl Iterator<Item = &'a CompoundTag> + '_ {
+        (0..SECTION_VOLUME).map(move |index| self.get_index(index))
+    }
+
+    fn get_index(&self, index: usize) -> &'a CompoundTag {
+        match &self.data {
+            Some(data) => self.palette[data.get(index).expect("Index is in bounds") as usize],
+            None => self.palette[0],
+        }
+    }
+}
+
+/// Bits per palette index, at least 4 as for block states.
+fn bits_per_entry(palette_len: usize) -> u8 {
+    let bits = usize::BITS - (palette_len - 1).leading_zeros();
+
+    bits.max(4) as u8
+}
+
+/// Possible types of errors while reading block states of a section.
+#[derive(Debug)]
+pub enum PaletteError<'a> {
+    /// Palette or packed indices are missing or have wrong type.
+    Tag(CompoundTagError<'a, 'static>),
+    /// Palette has no states.
+    EmptyPalette,
+    /// Length of packed indices doesn't match either layout.
+    InvalidDataLength {
+        bits_per_entry: u8,
+        actual_len: usize,
+    },
+    /// Packed index points outside of the palette.
+    InvalidPaletteIndex {
+        /// Position of the block inside of section.
+        index: usize,
+        palette_index: u64,
+    },
+}
+
+impl<'a> From<CompoundTagError<'a, 'static>> for PaletteError<'a> {
+    fn from(error: CompoundTagError<'a, 'static>) -> Self {
+        PaletteError::Tag(error)
+    }
+}
+
+impl<'a> Error for PaletteError<'a> {}
+
+impl<'a> Display for PaletteError<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            PaletteError::Tag(error) => Display::fmt(error, f),
+            PaletteError::EmptyPalette => write!(f, "Palette is empty"),
+            PaletteError::InvalidDataLength {
+                bits_per_entry,
+                actual_len,
+            } => write!(
+                f,
+                "Packed indices of {} bits have invalid length {}",
+                bits_per_entry, actual_len
+            ),
+            PaletteError::InvalidPaletteIndex {
+                index,
+                palette_index,
+            } => write!(
+                f,
+                "Block {} has palette index {} out of bounds",
+                index, palette_index
+            ),
+        }
+    }
+}
+
+#[test]
+fn test_block_states() {
+    let palette: Vec<CompoundTag> = (0..17)
+        .map(|i| {
+            let mut block_state = CompoundTag::new();
+            block_state.insert_i32("i", i);
+            block_state
+        })
+        .collect();
+
+    let i = |block_state: &CompoundTag| block_state.get_i32("i").unwrap();
+
+    // 17 states need 5 bits, which layouts pack into arrays of different length.
+    for layout in [PackedLayout::Compact, PackedLayout::Padded] {
+        let mut data = PackedBitArray::new(SECTION_VOLUME, 5, layout);
+
+        for index in 0..SECTION_VOLUME {
+            data.set(index, (index % 17) as u64);
+        }
+
+        let mut section = CompoundTag::new();
+        section.insert_compound_tag_vec("Palette", palette.clone());
+        section.insert("BlockStates", data);
+
+        let block_states = BlockStates::from_section(&section).unwrap();
+        assert_eq!(i(block_states.get(3, 2, 1)), (2 * 256 + 16 + 3) % 17);
+        assert!(block_states
+            .iter()
+            .map(i)
+            .eq((0..4096).map(|index| index % 17)));
+    }
+
+    let mut block_states = CompoundTag::new();
+    block_states.insert_compound_tag_vec("palette", palette[..1].to_vec());
+
+    let mut section = CompoundTag::new();
+    section.insert_compound_tag("block_states", block_states.clone());
+
+    let states = BlockStates::from_section(&section).unwrap();
+    assert!(states.iter().all(|block_state| i(block_state) == 0));
+
+    block_states.insert_compound_tag_vec("palette", palette[..2].to_vec());
+    block_states.insert_i64_vec("data", vec![0x20; 256]);
+    section.insert_compound_tag("block_states", block_states.clone());
+
+    let error = BlockStates::from_section(&section).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Block 1 has palette index 2 out of bounds"
+    );
+
+    block_states.insert_i64_vec("data", vec![0; 3]);
+    section.insert_compound_tag("block_states", block_states.clone());
+
+    let error = BlockStates::from_section(&section).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Packed indices of 4 bits have invalid length 3"
+    );
+
+    block_states.insert_compound_tag_vec("palette", Vec::new());
+    section.insert_compound_tag("block_states", block_states);
+
+    assert!(matches!(
+        BlockStates::from_section(&section),
+        Err(PaletteError::EmptyPalette)
+    ));
+    assert!(matches!(
+        BlockStates::from_section(&CompoundTag::new()),
+        Err(PaletteError::Tag(CompoundTagError::TagNotFound {
+            name: "Palette"
+        }))
+    ));
+}
@@ -0,0 +1,188 @@
+//! Typed access to player data as in `player.dat` and `Player` of `level.dat`.
+use super::{detect_version, DataVersion, ItemStack};
+use crate::{CompoundTag, CompoundTagError};
+
+/// Player compound tag with typed getters and setters for common fields.
+///
+/// All other tags are kept as is and stay accessible with
+/// [`as_compound_tag`](PlayerData::as_compound_tag).
+///
+/// # Example
+/// ```
+/// use nbt::minecraft::{ItemStack, PlayerData};
+/// use nbt::CompoundTag;
+///
+/// let mut tag = CompoundTag::new();
+/// tag.insert_i32("DataVersion", 3465);
+/// tag.insert_str("Dimension", "minecraft:overworld");
+///
+/// let mut player = PlayerData::new(tag);
+/// player.set_pos((0.5, 64.0, 0.5));
+/// player.set_health(20.0);
+/// player.set_inventory(&[ItemStack::new("minecraft:bread", 3)]);
+///
+/// assert_eq!(player.pos().unwrap(), (0.5, 64.0, 0.5));
+/// assert_eq!(player.inventory().unwrap()[0].count, 3);
+///
+/// let tag = player.into_compound_tag();
+/// assert_eq!(tag.get_str("Dimension").unwrap(), "minecraft:overworld");
+///
+/// let inventory = tag.get_compound_tag_vec("Inventory").unwrap();
+/// assert_eq!(inventory[0].get_i8("Count").unwrap(), 3);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PlayerData {
+    tag: CompoundTag,
+}
+
+impl PlayerData {
+    pub fn new(tag: CompoundTag) -> Self {
+        PlayerData { tag }
+    }
+
+    pub fn as_compound_tag(&self) -> &CompoundTag {
+        &self.tag
+    }
+
+    pub fn as_compound_tag_mut(&mut self) -> &mut CompoundTag {
+        &mut self.tag
+    }
+
+    pub fn into_compound_tag(self) -> CompoundTag {
+        self.tag
+    }
+
+    /// Returns data version of player tag, see [`detect_version`].
+    pub fn data_version(&self) -> Option<DataVersion> {
+        detect_version(&self.tag)
+    }
+
+    pub fn pos(&self) -> Result<(f64, f64, f64), CompoundTagError<'_, 'static>> {
+        self.tag.get_pos("Pos")
+    }
+
+    pub fn set_pos(&mut self, pos: (f64, f64, f64)) {
+        self.tag.insert_pos("Pos", pos);
+    }
+
+    pub fn health(&self) -> Result<f32, CompoundTagError<'_, 'static>> {
+        self.tag.get_f32("Health")
+    }
+
+    pub fn set_health(&mut self, health: f32) {
+        self.tag.insert_f32("Health", health);
+    }
+
+    pub fn xp_level(&self) -> Result<i32, CompoundTagError<'_, 'static>> {
+        self.tag.get_i32("XpLevel")
+    }
+
+    pub fn set_xp_level(&mut self, xp_level: i32) {
+        self.tag.insert_i32("XpLevel", xp_level);
+    }
+
+    /// Progress towards the next level from 0 to 1.
+    pub fn xp_progress(&self) -> Result<f32, CompoundTagError<'_, 'static>> {
+        self.tag.get_f32("XpP")
+    }
+
+    pub fn set_xp_progress(&mut self, xp_progress: f32) {
+        self.tag.insert_f32("XpP", xp_progress);
+    }
+
+    pub fn xp_total(&self) -> Result<i32, CompoundTagError<'_, 'static>> {
+        self.tag.get_i32("XpTotal")
+    }
+
+    pub fn set_xp_total(&mut self, xp_total: i32) {
+        self.tag.insert_i32("XpTotal", xp_total);
+    }
+
+    pub fn inventory(&self) -> Result<Vec<ItemStack>, CompoundTagError<'_, 'static>> {
+        self.items("Inventory")
+    }
+
+    /// Replaces inventory, items are written in layout of player data version.
+    pub fn set_inventory(&mut self, items: &[ItemStack]) {
+        self.set_items("Inventory", items);
+    }
+
+    pub fn ender_items(&self) -> Result<Vec<ItemStack>, CompoundTagError<'_, 'static>> {
+        self.items("EnderItems")
+    }
+
+    /// Replaces ender chest contents, items are written in layout of player data version.
+    pub fn set_ender_items(&mut self, items: &[ItemStack]) {
+        self.set_items("EnderItems", items);
+    }
+
+    fn items(&self, name: &'static str) -> Result<Vec<ItemStack>, CompoundTagError<'_, 'static>> {
+        self.tag
+            .get_compound_tag_vec(name)?
+            .into_iter()
+            .map(ItemStack::from_compound_tag)
+            .collect()
+    }
+
+    /// Data versions are missing only before 1.9, using the oldest one keeps the old layout.
+    fn set_items(&mut self, name: &str, items: &[ItemStack]) {
+        let version = self.data_version().unwrap_or(DataVersion(0));
+        let items: Vec<_> = items
+            .iter()
+            .map(|item| item.to_compound_tag(version))
+            .collect();
+
+        self.tag.insert_compound_tag_vec(name, items);
+    }
+}
+
+impl From<CompoundTag> for PlayerData {
+    fn from(tag: CompoundTag) -> Self {
+        PlayerData::new(tag)
+    }
+}
+
+impl From<PlayerData> for CompoundTag {
+    fn from(player: PlayerData) -> Self {
+        player.into_compound_tag()
+    }
+}
+
+#[test]
+fn test_player_data() {
+    let mut tag = CompoundTag::new();
+    tag.insert_i32("DataVersion", 3953);
+    tag.insert_i32("XpLevel", 7);
+    tag.insert_i8("OnGround", 1);
+
+    let mut player = PlayerData::from(tag);
+    assert_eq!(player.xp_level().unwrap(), 7);
+    assert!(matches!(
+        player.health(),
+        Err(CompoundTagError::TagNotFound { name: "Health" })
+    ));
+    assert!(player.inventory().is_err());
+
+    let mut item = ItemStack::new("minecraft:ender_pearl", 16);
+    item.slot = Some(0);
+
+    player.set_ender_items(&[item.clone()]);
+    player.set_xp_progress(0.5);
+    player.set_xp_total(100);
+
+    assert_eq!(player.ender_items().unwrap(), vec![item]);
+    assert_eq!(player.xp_progress().unwrap(), 0.5);
+    assert_eq!(player.xp_total().unwrap(), 100);
+
+    player.as_compound_tag_mut().insert_i32("Score", 5);
+
+    let tag = CompoundTag::from(player);
+    assert!(tag.get_bool("OnGround").unwrap());
+    assert_eq!(tag.get_i32("Score").unwrap(), 5);
+    assert_eq!(
+        tag.get_compound_tag_vec("EnderItems").unwrap()[0]
+            .get_i32("count")
+            .unwrap(),
+        16
+    );
+}
@@ -0,0 +1,163 @@
+//! Positions of entities and blocks.
+use crate::{CompoundTag, CompoundTagError, Tag, TagType};
+
+impl CompoundTag {
+    /// Inserts entity position as a list of 3 doubles, as in `Pos` and `Motion`.
+    pub fn insert_pos(&mut self, name: impl Into<String>, (x, y, z): (f64, f64, f64)) {
+        self.insert_f64_vec(name, vec![x, y, z]);
+    }
+
+    /// Returns entity position stored as a list of 3 doubles.
+    ///
+    /// # Example
+    /// ```
+    /// use nbt::CompoundTag;
+    ///
+    /// let mut entity = CompoundTag::new();
+    /// entity.insert_pos("Pos", (0.5, 64.0, -12.5));
+    ///
+    /// assert_eq!(entity.get_pos("Pos").unwrap(), (0.5, 64.0, -12.5));
+    /// ```
+    pub fn get_pos<'a, 'b>(
+        &'a self,
+        name: &'b str,
+    ) -> Result<(f64, f64, f64), CompoundTagError<'a, 'b>> {
+        match self.tags.get(name) {
+            Some(tag) => match tag {
+                Tag::List(value) => match value.as_slice() {
+                    [Tag::Double(x), Tag::Double(y), Tag::Double(z)] => Ok((*x, *y, *z)),
+                    _ => Err(CompoundTagError::TagWrongType {
+                        name,
+                        actual_tag: tag,
+                        expected_type: None,
+                    }),
+                },
+                actual_tag => Err(CompoundTagError::TagWrongType {
+                    name,
+                    actual_tag,
+                    expected_type: Some(TagType::List),
+                }),
+            },
+            None => Err(CompoundTagError::TagNotFound { name }),
+        }
+    }
+
+    /// Inserts block position as an int array of 3 elements, as used since 1.18.
+    pub fn insert_block_pos(&mut self, name: impl Into<String>, (x, y, z): (i32, i32, i32)) {
+        self.insert_i32_vec(name, vec![x, y, z]);
+    }
+
+    /// Returns block position stored as an int array of 3 elements,
+    /// or as a compound tag with `X`, `Y` and `Z` int tags used before 1.18.
+    ///
+    /// # Example
+    /// ```
+    /// use nbt::CompoundTag;
+    ///
+    /// let mut flower_pos = CompoundTag::new();
+    /// flower_pos.insert_i32("X", 10);
+    /// flower_pos.insert_i32("Y", 64);
+    /// flower_pos.insert_i32("Z", -3);
+    ///
+    /// let mut bee = CompoundTag::new();
+    /// bee.insert_compound_tag("FlowerPos", flower_pos);
+    /// bee.insert_block_pos("hive_pos", (12, 65, -3));
+    ///
+    /// assert_eq!(bee.get_block_pos("FlowerPos").unwrap(), (10, 64, -3));
+    /// assert_eq!(bee.get_block_pos("hive_pos").unwrap(), (12, 65, -3));
+    /// ```
+    pub fn get_block_pos<'a, 'b>(
+        &'a self,
+        name: &'b str,
+    ) -> Result<(i32, i32, i32), CompoundTagError<'a, 'b>> {
+        let wrong_type = |actual_tag, expected_type| CompoundTagError::TagWrongType {
+            name,
+            actual_tag,
+            expected_type,
+        };
+
+        match self.tags.get(name) {
+            Some(tag) => match tag {
+                Tag::IntArray(value) => match value.as_slice() {
+                    [x, y, z] => Ok((*x, *y, *z)),
+                    _ => Err(wrong_type(tag, None)),
+                },
+                Tag::Compound(value) => {
+                    match (value.get_i32("X"), value.get_i32("Y"), value.get_i32("Z")) {
+                        (Ok(x), Ok(y), Ok(z)) => Ok((x, y, z)),
+                        _ => Err(wrong_type(tag, None)),
+                    }
+                }
+                actual_tag => Err(wrong_type(actual_tag, Some(TagType::IntArray))),
+            },
+            None => Err(CompoundTagError::TagNotFound { name }),
+        }
+    }
+}
+
+/// Packs block position into a long with 26 bits for x and z and 12 bits for y,
+/// same as `BlockPos.asLong` of the game and network protocol.
+///
+/// Coordinates out of range are truncated.
+///
+/// # Example
+/// ```
+/// use nbt::minecraft::{pack_block_pos, unpack_block_pos};
+///
+/// let packed = pack_block_pos((-100, 64, 2000));
+/// assert_eq!(unpack_block_pos(packed), (-100, 64, 2000));
+/// ```
+pub fn pack_block_pos((x, y, z): (i32, i32, i32)) -> i64 {
+    (x as i64 & 0x3ff_ffff) << 38 | (z as i64 & 0x3ff_ffff) << 12 | y as i64 & 0xfff
+}
+
+/// Unpacks block position from a long, see [`pack_block_pos`].
+pub fn unpack_block_pos(value: i64) -> (i32, i32, i32) {
+    let x = value >> 38;
+    let y = value << 52 >> 52;
+    let z = value << 26 >> 38;
+
+    (x as i32, y as i32, z as i32)
+}
+
+#[test]
+fn test_pos() {
+    let mut compound_tag = CompoundTag::new();
+    compound_tag.insert_pos("Pos", (1.0, 2.0, 3.0));
+    compound_tag.insert_f64_vec("Short", vec![1.0, 2.0]);
+    compound_tag.insert_block_pos("pos", (-1, -64, 30_000_000));
+    compound_tag.insert_i32_vec("long_pos", vec![1, 2, 3, 4]);
+
+    assert_eq!(compound_tag.get_pos("Pos").unwrap(), (1.0, 2.0, 3.0));
+    assert_eq!(
+        compound_tag.get_block_pos("pos").unwrap(),
+        (-1, -64, 30_000_000)
+    );
+
+    assert!(matches!(
+        compound_tag.get_pos("Short"),
+        Err(CompoundTagError::TagWrongType {
+            expected_type: None,
+            ..
+        })
+    ));
+    assert!(matches!(
+        compound_tag.get_pos("pos"),
+        Err(CompoundTagError::TagWrongType {
+            expected_type: Some(TagType::List),
+            ..
+        })
+    ));
+    assert!(compound_tag.get_block_pos("long_pos").is_err());
+    assert!(compound_tag.get_block_pos("Pos").is_err());
+    assert!(matches!(
+        compound_tag.get_block_pos("missing"),
+        Err(CompoundTagError::TagNotFound { name: "missing" })
+    ));
+
+    for pos in [(0, 0, 0), (-1, -1, -1), (33_554_431, 2047, -33_554_432)] {
+        assert_eq!(unpack_block_pos(pack_block_pos(pos)), pos);
+    }
+
+    assert_eq!(pack_block_pos((1, 2, 3)), 1 << 38 | 3 << 12 | 2);
+}
@@ -0,0 +1,459 @@
+//! Structure templates saved by structure blocks, as in `.nbt` files of data packs.
+use super::DataVersion;
+use crate::{CompoundTag, CompoundTagError, Tag, TagType};
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+
+/// Structure template with a single palette of block states.
+///
+/// Files are gzip compressed and can be read and written with
+/// [`read_gzip_compound_tag`](crate::decode::read_gzip_compound_tag) and
+/// [`write_gzip_compound_tag`](crate::encode::write_gzip_compound_tag).
+/// Templates with several random `palettes`, like shipwrecks, are not supported.
+///
+/// # Example
+/// ```
+/// use nbt::decode::read_gzip_compound_tag;
+/// use nbt::encode::write_gzip_compound_tag;
+/// use nbt::minecraft::{BlockGrid, StructureTemplate};
+/// use nbt::CompoundTag;
+/// use std::io::Cursor;
+///
+/// let palette: Vec<CompoundTag> = ["minecraft:stone", "minecraft:glass"]
+///     .iter()
+///     .map(|name| {
+///         let mut block_state = CompoundTag::new();
+///         block_state.insert_str("Name", *name);
+///         block_state
+///     })
+///     .collect();
+///
+/// // Stone floor with a glass block on top, the rest is left as structure void.
+/// let mut grid = BlockGrid::new((2, 2, 2));
+///
+/// for (x, z) in [(0, 0), (0, 1), (1, 0), (1, 1)] {
+///     grid.set(x, 0, z, Some(0));
+/// }
+///
+/// grid.set(0, 1, 0, Some(1));
+///
+/// let structure = StructureTemplate::from_grid(palette, &grid);
+///
+/// let mut file = Vec::new();
+/// write_gzip_compound_tag(&mut file, &structure.to_compound_tag()).unwrap();
+///
+/// let tag = read_gzip_compound_tag(&mut Cursor::new(file)).unwrap();
+/// let structure = StructureTemplate::from_compound_tag(&tag).unwrap();
+///
+/// assert_eq!(structure.size, (2, 2, 2));
+/// assert_eq!(structure.blocks.len(), 5);
+/// assert_eq!(structure.to_grid(), grid);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StructureTemplate {
+    pub data_version: Option<DataVersion>,
+    pub size: (i32, i32, i32),
+    pub palette: Vec<CompoundTag>,
+    pub blocks: Vec<StructureBlock>,
+    pub entities: Vec<StructureEntity>,
+}
+
+/// Block of a structure template.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StructureBlock {
+    /// Position relative to the template origin.
+    pub pos: (i32, i32, i32),
+    /// Index of the block state in palette.
+    pub state: usize,
+    /// Block entity data, without position.
+    pub nbt: Option<CompoundTag>,
+}
+
+/// Entity of a structure template.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StructureEntity {
+    /// Exact position relative to the template origin.
+    pub pos: (f64, f64, f64),
+    /// Position of the block containing the entity.
+    pub block_pos: (i32, i32, i32),
+    pub nbt: CompoundTag,
+}
+
+impl StructureTemplate {
+    /// Reads template, checking that blocks are inside of it and point into the palette.
+    pub fn from_compound_tag(tag: &CompoundTag) -> Result<Self, StructureError<'_>> {
+        let (x, y, z) = get_int_list_pos(tag, "size")?;
+
+        if x < 0 || y < 0 || z < 0 {
+            return Err(StructureError::InvalidSize { size: (x, y, z) });
+        }
+
+        let palette = tag.get_compound_tag_vec("palette")?;
+        let mut blocks = Vec::new();
+
+        for (index, block) in tag.get_compound_tag_vec("blocks")?.into_iter().enumerate() {
+            let pos = get_int_list_pos(block, "pos")?;
+            let state = block.get_i32("state")?;
+
+            if !(0..x).contains(&pos.0) || !(0..y).contains(&pos.1) || !(0..z).contains(&pos.2) {
+                return Err(StructureError::BlockOutOfBounds { index, pos });
+            }
+
+            if state < 0 || state as usize >= palette.len() {
+                return Err(StructureError::InvalidPaletteIndex { index, state });
+            }
+
+            let nbt = match block.get_compound_tag("nbt") {
+                Ok(nbt) => Some(nbt.clone()),
+                Err(CompoundTagError::TagNotFound { .. }) => None,
+                Err(error) => return Err(error.into()),
+            };
+
+            blocks.push(StructureBlock {
+                pos,
+                state: state as usize,
+                nbt,
+            });
+        }
+
+        // Entities are optional in hand written templates.
+        let entities = match tag.get_compound_tag_vec("entities") {
+            Ok(entities) => entities,
+            Err(CompoundTagError::TagNotFound { .. }) => Vec::new(),
+            Err(error) => return Err(error.into()),
+        };
+
+        let entities = entities
+            .into_iter()
+            .map(|entity| {
+                Ok(StructureEntity {
+                    pos: entity.get_pos("pos")?,
+                    block_pos: get_int_list_pos(entity, "blockPos")?,
+                    nbt: entity.get_compound_tag("nbt")?.clone(),
+                })
+            })
+            .collect::<Result<_, StructureError>>()?;
+
+        Ok(StructureTemplate {
+            data_version: tag.get_i32("DataVersion").ok().map(DataVersion),
+            size: (x, y, z),
+            palette: palette.into_iter().cloned().collect(),
+            blocks,
+            entities,
+        })
+    }
+
+    /// Writes template in the layout of structure files.
+    pub fn to_compound_tag(&self) -> CompoundTag {
+        let blocks = self
+            .blocks
+            .iter()
+            .map(|block| {
+                let mut tag = CompoundTag::new();
+                insert_int_list_pos(&mut tag, "pos", block.pos);
+                tag.insert_i32("state", block.state as i32);
+
+                if let Some(nbt) = &block.nbt {
+                    tag.insert_compound_tag("nbt", nbt.clone());
+                }
+
+                tag
+            })
+            .collect::<Vec<_>>();
+
+        let entities = self
+            .entities
+            .iter()
+            .map(|entity| {
+                let mut tag = CompoundTag::new();
+                tag.insert_pos("pos", entity.pos);
+                insert_int_list_pos(&mut tag, "blockPos", entity.block_pos);
+                tag.insert_compound_tag("nbt", entity.nbt.clone());
+                tag
+            })
+            .collect::<Vec<_>>();
+
+        let mut tag = CompoundTag::new();
+        insert_int_list_pos(&mut tag, "size", self.size);
+        tag.insert_compound_tag_vec("entities", entities);
+        tag.insert_compound_tag_vec("blocks", blocks);
+        tag.insert_compound_tag_vec("palette", self.palette.clone());
+
+        if let Some(DataVersion(data_version)) = self.data_version {
+            tag.insert_i32("DataVersion", data_version);
+        }
+
+        tag
+    }
+
+    /// Creates template without entities and block entity data from palette indices of a grid.
+    pub fn from_grid(palette: Vec<CompoundTag>, grid: &BlockGrid) -> Self {
+        let (x, y, z) = grid.size;
+
+        let blocks = grid
+            .iter()
+            .map(|((x, y, z), state)| StructureBlock {
+                pos: (x as i32, y as i32, z as i32),
+                state,
+                nbt: None,
+            })
+            .collect();
+
+        StructureTemplate {
+            data_version: None,
+            size: (x as i32, y as i32, z as i32),
+            palette,
+            blocks,
+            entities: Vec::new(),
+        }
+    }
+
+    /// Returns palette indices of blocks placed into a grid of template size.
+    ///
+    /// # Panics
+    /// Panics if size is negative or a block is outside of the template,
+    /// which is checked by [`from_compound_tag`](StructureTemplate::from_compound_tag).
+    pub fn to_grid(&self) -> BlockGrid {
+        let (x, y, z) = self.size;
+        assert!(x >= 0 && y >= 0 && z >= 0, "negative structure size");
+
+        let mut grid = BlockGrid::new((x as usize, y as usize, z as usize));
+
+        for block in &self.blocks {
+            let (x, y, z) = block.pos;
+            assert!(x >= 0 && y >= 0 && z >= 0, "block out of structure bounds");
+
+            grid.set(x as usize, y as usize, z as usize, Some(block.state));
+        }
+
+        grid
+    }
+}
+
+/// Dense grid of palette indices, `None` where template has no block.
+///
+/// Missing blocks are structure voids, which keep existing blocks when template is placed.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct BlockGrid {
+    size: (usize, usize, usize),
+    blocks: Vec<Option<usize>>,
+}
+
+impl BlockGrid {
+    /// Creates grid of provided size along x, y and z without blocks.
+    pub fn new(size: (usize, usize, usize)) -> Self {
+        let (x, y, z) = size;
+
+        BlockGrid {
+            size,
+            blocks: vec![None; x * y * z],
+        }
+    }
+
+    pub fn size(&self) -> (usize, usize, usize) {
+        self.size
+    }
+
+    /// Returns palette index of the block at provided position.
+    ///
+    /// # Panics
+    /// Panics if position is out of bounds.
+    pub fn get(&self, x: usize, y: usize, z: usize) -> Option<usize> {
+        self.blocks[self.index(x, y, z)]
+    }
+
+    /// Replaces block at provided position.
+    ///
+    /// # Panics
+    /// Panics if position is out of bounds.
+    pub fn set(&mut self, x: usize, y: usize, z: usize, state: Option<usize>) {
+        let index = self.index(x, y, z);
+        self.blocks[index] = state;
+    }
+
+    /// Returns iterator over positions and palette indices of blocks ordered by y, z and x.
+    pub fn iter(&self) -> impl Iterator<Item = ((usize, usize, usize), usize)> + '_ {
+        let (size_x, _, size_z) = self.size;
+
+        self.blocks
+            .iter()
+            .enumerate()
+            .filter_map(move |(index, state)| {
+                let x = index % size_x;
+                let z = index / size_x % size_z;
+                let y = index / (size_x * size_z);
+
+                state.map(|state| ((x, y, z), state))
+            })
+    }
+
+    fn index(&self, x: usize, y: usize, z: usize) -> usize {
+        let (size_x, size_y, size_z) = self.size;
+        assert!(
+            x < size_x && y < size_y && z < size_z,
+            "position ({}, {}, {}) out of grid bounds",
+            x,
+            y,
+            z
+        );
+
+        (y * size_z + z) * size_x + x
+    }
+}
+
+/// Possible types of errors while reading structure template.
+#[derive(Debug)]
+pub enum StructureError<'a> {
+    /// Required tag is missing or has wrong type.
+    Tag(CompoundTagError<'a, 'static>),
+    /// Size has a negative coordinate.
+    InvalidSize { size: (i32, i32, i32) },
+    /// Block is outside of the template.
+    BlockOutOfBounds {
+        /// Position of the block in `blocks` list.
+        index: usize,
+        pos: (i32, i32, i32),
+    },
+    /// Block state points outside of the palette.
+    InvalidPaletteIndex {
+        /// Position of the block in `blocks` list.
+        index: usize,
+        state: i32,
+    },
+}
+
+impl<'a> From<CompoundTagError<'a, 'static>> for StructureError<'a> {
+    fn from(error: CompoundTagError<'a, 'static>) -> Self {
+        StructureError::Tag(error)
+    }
+}
+
+impl<'a> Error for StructureError<'a> {}
+
+impl<'a> Display for StructureError<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            StructureError::Tag(error) => Display::fmt(error, f),
+            StructureError::InvalidSize { size } => {
+                write!(f, "Structure size {:?} is negative", size)
+            }
+            StructureError::BlockOutOfBounds { index, pos } => {
+                write!(f, "Block {} at {:?} is outside of structure", index, pos)
+            }
+            StructureError::InvalidPaletteIndex { index, state } => write!(
+                f,
+                "Block {} has palette index {} out of bounds",
+                index, state
+            ),
+        }
+    }
+}
+
+/// Structure files store block positions as lists of 3 ints instead of int arrays.
+fn get_int_list_pos<'a>(
+    tag: &'a CompoundTag,
+    name: &'static str,
+) -> Result<(i32, i32, i32), CompoundTagError<'a, 'static>> {
+    match tag.tags.get(name) {
+        Some(tag) => match tag {
+            Tag::List(value) => match value.as_slice() {
+                [Tag::Int(x), Tag::Int(y), Tag::Int(z)] => Ok((*x, *y, *z)),
+                _ => Err(CompoundTagError::TagWrongType {
+                    name,
+                    actual_tag: tag,
+                    expected_type: None,
+                }),
+            },
+            actual_tag => Err(CompoundTagError::TagWrongType {
+                name,
+                actual_tag,
+                expected_type: Some(TagType::List),
+            }),
+        },
+        None => Err(CompoundTagError::TagNotFound { name }),
+    }
+}
+
+fn insert_int_list_pos(tag: &mut CompoundTag, name: &str, (x, y, z): (i32, i32, i32)) {
+    tag.insert(name, vec![Tag::Int(x), Tag::Int(y), Tag::Int(z)]);
+}
+
+#[test]
+fn test_structure_template() {
+    let mut chest = CompoundTag::new();
+    chest.insert_str("Name", "minecraft:chest");
+
+    let mut items = CompoundTag::new();
+    items.insert_compound_tag_vec("Items", Vec::new());
+
+    let mut zombie = CompoundTag::new();
+    zombie.insert_str("id", "minecraft:zombie");
+
+    let structure = StructureTemplate {
+        data_version: Some(DataVersion(3953)),
+        size: (1, 2, 3),
+        palette: vec![chest],
+        blocks: vec![StructureBlock {
+            pos: (0, 1, 2),
+            state: 0,
+            nbt: Some(items),
+        }],
+        entities: vec![StructureEntity {
+            pos: (0.5, 0.0, 1.5),
+            block_pos: (0, 0, 1),
+            nbt: zombie,
+        }],
+    };
+
+    let mut tag = structure.to_compound_tag();
+    assert_eq!(tag.get_i32("DataVersion").unwrap(), 3953);
+    assert_eq!(
+        tag.get_compound_tag_vec("blocks").unwrap()[0]
+            .get_vec("pos")
+            .unwrap(),
+        &vec![Tag::Int(0), Tag::Int(1), Tag::Int(2)]
+    );
+    assert_eq!(
+        StructureTemplate::from_compound_tag(&tag).unwrap(),
+        structure
+    );
+
+    let grid = structure.to_grid();
+    assert_eq!(grid.size(), (1, 2, 3));
+    assert_eq!(grid.get(0, 1, 2), Some(0));
+    assert_eq!(grid.iter().count(), 1);
+
+    tag.tags.remove("entities");
+    let without_entities = StructureTemplate::from_compound_tag(&tag).unwrap();
+    assert!(without_entities.entities.is_empty());
+
+    insert_int_list_pos(&mut tag, "size", (1, 2, 2));
+    let error = StructureTemplate::from_compound_tag(&tag).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Block 0 at (0, 1, 2) is outside of structure"
+    );
+
+    insert_int_list_pos(&mut tag, "size", (1, -2, 3));
+    assert!(matches!(
+        StructureTemplate::from_compound_tag(&tag),
+        Err(StructureError::InvalidSize { size: (1, -2, 3) })
+    ));
+
+    insert_int_list_pos(&mut tag, "size", (1, 2, 3));
+    tag.insert_compound_tag_vec("palette", Vec::new());
+    assert!(matches!(
+        StructureTemplate::from_compound_tag(&tag),
+        Err(StructureError::InvalidPaletteIndex { index: 0, state: 0 })
+    ));
+
+    tag.insert_i32_vec("size", vec![1, 2, 3]);
+    assert!(matches!(
+        StructureTemplate::from_compound_tag(&tag),
+        Err(StructureError::Tag(CompoundTagError::TagWrongType {
+            name: "size",
+            expected_type: Some(TagType::List),
+            ..
+        }))
+    ));
+}
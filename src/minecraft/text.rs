@@ -0,0 +1,279 @@
+//! Chat components stored as JSON inside of string tags, e.g. in signs, books and custom names.
+use crate::{CompoundTag, CompoundTagError};
+use serde_json::{Map, Value};
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+
+/// Simple tree of chat components with text, translation key and basic formatting.
+///
+/// Other fields like click and hover events are not kept.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TextComponent {
+    pub text: String,
+    /// Translation key, displayed instead of text.
+    pub translate: Option<String>,
+    /// Color name like `red` or hex code like `#ff0000`.
+    pub color: Option<String>,
+    pub bold: Option<bool>,
+    pub italic: Option<bool>,
+    pub underlined: Option<bool>,
+    pub strikethrough: Option<bool>,
+    pub obfuscated: Option<bool>,
+    /// Components appended after this one, inheriting its formatting.
+    pub extra: Vec<TextComponent>,
+}
+
+impl TextComponent {
+    /// Creates component with plain text.
+    pub fn text(text: impl Into<String>) -> Self {
+        TextComponent {
+            text: text.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Parses component in any of JSON forms: string, array or object.
+    pub fn from_json(json: &str) -> Result<Self, TextComponentError<'static, 'static>> {
+        let value: Value = serde_json::from_str(json).map_err(TextComponentError::InvalidJson)?;
+
+        component_from_value(&value).ok_or(TextComponentError::InvalidComponent)
+    }
+
+    /// Formats component as JSON object.
+    pub fn to_json(&self) -> String {
+        component_to_value(self).to_string()
+    }
+
+    /// Returns text of this component and all appended ones without formatting.
+    ///
+    /// Translation keys are returned as is.
+    pub fn to_plain_text(&self) -> String {
+        let mut text = String::new();
+        push_plain_text(self, &mut text);
+
+        text
+    }
+}
+
+/// Possible types of errors while parsing chat component.
+#[derive(Debug)]
+pub enum TextComponentError<'a, 'b> {
+    /// String tag is missing or has wrong type.
+    Tag(CompoundTagError<'a, 'b>),
+    InvalidJson(serde_json::Error),
+    /// JSON is not a chat component, e.g. `null` or an empty array.
+    InvalidComponent,
+}
+
+impl<'a, 'b> From<CompoundTagError<'a, 'b>> for TextComponentError<'a, 'b> {
+    fn from(error: CompoundTagError<'a, 'b>) -> Self {
+        TextComponentError::Tag(error)
+    }
+}
+
+impl<'a, 'b> Error for TextComponentError<'a, 'b> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            TextComponentError::InvalidJson(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl<'a, 'b> Display for TextComponentError<'a, 'b> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            TextComponentError::Tag(error) => Display::fmt(error, f),
+            TextComponentError::InvalidJson(error) => write!(f, "Invalid JSON: {}", error),
+            TextComponentError::InvalidComponent => write!(f, "JSON is not a chat component"),
+        }
+    }
+}
+
+impl CompoundTag {
+    /// Inserts chat component as JSON string tag.
+    pub fn insert_text_component(&mut self, name: impl Into<String>, value: &TextComponent) {
+        self.insert_str(name, value.to_json());
+    }
+
+    /// Returns chat component parsed from JSON string tag.
+    ///
+    /// # Example
+    /// ```
+    /// use nbt::minecraft::TextComponent;
+    /// use nbt::CompoundTag;
+    ///
+    /// let mut sign = CompoundTag::new();
+    /// sign.insert_str("Text1", r#"["Hello, ",{"text":"world","color":"red","bold":true}]"#);
+    ///
+    /// let text = sign.get_text_component("Text1").unwrap();
+    ///
+    /// assert_eq!(text.to_plain_text(), "Hello, world");
+    /// assert_eq!(text.extra[0].color.as_deref(), Some("red"));
+    ///
+    /// sign.insert_text_component("Text2", &TextComponent::text("Bye"));
+    /// assert_eq!(sign.get_str("Text2").unwrap(), r#"{"text":"Bye"}"#);
+    /// ```
+    pub fn get_text_component<'a, 'b>(
+        &'a self,
+        name: &'b str,
+    ) -> Result<TextComponent, TextComponentError<'a, 'b>> {
+        let json = self.get_str(name)?;
+        let value: Value = serde_json::from_str(json).map_err(TextComponentError::InvalidJson)?;
+
+        component_from_value(&value).ok_or(TextComponentError::InvalidComponent)
+    }
+}
+
+fn component_from_value(value: &Value) -> Option<TextComponent> {
+    match value {
+        Value::String(text) => Some(TextComponent::text(text.as_str())),
+        Value::Number(number) => Some(TextComponent::text(number.to_string())),
+        Value::Bool(value) => Some(TextComponent::text(value.to_string())),
+        // The first component is the parent of the rest.
+        Value::Array(values) => {
+            let (first, rest) = values.split_first()?;
+            let mut component = component_from_value(first)?;
+
+            for value in rest {
+                component.extra.push(component_from_value(value)?);
+            }
+
+            Some(component)
+        }
+        Value::Object(object) => {
+            let string = |name| object.get(name).and_then(Value::as_str).map(str::to_string);
+            let flag = |name| object.get(name).and_then(Value::as_bool);
+
+            let extra = match object.get("extra") {
+                Some(Value::Array(values)) => values
+                    .iter()
+                    .map(component_from_value)
+                    .collect::<Option<Vec<_>>>()?,
+                _ => Vec::new(),
+            };
+
+            Some(TextComponent {
+                text: string("text").unwrap_or_default(),
+                translate: string("translate"),
+                color: string("color"),
+                bold: flag("bold"),
+                italic: flag("italic"),
+                underlined: flag("underlined"),
+                strikethrough: flag("strikethrough"),
+                obfuscated: flag("obfuscated"),
+                extra,
+            })
+        }
+        Value::Null => None,
+    }
+}
+
+fn component_to_value(component: &TextComponent) -> Value {
+    let mut object = Map::new();
+    object.insert("text".to_string(), Value::from(component.text.as_str()));
+
+    let strings = [
+        ("translate", &component.translate),
+        ("color", &component.color),
+    ];
+
+    for (name, value) in strings {
+        if let Some(value) = value {
+            object.insert(name.to_string(), Value::from(value.as_str()));
+        }
+    }
+
+    let flags = [
+        ("bold", component.bold),
+        ("italic", component.italic),
+        ("underlined", component.underlined),
+        ("strikethrough", component.strikethrough),
+        ("obfuscated", component.obfuscated),
+    ];
+
+    for (name, value) in flags {
+        if let Some(value) = value {
+            object.insert(name.to_string(), Value::from(value));
+        }
+    }
+
+    if !component.extra.is_empty() {
+        let extra = component.extra.iter().map(component_to_value).collect();
+        object.insert("extra".to_string(), Value::Array(extra));
+    }
+
+    Value::Object(object)
+}
+
+fn push_plain_text(component: &TextComponent, text: &mut String) {
+    match &component.translate {
+        Some(key) if component.text.is_empty() => text.push_str(key),
+        _ => text.push_str(&component.text),
+    }
+
+    for component in &component.extra {
+        push_plain_text(component, text);
+    }
+}
+
+#[test]
+fn test_text_component() {
+    use crate::{Tag, TagType};
+
+    let mut component = TextComponent::text("a");
+    component.bold = Some(true);
+    component.extra.push(TextComponent {
+        translate: Some("block.minecraft.stone".to_string()),
+        color: Some("#00ff00".to_string()),
+        ..Default::default()
+    });
+
+    let json = component.to_json();
+    let expected = r##"{"text":"a","bold":true,"extra":[{"text":"","translate":"block.minecraft.stone","color":"#00ff00"}]}"##;
+
+    assert_eq!(
+        serde_json::from_str::<Value>(&json).unwrap(),
+        serde_json::from_str::<Value>(expected).unwrap()
+    );
+    assert_eq!(TextComponent::from_json(&json).unwrap(), component);
+    assert_eq!(component.to_plain_text(), "ablock.minecraft.stone");
+
+    assert_eq!(
+        TextComponent::from_json(r#"[1,true,["x"]]"#)
+            .unwrap()
+            .to_plain_text(),
+        "1truex"
+    );
+    assert!(matches!(
+        TextComponent::from_json("[]"),
+        Err(TextComponentError::InvalidComponent)
+    ));
+    assert!(matches!(
+        TextComponent::from_json(r#"{"text":"a","extra":[null]}"#),
+        Err(TextComponentError::InvalidComponent)
+    ));
+
+    let mut compound_tag = CompoundTag::new();
+    compound_tag.insert_str("CustomName", "\"Bob\"");
+    compound_tag.insert_str("Invalid", "{");
+    compound_tag.insert_i32("Int", 1);
+
+    assert_eq!(
+        compound_tag.get_text_component("CustomName").unwrap(),
+        TextComponent::text("Bob")
+    );
+
+    let error = compound_tag.get_text_component("Invalid").unwrap_err();
+    assert!(error.to_string().starts_with("Invalid JSON: "));
+    assert!(error.source().is_some());
+
+    assert!(matches!(
+        compound_tag.get_text_component("Int"),
+        Err(TextComponentError::Tag(CompoundTagError::TagWrongType {
+            actual_tag: Tag::Int(1),
+            expected_type: Some(TagType::String),
+            ..
+        }))
+    ));
+}
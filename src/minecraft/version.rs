@@ -0,0 +1,150 @@
+//! Data versions of the game.
+use crate::CompoundTag;
+use std::fmt::{self, Display, Formatter};
+
+/// Version of data format stored in `DataVersion` tag, increased by every release and snapshot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DataVersion(pub i32);
+
+/// Data versions of releases, in ascending order.
+const RELEASES: &[(i32, &str)] = &[
+    (169, "1.9"),
+    (175, "1.9.1"),
+    (176, "1.9.2"),
+    (183, "1.9.3"),
+    (184, "1.9.4"),
+    (510, "1.10"),
+    (511, "1.10.1"),
+    (512, "1.10.2"),
+    (819, "1.11"),
+    (921, "1.11.1"),
+    (922, "1.11.2"),
+    (1139, "1.12"),
+    (1241, "1.12.1"),
+    (1343, "1.12.2"),
+    (1519, "1.13"),
+    (1628, "1.13.1"),
+    (1631, "1.13.2"),
+    (1952, "1.14"),
+    (1957, "1.14.1"),
+    (1963, "1.14.2"),
+    (1968, "1.14.3"),
+    (1976, "1.14.4"),
+    (2225, "1.15"),
+    (2227, "1.15.1"),
+    (2230, "1.15.2"),
+    (2566, "1.16"),
+    (2567, "1.16.1"),
+    (2578, "1.16.2"),
+    (2580, "1.16.3"),
+    (2584, "1.16.4"),
+    (2586, "1.16.5"),
+    (2724, "1.17"),
+    (2730, "1.17.1"),
+    (2860, "1.18"),
+    (2865, "1.18.1"),
+    (2975, "1.18.2"),
+    (3105, "1.19"),
+    (3117, "1.19.1"),
+    (3120, "1.19.2"),
+    (3218, "1.19.3"),
+    (3337, "1.19.4"),
+    (3463, "1.20"),
+    (3465, "1.20.1"),
+    (3578, "1.20.2"),
+    (3698, "1.20.3"),
+    (3700, "1.20.4"),
+    (3837, "1.20.5"),
+    (3839, "1.20.6"),
+    (3953, "1.21"),
+    (3955, "1.21.1"),
+    (4080, "1.21.2"),
+    (4082, "1.21.3"),
+    (4189, "1.21.4"),
+];
+
+impl DataVersion {
+    /// Returns name of the release with exactly this data version.
+    pub fn release_name(self) -> Option<&'static str> {
+        RELEASES
+            .binary_search_by_key(&self.0, |(version, _)| *version)
+            .ok()
+            .map(|index| RELEASES[index].1)
+    }
+
+    /// Returns name of the latest release not newer than this data version,
+    /// so snapshots map to the release they follow.
+    pub fn based_on_release(self) -> Option<&'static str> {
+        RELEASES
+            .iter()
+            .rev()
+            .find(|(version, _)| *version <= self.0)
+            .map(|(_, name)| *name)
+    }
+}
+
+/// Formats release name if known, e.g. `1.20.1 (3465)`.
+impl Display for DataVersion {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.release_name() {
+            Some(name) => write!(f, "{} ({})", name, self.0),
+            None => write!(f, "{}", self.0),
+        }
+    }
+}
+
+/// Finds `DataVersion` tag at the root, as in chunks, entities and structures,
+/// or inside of `Data`, as in `level.dat`.
+///
+/// Returns `None` for data older than 1.9, which has no data version.
+///
+/// # Example
+/// ```
+/// use nbt::minecraft::{detect_version, DataVersion};
+/// use nbt::CompoundTag;
+///
+/// let mut data = CompoundTag::new();
+/// data.insert_i32("DataVersion", 3465);
+///
+/// let mut level = CompoundTag::new();
+/// level.insert_compound_tag("Data", data);
+///
+/// let version = detect_version(&level).unwrap();
+///
+/// assert_eq!(version, DataVersion(3465));
+/// assert_eq!(version.release_name(), Some("1.20.1"));
+/// assert!(version >= DataVersion(2860));
+/// ```
+pub fn detect_version(compound_tag: &CompoundTag) -> Option<DataVersion> {
+    std::iter::once(compound_tag)
+        .chain(compound_tag.get_compound_tag("Data").ok())
+        .find_map(|compound_tag| compound_tag.get_i32("DataVersion").ok())
+        .map(DataVersion)
+}
+
+#[test]
+fn test_detect_version() {
+    assert!(RELEASES.windows(2).all(|pair| pair[0].0 < pair[1].0));
+
+    let mut chunk = CompoundTag::new();
+    assert_eq!(detect_version(&chunk), None);
+
+    chunk.insert_compound_tag("Data", CompoundTag::new());
+    assert_eq!(detect_version(&chunk), None);
+
+    chunk.insert_i32("DataVersion", 2975);
+    assert_eq!(detect_version(&chunk), Some(DataVersion(2975)));
+
+    let version = DataVersion(2976);
+    assert_eq!(version.release_name(), None);
+    assert_eq!(version.based_on_release(), Some("1.18.2"));
+    assert_eq!(version.to_string(), "2976");
+    assert_eq!(DataVersion(2975).to_string(), "1.18.2 (2975)");
+    assert_eq!(DataVersion(100).based_on_release(), None);
+
+    assert_eq!(DataVersion(1628).release_name(), Some("1.13.1"));
+    assert_eq!(DataVersion(2580).release_name(), Some("1.16.3"));
+    assert_eq!(DataVersion(3117).release_name(), Some("1.19.1"));
+    assert_eq!(DataVersion(3698).release_name(), Some("1.20.3"));
+    assert_eq!(DataVersion(4081).based_on_release(), Some("1.21.2"));
+}
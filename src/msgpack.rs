@@ -0,0 +1,285 @@
+//! Conversion of tags to MessagePack values and back.
+//!
+//! Compound tags become maps with string keys in the same order, lists become arrays,
+//! strings become strings and byte arrays become binary. Floats and doubles keep their
+//! types as `float 32` and `float 64`. Int and long arrays are written as extension types
+//! with ids `11` and `12` and big endian elements, same as tag type ids.
+//!
+//! MessagePack has a single integer type, so integer tags are read back as `Int` if value
+//! fits or `Long` otherwise. Booleans are read as `Byte`. Unknown tags with ids up to 127
+//! are written as extension types with the same id, others as binary.
+//!
+//! # Example
+//! ```
+//! use nbt::msgpack::{compound_tag_from_msgpack, compound_tag_to_msgpack};
+//! use nbt::CompoundTag;
+//!
+//! let mut compound_tag = CompoundTag::new();
+//! compound_tag.insert_str("id", "minecraft:cow");
+//! compound_tag.insert_f32("Health", 10.0);
+//! compound_tag.insert_i32_vec("UUID", vec![1, 2, 3, 4]);
+//!
+//! let mut bytes = Vec::new();
+//! rmpv::encode::write_value(&mut bytes, &compound_tag_to_msgpack(&compound_tag)).unwrap();
+//!
+//! let value = rmpv::decode::read_value(&mut bytes.as_slice()).unwrap();
+//! assert_eq!(compound_tag_from_msgpack(&value).unwrap(), compound_tag);
+//! ```
+use crate::nbtpath::NbtPath;
+use crate::{CompoundTag, Tag, TagType};
+use rmpv::Value;
+use std::convert::{TryFrom, TryInto};
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+
+/// Converts compound tag to MessagePack map.
+pub fn compound_tag_to_msgpack(compound_tag: &CompoundTag) -> Value {
+    let entries = compound_tag
+        .iter()
+        .map(|(name, tag)| (Value::from(name), tag_to_msgpack(tag)))
+        .collect();
+
+    Value::Map(entries)
+}
+
+/// Converts tag to MessagePack value.
+pub fn tag_to_msgpack(tag: &Tag) -> Value {
+    match tag {
+        Tag::Byte(value) => Value::from(*value),
+        Tag::Short(value) => Value::from(*value),
+        Tag::Int(value) => Value::from(*value),
+        Tag::Long(value) => Value::from(*value),
+        Tag::Float(value) => Value::F32(*value),
+        Tag::Double(value) => Value::F64(*value),
+        Tag::ByteArray(value) => Value::Binary(value.iter().map(|value| *value as u8).collect()),
+        Tag::String(value) => Value::from(value.as_str()),
+        Tag::List(value) => Value::Array(value.iter().map(tag_to_msgpack).collect()),
+        Tag::Compound(value) => compound_tag_to_msgpack(value),
+        Tag::IntArray(value) => {
+            let bytes = value.iter().flat_map(|value| value.to_be_bytes()).collect();
+            Value::Ext(TagType::IntArray.id() as i8, bytes)
+        }
+        Tag::LongArray(value) => {
+            let bytes = value.iter().flat_map(|value| value.to_be_bytes()).collect();
+            Value::Ext(TagType::LongArray.id() as i8, bytes)
+        }
+        Tag::Unknown { id, bytes } => match i8::try_from(*id) {
+            Ok(id) => Value::Ext(id, bytes.clone()),
+            Err(_) => Value::Binary(bytes.clone()),
+        },
+    }
+}
+
+/// Converts MessagePack map back to compound tag.
+pub fn compound_tag_from_msgpack(value: &Value) -> Result<CompoundTag, MsgpackError> {
+    let mut path = NbtPath::new();
+
+    match value_to_tag(value, &mut path)? {
+        Tag::Compound(compound_tag) => Ok(compound_tag),
+        _ => Err(MsgpackError::UnexpectedValue { path }),
+    }
+}
+
+/// Converts MessagePack value back to tag.
+pub fn tag_from_msgpack(value: &Value) -> Result<Tag, MsgpackError> {
+    value_to_tag(value, &mut NbtPath::new())
+}
+
+fn value_to_tag(value: &Value, path: &mut NbtPath) -> Result<Tag, MsgpackError> {
+    let invalid_value = |path: &NbtPath, tag_type| MsgpackError::InvalidValue {
+        path: path.clone(),
+        tag_type,
+    };
+
+    match value {
+        Value::Boolean(value) => Ok(Tag::Byte(*value as i8)),
+        Value::Integer(value) => match value.as_i64() {
+            Some(value) => match i32::try_from(value) {
+                Ok(value) => Ok(Tag::Int(value)),
+                Err(_) => Ok(Tag::Long(value)),
+            },
+            None => Err(invalid_value(path, TagType::Long)),
+        },
+        Value::F32(value) => Ok(Tag::Float(*value)),
+        Value::F64(value) => Ok(Tag::Double(*value)),
+        Value::String(value) => match value.as_str() {
+            Some(value) => Ok(Tag::String(value.to_string())),
+            None => Err(invalid_value(path, TagType::String)),
+        },
+        Value::Binary(bytes) => Ok(Tag::ByteArray(
+            bytes.iter().map(|value| *value as i8).collect(),
+        )),
+        Value::Ext(id, bytes) => match TagType::from_id(*id as u8) {
+            Some(TagType::IntArray) if bytes.len() % 4 == 0 => Ok(Tag::IntArray(
+                bytes
+                    .chunks_exact(4)
+                    .map(|chunk| i32::from_be_bytes(chunk.try_into().unwrap()))
+                    .collect(),
+            )),
+            Some(TagType::LongArray) if bytes.len() % 8 == 0 => Ok(Tag::LongArray(
+                bytes
+                    .chunks_exact(8)
+                    .map(|chunk| i64::from_be_bytes(chunk.try_into().unwrap()))
+                    .collect(),
+            )),
+            Some(tag_type) => Err(invalid_value(path, tag_type)),
+            // Negative ids are reserved by MessagePack itself, e.g. for timestamps.
+            None if *id > 0 => Ok(Tag::Unknown {
+                id: *id as u8,
+                bytes: bytes.clone(),
+            }),
+            None => Err(MsgpackError::UnexpectedValue { path: path.clone() }),
+        },
+        Value::Array(values) => {
+            let mut tags = Vec::with_capacity(values.len());
+
+            for (index, value) in values.iter().enumerate() {
+                path.push_index(index);
+                tags.push(value_to_tag(value, path)?);
+                path.pop();
+            }
+
+            Ok(Tag::List(tags))
+        }
+        Value::Map(entries) => {
+            let mut compound_tag = CompoundTag::new();
+
+            for (name, value) in entries {
+                let name = match name.as_str() {
+                    Some(name) => name,
+                    None => return Err(MsgpackError::NonStringKey { path: path.clone() }),
+                };
+
+                path.push_key(name);
+                compound_tag.insert(name, value_to_tag(value, path)?);
+                path.pop();
+            }
+
+            Ok(Tag::Compound(compound_tag))
+        }
+        Value::Nil => Err(MsgpackError::UnexpectedValue { path: path.clone() }),
+    }
+}
+
+/// Possible types of errors while converting MessagePack back to tags.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MsgpackError {
+    /// MessagePack value can't be a tag, e.g. `nil` or reserved extension type.
+    UnexpectedValue { path: NbtPath },
+    /// Map has a key which is not a valid UTF-8 string.
+    NonStringKey { path: NbtPath },
+    /// Value doesn't fit into the tag type, e.g. integer out of long range.
+    InvalidValue { path: NbtPath, tag_type: TagType },
+}
+
+impl MsgpackError {
+    /// Returns path of the problematic tag.
+    pub fn path(&self) -> &NbtPath {
+        match self {
+            MsgpackError::UnexpectedValue { path } => path,
+            MsgpackError::NonStringKey { path } => path,
+            MsgpackError::InvalidValue { path, .. } => path,
+        }
+    }
+}
+
+impl Error for MsgpackError {}
+
+impl Display for MsgpackError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            MsgpackError::UnexpectedValue { path } => {
+                write!(f, "Tag {} has unexpected MessagePack value", path)
+            }
+            MsgpackError::NonStringKey { path } => {
+                write!(f, "Tag {} has key which is not a string", path)
+            }
+            MsgpackError::InvalidValue { path, tag_type } => {
+                write!(f, "Tag {} has invalid value for {}", path, tag_type)
+            }
+        }
+    }
+}
+
+#[test]
+fn test_msgpack_round_trip() {
+    let mut compound_tag = CompoundTag::new();
+    compound_tag.insert_i32("int", i32::MAX);
+    compound_tag.insert_i64("long", i64::MIN);
+    compound_tag.insert_f32("float", 0.1);
+    compound_tag.insert_f64("double", 0.1);
+    compound_tag.insert_i8_vec("bytes", vec![-128, 127]);
+    compound_tag.insert_i32_vec("ints", vec![-2, 0x0102_0304]);
+    compound_tag.insert_i64_vec("longs", vec![-1]);
+    compound_tag.insert_str_vec("list", vec!["a", "b"]);
+    compound_tag.insert_compound_tag("compound", CompoundTag::new());
+    compound_tag.insert(
+        "unknown",
+        Tag::Unknown {
+            id: 13,
+            bytes: vec![1],
+        },
+    );
+
+    let value = compound_tag_to_msgpack(&compound_tag);
+
+    let mut bytes = Vec::new();
+    rmpv::encode::write_value(&mut bytes, &value).unwrap();
+
+    let decoded = rmpv::decode::read_value(&mut bytes.as_slice()).unwrap();
+    assert_eq!(decoded, value);
+    assert_eq!(compound_tag_from_msgpack(&decoded).unwrap(), compound_tag);
+
+    assert_eq!(
+        tag_to_msgpack(&Tag::IntArray(vec![-2, 0x0102_0304])),
+        Value::Ext(11, vec![255, 255, 255, 254, 1, 2, 3, 4])
+    );
+    assert_eq!(
+        tag_from_msgpack(&tag_to_msgpack(&Tag::Short(1))),
+        Ok(Tag::Int(1))
+    );
+    assert_eq!(
+        tag_to_msgpack(&Tag::Unknown {
+            id: 200,
+            bytes: vec![1],
+        }),
+        Value::Binary(vec![1])
+    );
+}
+
+#[test]
+fn test_msgpack_errors() {
+    let map = |name: Value, value: Value| Value::Map(vec![(name, value)]);
+
+    let error = compound_tag_from_msgpack(&map(
+        Value::from("a"),
+        Value::Array(vec![Value::from(1), Value::Nil]),
+    ))
+    .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Tag a[1] has unexpected MessagePack value"
+    );
+
+    assert_eq!(
+        compound_tag_from_msgpack(&map(Value::from(1), Value::from(1))),
+        Err(MsgpackError::NonStringKey {
+            path: NbtPath::new()
+        })
+    );
+    assert_eq!(
+        tag_from_msgpack(&Value::Ext(12, vec![0; 4])),
+        Err(MsgpackError::InvalidValue {
+            path: NbtPath::new(),
+            tag_type: TagType::LongArray,
+        })
+    );
+    assert_eq!(
+        tag_from_msgpack(&Value::from(u64::MAX)),
+        Err(MsgpackError::InvalidValue {
+            path: NbtPath::new(),
+            tag_type: TagType::Long,
+        })
+    );
+    assert!(tag_from_msgpack(&Value::Ext(-1, vec![0; 4])).is_err());
+}
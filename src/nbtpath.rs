@@ -0,0 +1,1436 @@
+//! Paths addressing tags inside of a tag tree.
+//!
+//! Paths are parsed from vanilla syntax used by `/data` command, e.g.
+//! `Inventory[0].tag.Enchantments[{id:"minecraft:sharpness"}].lvl`.
+use crate::{CompoundTag, CompoundTagError, ErrorKind, Tag, TagType};
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+mod parse;
+
+/// Single step of a [`NbtPath`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PathSegment {
+    /// Tag with provided name inside of a compound tag.
+    Key(String),
+    /// Element at provided position inside of a list tag.
+    Index(usize),
+    /// Elements of a list tag which contain all tags of provided compound tag,
+    /// written as `[{...}]`.
+    Match(CompoundTag),
+    /// All elements of a list tag, written as `[]`.
+    All,
+    /// Compound tag itself if it contains all tags of provided compound tag,
+    /// written as `{...}` after name or at the start of path.
+    Filter(CompoundTag),
+}
+
+/// Location of a tag inside of a tag tree, e.g. `Level.Player.Inventory[3].id`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct NbtPath {
+    segments: Vec<PathSegment>,
+}
+
+impl NbtPath {
+    /// Creates an empty path pointing to the root tag.
+    pub fn new() -> Self {
+        NbtPath::default()
+    }
+
+    pub fn segments(&self) -> &[PathSegment] {
+        &self.segments
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.segments.len()
+    }
+
+    pub fn push_key(&mut self, name: impl Into<String>) {
+        self.segments.push(PathSegment::Key(name.into()));
+    }
+
+    pub fn push_index(&mut self, index: usize) {
+        self.segments.push(PathSegment::Index(index));
+    }
+
+    pub fn pop(&mut self) -> Option<PathSegment> {
+        self.segments.pop()
+    }
+
+    /// Inserts segment at the start, used to build paths while errors propagate to the root.
+    pub(crate) fn push_front(&mut self, segment: PathSegment) {
+        self.segments.insert(0, segment);
+    }
+
+    /// Returns new path with provided name appended.
+    pub fn join_key(&self, name: impl Into<String>) -> NbtPath {
+        let mut path = self.clone();
+        path.push_key(name);
+
+        path
+    }
+
+    /// Returns new path with provided position appended.
+    pub fn join_index(&self, index: usize) -> NbtPath {
+        let mut path = self.clone();
+        path.push_index(index);
+
+        path
+    }
+}
+
+/// Possible types of errors while resolving tag by path.
+///
+/// Unlike [`CompoundTagError`] error contains full path to the problematic tag.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathError {
+    /// Tag with provided path not found.
+    TagNotFound {
+        /// Path of tag which was not found.
+        path: NbtPath,
+    },
+    /// Tag actual type not match expected.
+    TagWrongType {
+        /// Path of tag which type not matched.
+        path: NbtPath,
+        /// Actual tag type.
+        actual_type: TagType,
+        /// Expected tag type if known.
+        expected_type: Option<TagType>,
+    },
+}
+
+impl PathError {
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            PathError::TagNotFound { .. } => ErrorKind::TagNotFound,
+            PathError::TagWrongType { .. } => ErrorKind::TagWrongType,
+        }
+    }
+
+    /// Returns path of the problematic tag.
+    pub fn path(&self) -> &NbtPath {
+        match self {
+            PathError::TagNotFound { path } => path,
+            PathError::TagWrongType { path, .. } => path,
+        }
+    }
+}
+
+impl Error for PathError {}
+
+impl Display for PathError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            PathError::TagNotFound { path } => write!(f, "Tag {} not found", path),
+            PathError::TagWrongType {
+                path,
+                actual_type,
+                expected_type,
+            } => {
+                write!(f, "Tag {} has type {}", path, actual_type)?;
+
+                match expected_type {
+                    Some(expected_type) => write!(f, ", expected {}", expected_type),
+                    None => Ok(()),
+                }
+            }
+        }
+    }
+}
+
+impl<'a, 'b> CompoundTagError<'a, 'b> {
+    /// Converts error into [`PathError`] with full path, given path of compound tag
+    /// on which getter was called.
+    ///
+    /// # Example
+    /// ```
+    /// use nbt::nbtpath::NbtPath;
+    /// use nbt::CompoundTag;
+    ///
+    /// let player = CompoundTag::new();
+    /// let mut path = NbtPath::new();
+    /// path.push_key("Data");
+    /// path.push_key("Player");
+    ///
+    /// let error = player.get_f32("Health").map_err(|e| e.with_path(&path)).unwrap_err();
+    /// assert_eq!(error.to_string(), "Tag Data.Player.Health not found");
+    /// ```
+    pub fn with_path(self, parent: &NbtPath) -> PathError {
+        match self {
+            CompoundTagError::TagNotFound { name } => PathError::TagNotFound {
+                path: parent.join_key(name),
+            },
+            CompoundTagError::TagWrongType {
+                name,
+                actual_tag,
+                expected_type,
+            } => PathError::TagWrongType {
+                path: parent.join_key(name),
+                actual_type: actual_tag.tag_type(),
+                expected_type,
+            },
+        }
+    }
+}
+
+impl NbtPath {
+    /// Returns tag at this path inside of provided compound tag.
+    ///
+    /// Error contains path up to the segment which failed to resolve.
+    /// Empty path can't be resolved since root compound tag isn't a [`Tag`].
+    /// Segments matching several elements resolve to the first one, see [`select`](NbtPath::select).
+    pub fn get<'a>(&self, root: &'a CompoundTag) -> Result<&'a Tag, PathError> {
+        let depth = self.match_root(root)?;
+
+        match self.segments.get(depth) {
+            Some(PathSegment::Key(name)) => {
+                let tag = root.tags.get(name).ok_or_else(|| PathError::TagNotFound {
+                    path: self.prefix(depth + 1),
+                })?;
+
+                resolve(tag, &self.segments[depth + 1..], self, depth + 1)
+            }
+            Some(segment) => Err(wrong_type(self, depth, segment, TagType::Compound)),
+            None => Err(PathError::TagNotFound { path: self.clone() }),
+        }
+    }
+
+    /// Returns tag at this path relative to provided tag.
+    ///
+    /// Empty path resolves to provided tag itself.
+    pub fn get_in<'a>(&self, root: &'a Tag) -> Result<&'a Tag, PathError> {
+        resolve(root, &self.segments, self, 0)
+    }
+
+    /// Returns mutable tag at this path inside of provided compound tag, see [`get`](NbtPath::get).
+    pub fn get_mut<'a>(&self, root: &'a mut CompoundTag) -> Result<&'a mut Tag, PathError> {
+        let depth = self.match_root(root)?;
+
+        match self.segments.get(depth) {
+            Some(PathSegment::Key(name)) => {
+                let tag = root
+                    .tags
+                    .get_mut(name)
+                    .ok_or_else(|| PathError::TagNotFound {
+                        path: self.prefix(depth + 1),
+                    })?;
+
+                resolve_mut(tag, &self.segments[depth + 1..], self, depth + 1)
+            }
+            Some(segment) => Err(wrong_type(self, depth, segment, TagType::Compound)),
+            None => Err(PathError::TagNotFound { path: self.clone() }),
+        }
+    }
+
+    /// Returns mutable tag at this path relative to provided tag, see [`get_in`](NbtPath::get_in).
+    pub fn get_in_mut<'a>(&self, root: &'a mut Tag) -> Result<&'a mut Tag, PathError> {
+        resolve_mut(root, &self.segments, self, 0)
+    }
+
+    /// Puts tag at this path inside of provided compound tag and returns replaced one.
+    ///
+    /// Last name is inserted into its compound tag if missing, keeping position of existing tag
+    /// otherwise. List elements have to exist and can't change type of the list.
+    ///
+    /// # Example
+    /// ```
+    /// use nbt::nbtpath::NbtPath;
+    /// use nbt::{CompoundTag, Tag};
+    ///
+    /// let mut player = CompoundTag::new();
+    /// player.insert_f32("Health", 20.0);
+    ///
+    /// let mut root_tag = CompoundTag::new();
+    /// root_tag.insert_compound_tag("Player", player);
+    ///
+    /// let path: NbtPath = "Player.Health".parse().unwrap();
+    /// let previous = path.set(&mut root_tag, Tag::Float(10.0)).unwrap();
+    ///
+    /// assert_eq!(previous, Some(Tag::Float(20.0)));
+    /// assert_eq!(path.get(&root_tag).unwrap(), &Tag::Float(10.0));
+    /// ```
+    pub fn set(&self, root: &mut CompoundTag, tag: Tag) -> Result<Option<Tag>, PathError> {
+        with_root_tag(root, |root_tag| {
+            let (last, parents) = self.split_target(root_tag)?;
+            let depth = parents.len();
+            let target = self.prefix(depth + 1);
+
+            match (last, resolve_mut(root_tag, parents, self, 0)?) {
+                (PathSegment::Key(name), Tag::Compound(value)) => Ok(set_key(value, name, tag)),
+                (segment, Tag::List(value)) if is_element_segment(segment) => {
+                    match element_index(value, segment) {
+                        Some(index) => replace_element(value, index, tag, &target),
+                        None => Err(PathError::TagNotFound { path: target }),
+                    }
+                }
+                (segment, parent) => Err(wrong_type(self, depth, segment, parent.tag_type())),
+            }
+        })
+    }
+
+    /// Removes tag at this path inside of provided compound tag and returns it.
+    ///
+    /// Order of remaining tags is kept.
+    pub fn remove(&self, root: &mut CompoundTag) -> Result<Tag, PathError> {
+        with_root_tag(root, |root_tag| {
+            let (last, parents) = self.split_target(root_tag)?;
+            let depth = parents.len();
+
+            let removed = match (last, resolve_mut(root_tag, parents, self, 0)?) {
+                (PathSegment::Key(name), Tag::Compound(value)) => value.tags.remove(name),
+                (segment, Tag::List(value)) if is_element_segment(segment) => {
+                    element_index(value, segment).map(|index| value.remove(index))
+                }
+                (segment, parent) => {
+                    return Err(wrong_type(self, depth, segment, parent.tag_type()))
+                }
+            };
+
+            removed.ok_or_else(|| PathError::TagNotFound {
+                path: self.prefix(depth + 1),
+            })
+        })
+    }
+
+    /// Puts tag at this path inside of provided compound tag like [`set`](NbtPath::set),
+    /// creating missing tags along the way, and returns replaced one.
+    ///
+    /// Missing names are created as compound tags or as lists if followed by index or matcher.
+    /// If no element matches, copy of the matcher is added to the list.
+    ///
+    /// # Example
+    /// ```
+    /// use nbt::nbtpath::{InsertOptions, NbtPath};
+    /// use nbt::{CompoundTag, Tag};
+    ///
+    /// let mut root_tag = CompoundTag::new();
+    ///
+    /// let path: NbtPath = "Data.Items[{Slot:0b}].id".parse().unwrap();
+    /// let options = InsertOptions::default();
+    /// path.insert(&mut root_tag, Tag::from("minecraft:stone"), &options).unwrap();
+    ///
+    /// let item = root_tag.get_path("Data.Items[0]").unwrap();
+    /// assert_eq!(item.to_string(), r#"{"Slot":0b,"id":"minecraft:stone"}"#);
+    /// ```
+    pub fn insert(
+        &self,
+        root: &mut CompoundTag,
+        tag: Tag,
+        options: &InsertOptions,
+    ) -> Result<Option<Tag>, PathError> {
+        with_root_tag(root, |root_tag| {
+            let (last, parents) = self.split_target(root_tag)?;
+            let mut parent = root_tag;
+
+            for (depth, segment) in parents.iter().enumerate() {
+                let child = match &self.segments[depth + 1] {
+                    PathSegment::Key(_) | PathSegment::Filter(_) => {
+                        Tag::Compound(CompoundTag::new())
+                    }
+                    PathSegment::Index(_) | PathSegment::Match(_) | PathSegment::All => {
+                        Tag::List(Vec::new())
+                    }
+                };
+
+                parent = entry(parent, segment, child, self, depth, options)?;
+            }
+
+            put(parent, last, tag, self, parents.len(), options)
+        })
+    }
+
+    /// Returns all tags at this path inside of provided compound tag, in order of appearance.
+    ///
+    /// # Example
+    /// ```
+    /// use nbt::nbtpath::NbtPath;
+    /// use nbt::CompoundTag;
+    ///
+    /// let block_entities = ["minecraft:chest", "minecraft:sign"]
+    ///     .iter()
+    ///     .map(|id| {
+    ///         let mut block_entity = CompoundTag::new();
+    ///         block_entity.insert_str("id", *id);
+    ///         block_entity
+    ///     })
+    ///     .collect::<Vec<_>>();
+    ///
+    /// let mut root_tag = CompoundTag::new();
+    /// root_tag.insert_compound_tag_vec("block_entities", block_entities);
+    ///
+    /// let path: NbtPath = "block_entities[].id".parse().unwrap();
+    /// let ids: Vec<_> = path.select(&root_tag).filter_map(|tag| tag.as_str()).collect();
+    ///
+    /// assert_eq!(ids, vec!["minecraft:chest", "minecraft:sign"]);
+    /// ```
+    pub fn select<'a>(&self, root: &'a CompoundTag) -> impl Iterator<Item = &'a Tag> {
+        let mut tags = Vec::new();
+
+        if let Ok(depth) = self.match_root(root) {
+            if let Some(PathSegment::Key(name)) = self.segments.get(depth) {
+                tags.extend(root.tags.get(name));
+
+                for segment in &self.segments[depth + 1..] {
+                    let mut children = Vec::new();
+
+                    for tag in tags {
+                        select_children(tag, segment, &mut children);
+                    }
+
+                    tags = children;
+                }
+            }
+        }
+
+        tags.into_iter()
+    }
+
+    /// Parses RFC 6901 JSON pointer like `/Data/Player/Inventory/0/id`.
+    ///
+    /// Tokens consisting of digits without leading zeros are indices, others are names.
+    /// Empty pointer addresses the root.
+    ///
+    /// # Example
+    /// ```
+    /// use nbt::nbtpath::NbtPath;
+    ///
+    /// let path = NbtPath::from_pointer("/Data/Player/Inventory/0/id").unwrap();
+    ///
+    /// assert_eq!(path, "Data.Player.Inventory[0].id".parse().unwrap());
+    /// assert_eq!(path.to_pointer().unwrap(), "/Data/Player/Inventory/0/id");
+    /// ```
+    pub fn from_pointer(pointer: &str) -> Result<NbtPath, PathParseError> {
+        parse::parse_pointer(pointer).map(NbtPath::from)
+    }
+
+    /// Parses path in vanilla syntax once, so it can be applied to many tags
+    /// without parsing it again like [`CompoundTag::get_path`] does.
+    ///
+    /// # Example
+    /// ```
+    /// use nbt::nbtpath::NbtPath;
+    /// use nbt::CompoundTag;
+    ///
+    /// let path = NbtPath::compile("Data.DataVersion").unwrap();
+    ///
+    /// let levels: Vec<CompoundTag> = (3465..3470)
+    ///     .map(|version| {
+    ///         let mut data = CompoundTag::new();
+    ///         data.insert_i32("DataVersion", version);
+    ///
+    ///         let mut level = CompoundTag::new();
+    ///         level.insert_compound_tag("Data", data);
+    ///         level
+    ///     })
+    ///     .collect();
+    ///
+    /// let versions: Vec<i32> = levels
+    ///     .iter()
+    ///     .map(|level| path.get(level).unwrap().as_i32().unwrap())
+    ///     .collect();
+    ///
+    /// assert_eq!(versions, vec![3465, 3466, 3467, 3468, 3469]);
+    /// ```
+    pub fn compile(path: &str) -> Result<NbtPath, PathParseError> {
+        path.parse()
+    }
+
+    /// Formats path as RFC 6901 JSON pointer.
+    ///
+    /// Returns `None` if path has matchers or names consisting of digits,
+    /// which pointers can't express.
+    pub fn to_pointer(&self) -> Option<String> {
+        let mut pointer = String::new();
+
+        for segment in &self.segments {
+            pointer.push('/');
+
+            match segment {
+                PathSegment::Key(name) => {
+                    if !name.is_empty() && name.bytes().all(|b| b.is_ascii_digit()) {
+                        return None;
+                    }
+
+                    pointer.push_str(&name.replace('~', "~0").replace('/', "~1"));
+                }
+                PathSegment::Index(index) => pointer.push_str(&index.to_string()),
+                PathSegment::Match(_) | PathSegment::All | PathSegment::Filter(_) => return None,
+            }
+        }
+
+        Some(pointer)
+    }
+
+    /// Checks compound matchers at the start of path against the root and returns their number.
+    fn match_root(&self, root: &CompoundTag) -> Result<usize, PathError> {
+        let mut depth = 0;
+
+        while let Some(PathSegment::Filter(pattern)) = self.segments.get(depth) {
+            if !compound_matches(pattern, root) {
+                return Err(PathError::TagNotFound {
+                    path: self.prefix(depth + 1),
+                });
+            }
+
+            depth += 1;
+        }
+
+        Ok(depth)
+    }
+
+    /// Splits segments up to the tag changed by [`set`](NbtPath::set), [`insert`](NbtPath::insert)
+    /// and [`remove`](NbtPath::remove) into the last one and its parents.
+    ///
+    /// Compound matchers at the end only have to match the existing tag.
+    fn split_target(&self, root_tag: &Tag) -> Result<(&PathSegment, &[PathSegment]), PathError> {
+        let len = self
+            .segments
+            .iter()
+            .rposition(|segment| !matches!(segment, PathSegment::Filter(_)))
+            .map_or(0, |index| index + 1);
+
+        if len < self.segments.len() {
+            resolve(root_tag, &self.segments, self, 0)?;
+        }
+
+        self.segments[..len]
+            .split_last()
+            .ok_or_else(|| PathError::TagNotFound { path: self.clone() })
+    }
+
+    fn prefix(&self, len: usize) -> NbtPath {
+        NbtPath {
+            segments: self.segments[..len].to_vec(),
+        }
+    }
+}
+
+fn resolve<'a>(
+    mut tag: &'a Tag,
+    segments: &[PathSegment],
+    path: &NbtPath,
+    offset: usize,
+) -> Result<&'a Tag, PathError> {
+    for (i, segment) in segments.iter().enumerate() {
+        let depth = offset + i;
+
+        if let PathSegment::Filter(pattern) = segment {
+            check_filter(tag, pattern, path, depth)?;
+            continue;
+        }
+
+        let next = match (segment, tag) {
+            (PathSegment::Key(name), Tag::Compound(value)) => value.tags.get(name),
+            (segment, Tag::List(value)) if is_element_segment(segment) => {
+                element_index(value, segment).map(|index| &value[index])
+            }
+            (segment, tag) => return Err(wrong_type(path, depth, segment, tag.tag_type())),
+        };
+
+        tag = next.ok_or_else(|| PathError::TagNotFound {
+            path: path.prefix(depth + 1),
+        })?;
+    }
+
+    Ok(tag)
+}
+
+fn resolve_mut<'a>(
+    mut tag: &'a mut Tag,
+    segments: &[PathSegment],
+    path: &NbtPath,
+    offset: usize,
+) -> Result<&'a mut Tag, PathError> {
+    for (i, segment) in segments.iter().enumerate() {
+        let depth = offset + i;
+
+        if let PathSegment::Filter(pattern) = segment {
+            check_filter(tag, pattern, path, depth)?;
+            continue;
+        }
+
+        let next = match (segment, tag) {
+            (PathSegment::Key(name), Tag::Compound(value)) => value.tags.get_mut(name),
+            (PathSegment::Key(_), tag) => {
+                return Err(wrong_type(path, depth, segment, tag.tag_type()))
+            }
+            // Compound matchers are checked above, the rest address list elements.
+            (segment, Tag::List(value)) => {
+                element_index(value, segment).map(move |index| &mut value[index])
+            }
+            (segment, tag) => return Err(wrong_type(path, depth, segment, tag.tag_type())),
+        };
+
+        tag = next.ok_or_else(|| PathError::TagNotFound {
+            path: path.prefix(depth + 1),
+        })?;
+    }
+
+    Ok(tag)
+}
+
+/// Error for segment at provided depth which can't be applied to tag of provided type.
+fn wrong_type(
+    path: &NbtPath,
+    depth: usize,
+    segment: &PathSegment,
+    actual_type: TagType,
+) -> PathError {
+    let expected_type = match segment {
+        PathSegment::Key(_) | PathSegment::Filter(_) => TagType::Compound,
+        PathSegment::Index(_) | PathSegment::Match(_) | PathSegment::All => TagType::List,
+    };
+
+    PathError::TagWrongType {
+        path: path.prefix(depth),
+        actual_type,
+        expected_type: Some(expected_type),
+    }
+}
+
+/// Replaces existing tag keeping its position, or inserts new one at the end.
+fn set_key(compound_tag: &mut CompoundTag, name: &str, tag: Tag) -> Option<Tag> {
+    match compound_tag.tags.get_mut(name) {
+        Some(previous) => Some(std::mem::replace(previous, tag)),
+        None => {
+            compound_tag.tags.insert(name, tag);
+            None
+        }
+    }
+}
+
+/// Replaces list element, which may only change type if it's the only one.
+fn replace_element(
+    tags: &mut [Tag],
+    index: usize,
+    tag: Tag,
+    path: &NbtPath,
+) -> Result<Option<Tag>, PathError> {
+    let expected_type = tags[index].tag_type();
+
+    if tags.len() > 1 && tag.tag_type() != expected_type {
+        return Err(PathError::TagWrongType {
+            path: path.clone(),
+            actual_type: tag.tag_type(),
+            expected_type: Some(expected_type),
+        });
+    }
+
+    Ok(Some(std::mem::replace(&mut tags[index], tag)))
+}
+
+/// Returns child tag of parent at provided depth, inserting provided one if missing.
+fn entry<'a>(
+    parent: &'a mut Tag,
+    segment: &PathSegment,
+    child: Tag,
+    path: &NbtPath,
+    depth: usize,
+    options: &InsertOptions,
+) -> Result<&'a mut Tag, PathError> {
+    if let PathSegment::Filter(pattern) = segment {
+        check_filter(parent, pattern, path, depth)?;
+        return Ok(parent);
+    }
+
+    match (segment, parent) {
+        (PathSegment::Key(name), Tag::Compound(value)) => {
+            if !value.tags.contains_key(name) {
+                value.tags.insert(name.as_str(), child);
+            }
+
+            Ok(value.tags.get_mut(name).expect("Tag is inserted above"))
+        }
+        (PathSegment::Index(index), Tag::List(value)) => {
+            if *index >= value.len() {
+                if !options.create_list_slots {
+                    return Err(PathError::TagNotFound {
+                        path: path.prefix(depth + 1),
+                    });
+                }
+
+                check_element_type(value, child.tag_type(), &path.prefix(depth + 1))?;
+
+                value.resize(*index, child.tag_type().default_tag());
+                value.push(child);
+            }
+
+            Ok(&mut value[*index])
+        }
+        (PathSegment::Match(pattern), Tag::List(value)) => {
+            let index = match value.iter().position(|tag| element_matches(pattern, tag)) {
+                Some(index) => index,
+                None => {
+                    check_element_type(value, TagType::Compound, &path.prefix(depth + 1))?;
+                    value.push(Tag::Compound(pattern.clone()));
+
+                    value.len() - 1
+                }
+            };
+
+            Ok(&mut value[index])
+        }
+        (PathSegment::All, Tag::List(value)) => {
+            if value.is_empty() {
+                value.push(child);
+            }
+
+            Ok(&mut value[0])
+        }
+        (segment, parent) => Err(wrong_type(path, depth, segment, parent.tag_type())),
+    }
+}
+
+/// Puts tag as the last segment of path, replacing existing one or inserting it.
+fn put(
+    parent: &mut Tag,
+    segment: &PathSegment,
+    tag: Tag,
+    path: &NbtPath,
+    depth: usize,
+    options: &InsertOptions,
+) -> Result<Option<Tag>, PathError> {
+    let target = path.prefix(depth + 1);
+
+    match (segment, parent) {
+        (PathSegment::Key(name), Tag::Compound(value)) => Ok(set_key(value, name, tag)),
+        (segment, Tag::List(value)) if element_index(value, segment).is_some() => {
+            let index = element_index(value, segment).expect("Index is checked above");
+
+            replace_element(value, index, tag, &target)
+        }
+        (PathSegment::Match(_), Tag::List(value)) | (PathSegment::All, Tag::List(value)) => {
+            check_element_type(value, tag.tag_type(), &target)?;
+            value.push(tag);
+
+            Ok(None)
+        }
+        (segment, parent) => {
+            entry(parent, segment, tag, path, depth, options)?;
+
+            Ok(None)
+        }
+    }
+}
+
+/// Checks that tag of provided type can be added to the list.
+fn check_element_type(tags: &[Tag], tag_type: TagType, path: &NbtPath) -> Result<(), PathError> {
+    match tags.first() {
+        Some(first) if first.tag_type() != tag_type => Err(PathError::TagWrongType {
+            path: path.clone(),
+            actual_type: tag_type,
+            expected_type: Some(first.tag_type()),
+        }),
+        _ => Ok(()),
+    }
+}
+
+/// Checks that tag at provided depth is a compound tag matching the pattern.
+fn check_filter(
+    tag: &Tag,
+    pattern: &CompoundTag,
+    path: &NbtPath,
+    depth: usize,
+) -> Result<(), PathError> {
+    match tag {
+        Tag::Compound(value) if compound_matches(pattern, value) => Ok(()),
+        Tag::Compound(_) => Err(PathError::TagNotFound {
+            path: path.prefix(depth + 1),
+        }),
+        tag => Err(PathError::TagWrongType {
+            path: path.prefix(depth),
+            actual_type: tag.tag_type(),
+            expected_type: Some(TagType::Compound),
+        }),
+    }
+}
+
+/// Whether segment addresses elements of a list tag.
+fn is_element_segment(segment: &PathSegment) -> bool {
+    matches!(
+        segment,
+        PathSegment::Index(_) | PathSegment::Match(_) | PathSegment::All
+    )
+}
+
+/// Position of list element addressed by segment, the first one if segment matches several.
+fn element_index(tags: &[Tag], segment: &PathSegment) -> Option<usize> {
+    match segment {
+        PathSegment::Index(index) if *index < tags.len() => Some(*index),
+        PathSegment::Match(pattern) => tags.iter().position(|tag| element_matches(pattern, tag)),
+        PathSegment::All if !tags.is_empty() => Some(0),
+        _ => None,
+    }
+}
+
+/// Adds tags addressed by segment relative to provided tag.
+fn select_children<'a>(tag: &'a Tag, segment: &PathSegment, children: &mut Vec<&'a Tag>) {
+    match (segment, tag) {
+        (PathSegment::Key(name), Tag::Compound(value)) => children.extend(value.tags.get(name)),
+        (PathSegment::Index(index), Tag::List(value)) => children.extend(value.get(*index)),
+        (PathSegment::Match(pattern), Tag::List(value)) => {
+            children.extend(value.iter().filter(|tag| element_matches(pattern, tag)))
+        }
+        (PathSegment::All, Tag::List(value)) => children.extend(value),
+        (PathSegment::Filter(pattern), Tag::Compound(value))
+            if compound_matches(pattern, value) =>
+        {
+            children.push(tag)
+        }
+        _ => {}
+    }
+}
+
+/// Calls function with root compound tag temporarily moved into a tag,
+/// so it's walked the same way as nested tags.
+fn with_root_tag<T>(root: &mut CompoundTag, f: impl FnOnce(&mut Tag) -> T) -> T {
+    let mut root_tag = Tag::Compound(std::mem::take(root));
+    let result = f(&mut root_tag);
+
+    if let Tag::Compound(value) = root_tag {
+        *root = value;
+    }
+
+    result
+}
+
+fn element_matches(pattern: &CompoundTag, tag: &Tag) -> bool {
+    matches!(tag, Tag::Compound(tag) if compound_matches(pattern, tag))
+}
+
+/// Whether compound tag contains every tag of provided pattern, as in vanilla matchers.
+///
+/// Nested compound tags are matched the same way. Nested non-empty lists match if every element
+/// of the pattern matches some element, other tags have to be equal.
+fn compound_matches(pattern: &CompoundTag, compound_tag: &CompoundTag) -> bool {
+    pattern
+        .iter()
+        .all(|(name, pattern)| match compound_tag.tags.get(name) {
+            Some(tag) => tag_matches(pattern, tag),
+            None => false,
+        })
+}
+
+fn tag_matches(pattern: &Tag, tag: &Tag) -> bool {
+    match (pattern, tag) {
+        (Tag::Compound(pattern), Tag::Compound(tag)) => compound_matches(pattern, tag),
+        (Tag::List(pattern), Tag::List(tags)) if !pattern.is_empty() => pattern
+            .iter()
+            .all(|pattern| tags.iter().any(|tag| tag_matches(pattern, tag))),
+        _ => pattern == tag,
+    }
+}
+
+impl From<Vec<PathSegment>> for NbtPath {
+    fn from(segments: Vec<PathSegment>) -> Self {
+        NbtPath { segments }
+    }
+}
+
+/// Formats path preceded by a space, or nothing for the root tag.
+pub(crate) fn fmt_path(f: &mut Formatter<'_>, path: &NbtPath) -> fmt::Result {
+    if path.is_empty() {
+        Ok(())
+    } else {
+        write!(f, " {}", path)
+    }
+}
+
+impl Display for NbtPath {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (i, segment) in self.segments.iter().enumerate() {
+            match segment {
+                PathSegment::Key(name) => {
+                    if i > 0 {
+                        write!(f, ".")?;
+                    }
+
+                    fmt_key(f, name)?;
+                }
+                PathSegment::Index(index) => write!(f, "[{}]", index)?,
+                PathSegment::Match(pattern) => write!(f, "[{}]", pattern)?,
+                PathSegment::All => write!(f, "[]")?,
+                PathSegment::Filter(pattern) => write!(f, "{}", pattern)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// How missing tags are created by [`NbtPath::insert`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct InsertOptions {
+    /// Create missing list elements up to the index, filling the gap with default tags of
+    /// the same type. Disabled by default, index must point to an existing element.
+    pub create_list_slots: bool,
+}
+
+/// Parses path in vanilla syntax.
+///
+/// # Example
+/// ```
+/// use nbt::nbtpath::{NbtPath, PathSegment};
+///
+/// let path: NbtPath = "Inventory[0].id".parse().unwrap();
+///
+/// assert_eq!(path.segments()[1], PathSegment::Index(0));
+/// assert_eq!(path.to_string(), "Inventory[0].id");
+/// ```
+impl FromStr for NbtPath {
+    type Err = PathParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse::parse_path(s).map(NbtPath::from)
+    }
+}
+
+/// Error while parsing path from string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathParseError {
+    /// Character not allowed at this place.
+    UnexpectedChar {
+        /// Offset of character in bytes.
+        position: usize,
+        /// Actual character.
+        found: char,
+        /// Description of what was expected instead.
+        expected: &'static str,
+    },
+    /// Path ended too early.
+    UnexpectedEnd {
+        /// Description of what was expected instead.
+        expected: &'static str,
+    },
+    /// Index doesn't fit into `usize`.
+    InvalidNumber {
+        /// Offset of number in bytes.
+        position: usize,
+    },
+    /// Compound tags and lists are nested too deep.
+    TooDeep {
+        /// Offset of the first tag beyond the limit in bytes.
+        position: usize,
+    },
+}
+
+impl PathParseError {
+    pub fn kind(&self) -> ErrorKind {
+        ErrorKind::InvalidPath
+    }
+}
+
+impl Error for PathParseError {}
+
+impl Display for PathParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            PathParseError::UnexpectedChar {
+                position,
+                found,
+                expected,
+            } => write!(
+                f,
+                "Unexpected {:?} at position {}, expected {}",
+                found, position, expected
+            ),
+            PathParseError::UnexpectedEnd { expected } => {
+                write!(f, "Unexpected end of path, expected {}", expected)
+            }
+            PathParseError::InvalidNumber { position } => {
+                write!(f, "Index at position {} is too large", position)
+            }
+            PathParseError::TooDeep { position } => {
+                write!(f, "Tag at position {} is nested too deep", position)
+            }
+        }
+    }
+}
+
+impl CompoundTag {
+    /// Returns tag at provided path in vanilla syntax.
+    ///
+    /// Element matchers like `[{id:"minecraft:stone"}]` resolve to the first matching element.
+    /// Path is parsed on every call, use [`NbtPath::compile`] to apply it to many tags.
+    ///
+    /// # Example
+    /// ```
+    /// use nbt::CompoundTag;
+    ///
+    /// let mut sharpness = CompoundTag::new();
+    /// sharpness.insert_str("id", "minecraft:sharpness");
+    /// sharpness.insert_i16("lvl", 5);
+    ///
+    /// let mut tag = CompoundTag::new();
+    /// tag.insert_compound_tag_vec("Enchantments", vec![sharpness]);
+    ///
+    /// let mut item = CompoundTag::new();
+    /// item.insert_compound_tag("tag", tag);
+    ///
+    /// let mut root_tag = CompoundTag::new();
+    /// root_tag.insert_compound_tag_vec("Inventory", vec![item]);
+    ///
+    /// let lvl = root_tag
+    ///     .get_path("Inventory[0].tag.Enchantments[{id:\"minecraft:sharpness\"}].lvl")
+    ///     .unwrap();
+    ///
+    /// assert_eq!(lvl.as_i16(), Some(5));
+    /// ```
+    pub fn get_path(&self, path: &str) -> crate::Result<&Tag> {
+        let path: NbtPath = path.parse()?;
+
+        Ok(path.get(self)?)
+    }
+
+    /// Returns mutable tag at provided path in vanilla syntax, see [`get_path`](CompoundTag::get_path).
+    pub fn get_path_mut(&mut self, path: &str) -> crate::Result<&mut Tag> {
+        let path: NbtPath = path.parse()?;
+
+        Ok(path.get_mut(self)?)
+    }
+
+    /// Puts tag at provided path in vanilla syntax and returns replaced one, see [`NbtPath::set`].
+    ///
+    /// # Example
+    /// ```
+    /// use nbt::{CompoundTag, Tag};
+    ///
+    /// let mut section = CompoundTag::new();
+    /// section.insert_i8("Y", 0);
+    ///
+    /// let mut root_tag = CompoundTag::new();
+    /// root_tag.insert_compound_tag_vec("sections", vec![section]);
+    ///
+    /// root_tag.set_path("sections[{Y:0b}].BlockLight", vec![0i8; 2048]).unwrap();
+    ///
+    /// let error = root_tag.set_path("sections[1].Y", 1i8).unwrap_err();
+    /// assert_eq!(error.to_string(), "Tag sections[1] not found");
+    /// ```
+    pub fn set_path(&mut self, path: &str, tag: impl Into<Tag>) -> crate::Result<Option<Tag>> {
+        let path: NbtPath = path.parse()?;
+
+        Ok(path.set(self, tag.into())?)
+    }
+
+    /// Returns all tags at provided path in vanilla syntax, see [`NbtPath::select`].
+    ///
+    /// # Example
+    /// ```
+    /// use nbt::CompoundTag;
+    ///
+    /// let mut pig = CompoundTag::new();
+    /// pig.insert_str("id", "minecraft:pig");
+    /// pig.insert_bool("Saddle", true);
+    ///
+    /// let mut cow = CompoundTag::new();
+    /// cow.insert_str("id", "minecraft:cow");
+    ///
+    /// let mut root_tag = CompoundTag::new();
+    /// root_tag.insert_compound_tag_vec("Entities", vec![pig, cow]);
+    ///
+    /// let saddled = root_tag.select_path("Entities[{Saddle:1b}].id").unwrap().count();
+    /// assert_eq!(saddled, 1);
+    /// ```
+    pub fn select_path(&self, path: &str) -> crate::Result<impl Iterator<Item = &Tag>> {
+        let path: NbtPath = path.parse()?;
+
+        Ok(path.select(self))
+    }
+
+    /// Returns tag at provided RFC 6901 JSON pointer, see [`NbtPath::from_pointer`].
+    ///
+    /// # Example
+    /// ```
+    /// use nbt::CompoundTag;
+    ///
+    /// let mut root_tag = CompoundTag::new();
+    /// root_tag.insert_str_vec("a/b", vec!["x", "y"]);
+    ///
+    /// assert_eq!(root_tag.get_pointer("/a~1b/1").unwrap().as_str(), Some("y"));
+    /// ```
+    pub fn get_pointer(&self, pointer: &str) -> crate::Result<&Tag> {
+        let path = NbtPath::from_pointer(pointer)?;
+
+        Ok(path.get(self)?)
+    }
+
+    /// Removes tag at provided path in vanilla syntax and returns it, see [`NbtPath::remove`].
+    ///
+    /// # Example
+    /// ```
+    /// use nbt::{CompoundTag, Tag};
+    ///
+    /// let mut player = CompoundTag::new();
+    /// player.insert_i32("XpLevel", 30);
+    ///
+    /// let mut data = CompoundTag::new();
+    /// data.insert_compound_tag("Player", player);
+    /// data.insert_str("LevelName", "world");
+    ///
+    /// let mut level = CompoundTag::named("");
+    /// level.insert_compound_tag("Data", data);
+    ///
+    /// // Move player out into a separate document.
+    /// let player = match level.remove_path("Data.Player").unwrap() {
+    ///     Tag::Compound(player) => player,
+    ///     _ => unreachable!(),
+    /// };
+    ///
+    /// assert_eq!(player.get_i32("XpLevel").unwrap(), 30);
+    /// assert_eq!(level.to_string(), r#"{"Data":{"LevelName":"world"}}"#);
+    /// ```
+    pub fn remove_path(&mut self, path: &str) -> crate::Result<Tag> {
+        let path: NbtPath = path.parse()?;
+
+        Ok(path.remove(self)?)
+    }
+
+    /// Puts tag at provided path in vanilla syntax creating missing tags along the way
+    /// and returns replaced one, see [`NbtPath::insert`].
+    pub fn insert_path(&mut self, path: &str, tag: impl Into<Tag>) -> crate::Result<Option<Tag>> {
+        let path: NbtPath = path.parse()?;
+
+        Ok(path.insert(self, tag.into(), &InsertOptions::default())?)
+    }
+}
+
+fn fmt_key(f: &mut Formatter<'_>, name: &str) -> fmt::Result {
+    let is_plain = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| !matches!(c, '.' | '[' | ']' | '{' | '}' | '"' | '\'') && !c.is_whitespace());
+
+    if is_plain {
+        write!(f, "{}", name)
+    } else {
+        write!(f, "{:?}", name)
+    }
+}
+
+#[test]
+fn test_path_display() {
+    let mut path = NbtPath::new();
+    path.push_key("Level");
+    path.push_key("Inventory");
+    path.push_index(3);
+    path.push_key("id");
+
+    assert_eq!(path.to_string(), "Level.Inventory[3].id");
+
+    let path = NbtPath::new().join_index(0).join_key("display name");
+    assert_eq!(path.to_string(), "[0].\"display name\"");
+}
+
+#[test]
+fn test_path_get() {
+    let mut item = CompoundTag::new();
+    item.insert_str("id", "minecraft:stone");
+
+    let mut player = CompoundTag::new();
+    player.insert_compound_tag_vec("Inventory", vec![item]);
+
+    let mut root_tag = CompoundTag::new();
+    root_tag.insert_compound_tag("Player", player);
+
+    let path = NbtPath::from(vec![
+        PathSegment::Key("Player".to_string()),
+        PathSegment::Key("Inventory".to_string()),
+        PathSegment::Index(0),
+        PathSegment::Key("id".to_string()),
+    ]);
+
+    assert_eq!(
+        path.get(&root_tag).unwrap().as_str(),
+        Some("minecraft:stone")
+    );
+
+    let missing = NbtPath::from(vec![
+        PathSegment::Key("Player".to_string()),
+        PathSegment::Key("Inventory".to_string()),
+        PathSegment::Index(3),
+        PathSegment::Key("id".to_string()),
+    ]);
+
+    assert_eq!(
+        missing.get(&root_tag).unwrap_err().to_string(),
+        "Tag Player.Inventory[3] not found"
+    );
+
+    let wrong_type = path.join_key("Count");
+
+    assert_eq!(
+        wrong_type.get(&root_tag).unwrap_err().to_string(),
+        "Tag Player.Inventory[0].id has type TAG_String, expected TAG_Compound"
+    );
+}
+
+#[test]
+fn test_path_match() {
+    let items: Vec<_> = ["minecraft:stone", "minecraft:dirt"]
+        .iter()
+        .enumerate()
+        .map(|(slot, id)| {
+            let mut item = CompoundTag::new();
+            item.insert_i8("Slot", slot as i8);
+            item.insert_str("id", *id);
+            item.insert_str_vec("Tags", vec!["a", "b"]);
+            item
+        })
+        .collect();
+
+    let mut root_tag = CompoundTag::new();
+    root_tag.insert_compound_tag_vec("Inventory", items);
+
+    let tag = root_tag.get_path("Inventory[{Slot:1b}].id").unwrap();
+    assert_eq!(tag.as_str(), Some("minecraft:dirt"));
+
+    let tag = root_tag.get_path("Inventory[{Tags:[\"b\"]}].Slot").unwrap();
+    assert_eq!(tag.as_i8(), Some(0));
+
+    // Type of numbers has to match.
+    let error = root_tag.get_path("Inventory[{Slot:1}]").unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::TagNotFound);
+    assert_eq!(error.to_string(), "Tag Inventory[{\"Slot\":1}] not found");
+
+    let error = root_tag.get_path("Inventory[{Slot:1b}").unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::InvalidPath);
+    assert_eq!(
+        error.to_string(),
+        "Invalid path: Unexpected end of path, expected ']'"
+    );
+
+    let path: NbtPath = "Inventory[{Slot:1b,\"a b\":{}}].id".parse().unwrap();
+    assert_eq!(path.to_string().parse::<NbtPath>().unwrap(), path);
+}
+
+#[test]
+fn test_path_set() {
+    let mut root_tag = CompoundTag::new();
+    root_tag.insert_i32_vec("Pos", vec![1, 2, 3]);
+    root_tag.insert_i32("a", 1);
+    root_tag.insert_i32("b", 2);
+    root_tag.insert_compound_tag_vec("Items", vec![CompoundTag::new()]);
+
+    assert_eq!(root_tag.set_path("a", 3).unwrap(), Some(Tag::Int(1)));
+    assert_eq!(root_tag.set_path("c", 4).unwrap(), None);
+
+    let names: Vec<_> = root_tag.iter().map(|(name, _)| name).collect();
+    assert_eq!(names, vec!["Pos", "a", "b", "Items", "c"]);
+
+    root_tag.set_path("Items[0].id", "minecraft:stone").unwrap();
+    root_tag
+        .set_path("Items[{id:\"minecraft:stone\"}].Count", 1i8)
+        .unwrap();
+
+    *root_tag.get_path_mut("Items[0].Count").unwrap() = Tag::Byte(2);
+
+    assert_eq!(
+        root_tag.get_path("Items[{Count:2b}].id").unwrap().as_str(),
+        Some("minecraft:stone")
+    );
+
+    // Only element of the list can change its type.
+    root_tag.set_path("Items[0]", 1).unwrap();
+    assert_eq!(root_tag.get_path("Items[0]").unwrap(), &Tag::Int(1));
+
+    let mut list = vec![Tag::Int(1), Tag::Int(2)];
+    let mut tag_path = NbtPath::new();
+    tag_path.push_index(1);
+
+    assert_eq!(
+        tag_path.get_in_mut(&mut Tag::List(list.clone())).unwrap(),
+        &mut Tag::Int(2)
+    );
+    assert_eq!(
+        replace_element(&mut list, 1, Tag::Byte(1), &tag_path)
+            .unwrap_err()
+            .to_string(),
+        "Tag [1] has type TAG_Byte, expected TAG_Int"
+    );
+
+    let error = root_tag.set_path("a.b", 1).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Tag a has type TAG_Int, expected TAG_Compound"
+    );
+
+    let error = root_tag.set_path("Pos[0]", 1).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Tag Pos has type TAG_Int_Array, expected TAG_List"
+    );
+
+    let error = root_tag.set_path("[0]", 1).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::TagWrongType);
+
+    let error = root_tag.set_path("", 1).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::TagNotFound);
+}
+
+#[test]
+fn test_path_remove() {
+    let mut root_tag = CompoundTag::new();
+    root_tag.insert_i32("a", 1);
+    root_tag.insert_i32("b", 2);
+    root_tag.insert_i16_vec("c", vec![1, 2, 3]);
+
+    assert_eq!(root_tag.remove_path("a").unwrap(), Tag::Int(1));
+    assert_eq!(root_tag.remove_path("c[1]").unwrap(), Tag::Short(2));
+    assert_eq!(root_tag.get_i16_vec("c").unwrap(), vec![1, 3]);
+
+    let error = root_tag.remove_path("c[2]").unwrap_err();
+    assert_eq!(error.to_string(), "Tag c[2] not found");
+
+    let error = root_tag.remove_path("b.x").unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Tag b has type TAG_Int, expected TAG_Compound"
+    );
+
+    let names: Vec<_> = root_tag.iter().map(|(name, _)| name).collect();
+    assert_eq!(names, vec!["b", "c"]);
+}
+
+#[test]
+fn test_path_insert() {
+    let mut root_tag = CompoundTag::new();
+
+    root_tag.insert_path("a.b.c", 1).unwrap();
+    root_tag.insert_path("a.b.d", 2).unwrap();
+    root_tag.insert_path("a.e[{id:1}].x", 3).unwrap();
+    root_tag.insert_path("a.e[{id:1}].y", 4).unwrap();
+
+    assert_eq!(
+        root_tag.to_string(),
+        r#"{"a":{"b":{"c":1,"d":2},"e":[{"id":1,"x":3,"y":4}]}}"#
+    );
+
+    assert_eq!(root_tag.insert_path("a.b.c", 5).unwrap(), Some(Tag::Int(1)));
+
+    let error = root_tag.insert_path("a.f[1]", 1).unwrap_err();
+    assert_eq!(error.to_string(), "Tag a.f[1] not found");
+
+    let options = InsertOptions {
+        create_list_slots: true,
+    };
+
+    let path: NbtPath = "a.f[2].g".parse().unwrap();
+    path.insert(&mut root_tag, Tag::Int(1), &options).unwrap();
+    assert_eq!(
+        root_tag.get_path("a.f").unwrap().to_string(),
+        r#"[{},{},{"g":1}]"#
+    );
+
+    let path: NbtPath = "a.f[4]".parse().unwrap();
+    let error = path
+        .insert(&mut root_tag, Tag::Int(1), &options)
+        .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Tag a.f[4] has type TAG_Int, expected TAG_Compound"
+    );
+
+    let error = root_tag.insert_path("a.b.c.d", 1).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Tag a.b.c has type TAG_Int, expected TAG_Compound"
+    );
+
+    // Root is kept on errors.
+    assert_eq!(root_tag.get_path("a.b.c").unwrap(), &Tag::Int(5));
+}
+
+#[test]
+fn test_path_select() {
+    let mut root_tag = CompoundTag::new();
+    root_tag.insert_i32("DataVersion", 3465);
+
+    for (x, ids) in [["a", "b"], ["c", "a"]].iter().enumerate() {
+        let block_entities: Vec<_> = ids
+            .iter()
+            .map(|id| {
+                let mut block_entity = CompoundTag::new();
+                block_entity.insert_str("id", *id);
+                block_entity.insert_i32("x", x as i32);
+                block_entity
+            })
+            .collect();
+
+        let mut section = CompoundTag::new();
+        section.insert_compound_tag_vec("block_entities", block_entities);
+
+        root_tag.insert_compound_tag(format!("s{}", x), section);
+    }
+
+    let select = |root_tag: &CompoundTag, path: &str| -> Vec<String> {
+        root_tag
+            .select_path(path)
+            .unwrap()
+            .map(|tag| tag.to_string())
+            .collect()
+    };
+
+    assert_eq!(
+        select(&root_tag, "s0.block_entities[].id"),
+        vec!["\"a\"", "\"b\""]
+    );
+    assert_eq!(
+        select(&root_tag, "s1.block_entities[{id:\"a\"}].x"),
+        vec!["1"]
+    );
+    assert_eq!(
+        select(&root_tag, "{DataVersion:3465}.s0.block_entities[1].id"),
+        vec!["\"b\""]
+    );
+    assert!(select(&root_tag, "{DataVersion:1}.s0").is_empty());
+    assert!(select(&root_tag, "s0{block_entities:[{id:\"c\"}]}").is_empty());
+    assert_eq!(
+        select(&root_tag, "s1{block_entities:[{id:\"c\"}]}").len(),
+        1
+    );
+
+    let path: NbtPath = "{DataVersion:3465}.s0{a:1b}.b[]".parse().unwrap();
+    assert_eq!(path.to_string(), "{\"DataVersion\":3465}.s0{\"a\":1b}.b[]");
+    assert_eq!(path.to_string().parse::<NbtPath>().unwrap(), path);
+
+    assert_eq!(
+        root_tag
+            .get_path("s1.block_entities[].id")
+            .unwrap()
+            .as_str(),
+        Some("c")
+    );
+
+    let error = root_tag.get_path("s0{x:1}").unwrap_err();
+    assert_eq!(error.to_string(), "Tag s0{\"x\":1} not found");
+
+    let error = root_tag.get_path("DataVersion{x:1}").unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Tag DataVersion has type TAG_Int, expected TAG_Compound"
+    );
+
+    // Changes apply to the first element and check trailing matchers.
+    root_tag.set_path("s0.block_entities[].x", 5).unwrap();
+    assert_eq!(select(&root_tag, "s0.block_entities[].x"), vec!["5", "0"]);
+
+    root_tag.remove_path("s1{block_entities:[]}").unwrap_err();
+    root_tag.insert_path("s2.a[].b", 1).unwrap();
+    root_tag
+        .insert_path("s2.a[{c:1}]", CompoundTag::new())
+        .unwrap();
+    assert_eq!(
+        root_tag.get_path("s2").unwrap().to_string(),
+        r#"{"a":[{"b":1},{}]}"#
+    );
+
+    root_tag.remove_path("s2{a:[{b:1}]}").unwrap();
+    assert!(!root_tag.contains_key("s2"));
+}
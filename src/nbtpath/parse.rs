@@ -0,0 +1,550 @@
+//! Parsing of paths in vanilla syntax, e.g. `Inventory[{Slot:0b}].tag.display.Name`.
+use super::{PathParseError, PathSegment};
+use crate::{CompoundTag, Tag};
+
+/// Characters which can't appear in unquoted names of path nodes.
+fn is_path_name_char(c: char) -> bool {
+    !matches!(c, '.' | '[' | ']' | '{' | '}' | '"' | '\'') && !c.is_whitespace()
+}
+
+/// Characters of unquoted strings, names and numbers in SNBT.
+fn is_snbt_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | '+')
+}
+
+/// Maximum nesting of compound tags and lists in path, same as default of the decoder.
+const MAX_DEPTH: usize = 512;
+
+pub(super) fn parse_path(input: &str) -> Result<Vec<PathSegment>, PathParseError> {
+    let mut parser = Parser {
+        input,
+        position: 0,
+        depth: 0,
+    };
+    let mut segments = Vec::new();
+
+    if input.is_empty() {
+        return Ok(segments);
+    }
+
+    loop {
+        let is_first = segments.is_empty();
+
+        match parser.peek() {
+            Some('[') => {}
+            Some('{') if is_first => {}
+            _ => segments.push(PathSegment::Key(parser.parse_path_name()?)),
+        }
+
+        loop {
+            match parser.peek() {
+                Some('[') => segments.push(parser.parse_path_brackets()?),
+                Some('{') => segments.push(PathSegment::Filter(parser.parse_compound()?)),
+                _ => break,
+            }
+        }
+
+        match parser.next() {
+            None => return Ok(segments),
+            Some('.') => continue,
+            Some(_) => return Err(parser.error_at(parser.position - 1, "'.', '[' or '{'")),
+        }
+    }
+}
+
+/// Parses RFC 6901 JSON pointer, tokens consisting of digits are indices.
+pub(super) fn parse_pointer(input: &str) -> Result<Vec<PathSegment>, PathParseError> {
+    let mut segments = Vec::new();
+
+    if input.is_empty() {
+        return Ok(segments);
+    }
+
+    let tokens = match input.strip_prefix('/') {
+        Some(tokens) => tokens,
+        None => return Err(unexpected(input, 0, "'/'")),
+    };
+
+    let mut position = 1;
+
+    for token in tokens.split('/') {
+        let is_index = !token.is_empty()
+            && token.bytes().all(|b| b.is_ascii_digit())
+            && (token == "0" || !token.starts_with('0'));
+
+        let segment = if is_index {
+            let index = token
+                .parse()
+                .map_err(|_| PathParseError::InvalidNumber { position })?;
+
+            PathSegment::Index(index)
+        } else {
+            PathSegment::Key(unescape_pointer_token(input, token, position)?)
+        };
+
+        segments.push(segment);
+        position += token.len() + 1;
+    }
+
+    Ok(segments)
+}
+
+/// Replaces `~1` with `/` and `~0` with `~`.
+fn unescape_pointer_token(
+    input: &str,
+    token: &str,
+    position: usize,
+) -> Result<String, PathParseError> {
+    let mut name = String::with_capacity(token.len());
+    let mut chars = token.char_indices();
+
+    while let Some((_, c)) = chars.next() {
+        match c {
+            '~' => match chars.next() {
+                Some((_, '0')) => name.push('~'),
+                Some((_, '1')) => name.push('/'),
+                Some((offset, _)) => {
+                    return Err(unexpected(input, position + offset, "'0' or '1'"))
+                }
+                None => return Err(unexpected(input, position + token.len(), "'0' or '1'")),
+            },
+            c => name.push(c),
+        }
+    }
+
+    Ok(name)
+}
+
+/// Error for character of input at provided position.
+fn unexpected(input: &str, position: usize, expected: &'static str) -> PathParseError {
+    match input[position..].chars().next() {
+        Some(found) => PathParseError::UnexpectedChar {
+            position,
+            found,
+            expected,
+        },
+        None => PathParseError::UnexpectedEnd { expected },
+    }
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    /// Offset of the next character in bytes.
+    position: usize,
+    /// Number of compound tags and lists being parsed.
+    depth: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<char> {
+        self.input[self.position..].chars().next()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.position += c.len_utf8();
+
+        Some(c)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.next();
+        }
+    }
+
+    fn expect(
+        &mut self,
+        expected_char: char,
+        expected: &'static str,
+    ) -> Result<(), PathParseError> {
+        match self.peek() {
+            Some(c) if c == expected_char => {
+                self.next();
+                Ok(())
+            }
+            _ => Err(self.error(expected)),
+        }
+    }
+
+    /// Error for the next character.
+    fn error(&self, expected: &'static str) -> PathParseError {
+        self.error_at(self.position, expected)
+    }
+
+    fn error_at(&self, position: usize, expected: &'static str) -> PathParseError {
+        unexpected(self.input, position, expected)
+    }
+
+    /// Enters compound tag or list, parsers leave it by decrementing depth on success.
+    fn enter(&mut self) -> Result<(), PathParseError> {
+        if self.depth == MAX_DEPTH {
+            return Err(PathParseError::TooDeep {
+                position: self.position,
+            });
+        }
+
+        self.depth += 1;
+
+        Ok(())
+    }
+
+    fn take_while(&mut self, predicate: fn(char) -> bool) -> &'a str {
+        let start = self.position;
+
+        while self.peek().is_some_and(predicate) {
+            self.next();
+        }
+
+        &self.input[start..self.position]
+    }
+
+    fn parse_path_name(&mut self) -> Result<String, PathParseError> {
+        match self.peek() {
+            Some('"') | Some('\'') => self.parse_quoted(),
+            _ => match self.take_while(is_path_name_char) {
+                "" => Err(self.error("name")),
+                name => Ok(name.to_string()),
+            },
+        }
+    }
+
+    /// Parses index, element matcher or all elements selector in brackets.
+    fn parse_path_brackets(&mut self) -> Result<PathSegment, PathParseError> {
+        self.expect('[', "'['")?;
+
+        let segment = match self.peek() {
+            Some(']') => PathSegment::All,
+            Some('{') => PathSegment::Match(self.parse_compound()?),
+            Some(c) if c.is_ascii_digit() => {
+                let position = self.position;
+                let digits = self.take_while(|c| c.is_ascii_digit());
+                let index = digits
+                    .parse()
+                    .map_err(|_| PathParseError::InvalidNumber { position })?;
+
+                PathSegment::Index(index)
+            }
+            _ => return Err(self.error("index or compound tag")),
+        };
+
+        self.expect(']', "']'")?;
+
+        Ok(segment)
+    }
+
+    /// Parses string in single or double quotes, quotes and backslashes inside are escaped by backslash.
+    fn parse_quoted(&mut self) -> Result<String, PathParseError> {
+        let quote = match self.next() {
+            Some(c @ '"') | Some(c @ '\'') => c,
+            _ => return Err(self.error_at(self.position, "quote")),
+        };
+
+        let mut value = String::new();
+
+        loop {
+            match self.next() {
+                None => return Err(PathParseError::UnexpectedEnd { expected: "quote" }),
+                Some(c) if c == quote => return Ok(value),
+                Some('\\') => match self.next() {
+                    None => return Err(PathParseError::UnexpectedEnd { expected: "quote" }),
+                    Some('n') => value.push('\n'),
+                    Some('t') => value.push('\t'),
+                    Some('r') => value.push('\r'),
+                    Some('0') => value.push('\0'),
+                    Some(c) => value.push(c),
+                },
+                Some(c) => value.push(c),
+            }
+        }
+    }
+
+    fn parse_compound(&mut self) -> Result<CompoundTag, PathParseError> {
+        self.enter()?;
+        self.expect('{', "'{'")?;
+        self.skip_whitespace();
+
+        let mut compound_tag = CompoundTag::new();
+
+        if self.peek() == Some('}') {
+            self.next();
+            self.depth -= 1;
+            return Ok(compound_tag);
+        }
+
+        loop {
+            self.skip_whitespace();
+
+            let name = match self.peek() {
+                Some('"') | Some('\'') => self.parse_quoted()?,
+                _ => match self.take_while(is_snbt_char) {
+                    "" => return Err(self.error("name")),
+                    name => name.to_string(),
+                },
+            };
+
+            self.skip_whitespace();
+            self.expect(':', "':'")?;
+
+            let tag = self.parse_value()?;
+            compound_tag.insert(name, tag);
+
+            self.skip_whitespace();
+
+            match self.peek() {
+                Some(',') => {
+                    self.next();
+                }
+                Some('}') => {
+                    self.next();
+                    self.depth -= 1;
+                    return Ok(compound_tag);
+                }
+                _ => return Err(self.error("',' or '}'")),
+            }
+        }
+    }
+
+    fn parse_value(&mut self) -> Result<Tag, PathParseError> {
+        self.skip_whitespace();
+
+        match self.peek() {
+            Some('{') => Ok(Tag::Compound(self.parse_compound()?)),
+            Some('[') => self.parse_list(),
+            Some('"') | Some('\'') => Ok(Tag::String(self.parse_quoted()?)),
+            _ => match self.take_while(is_snbt_char) {
+                "" => Err(self.error("value")),
+                value => Ok(parse_literal(value)),
+            },
+        }
+    }
+
+    /// Parses list or typed array, e.g. `[I; 1, 2]`.
+    fn parse_list(&mut self) -> Result<Tag, PathParseError> {
+        self.enter()?;
+        self.expect('[', "'['")?;
+
+        let array_type = match &self.input.as_bytes()[self.position..] {
+            [prefix @ (b'B' | b'I' | b'L'), b';', ..] => Some(*prefix),
+            _ => None,
+        };
+
+        if array_type.is_some() {
+            self.position += 2;
+        }
+
+        let mut tags = Vec::new();
+        self.skip_whitespace();
+
+        if self.peek() == Some(']') {
+            self.next();
+        } else {
+            loop {
+                let position = self.position;
+                let tag = self.parse_value()?;
+
+                let is_valid = matches!(
+                    (array_type, &tag),
+                    (None, _)
+                        | (Some(b'B'), Tag::Byte(_))
+                        | (Some(b'I'), Tag::Int(_))
+                        | (Some(b'L'), Tag::Long(_))
+                );
+
+                if !is_valid {
+                    return Err(self.error_at(position, "array element"));
+                }
+
+                tags.push(tag);
+                self.skip_whitespace();
+
+                match self.peek() {
+                    Some(',') => {
+                        self.next();
+                    }
+                    Some(']') => {
+                        self.next();
+                        break;
+                    }
+                    _ => return Err(self.error("',' or ']'")),
+                }
+            }
+        }
+
+        let tag = match array_type {
+            Some(b'B') => Tag::ByteArray(tags.iter().filter_map(Tag::as_i8).collect()),
+            Some(b'I') => Tag::IntArray(tags.iter().filter_map(Tag::as_i32).collect()),
+            Some(b'L') => Tag::LongArray(tags.iter().filter_map(Tag::as_i64).collect()),
+            _ => Tag::List(tags),
+        };
+
+        self.depth -= 1;
+
+        Ok(tag)
+    }
+}
+
+/// Parses unquoted value as a number with optional type suffix, boolean or string.
+fn parse_literal(value: &str) -> Tag {
+    match value {
+        "true" => return Tag::Byte(1),
+        "false" => return Tag::Byte(0),
+        _ => {}
+    }
+
+    let (number, suffix) = value.split_at(value.len() - 1);
+
+    let tag = match suffix {
+        "b" | "B" => number.parse().ok().map(Tag::Byte),
+        "s" | "S" => number.parse().ok().map(Tag::Short),
+        "l" | "L" => number.parse().ok().map(Tag::Long),
+        "f" | "F" => number.parse().ok().map(Tag::Float),
+        "d" | "D" => number.parse().ok().map(Tag::Double),
+        _ => None,
+    };
+
+    let is_decimal = value.contains('.') && value.chars().any(|c| c.is_ascii_digit());
+
+    tag.or_else(|| value.parse().ok().map(Tag::Int))
+        .or_else(|| match is_decimal {
+            true => value.parse().ok().map(Tag::Double),
+            false => None,
+        })
+        .unwrap_or_else(|| Tag::String(value.to_string()))
+}
+
+#[test]
+fn test_parse_literal() {
+    assert_eq!(parse_literal("1b"), Tag::Byte(1));
+    assert_eq!(parse_literal("-2s"), Tag::Short(-2));
+    assert_eq!(parse_literal("3"), Tag::Int(3));
+    assert_eq!(parse_literal("4L"), Tag::Long(4));
+    assert_eq!(parse_literal("0.5f"), Tag::Float(0.5));
+    assert_eq!(parse_literal("0.25"), Tag::Double(0.25));
+    assert_eq!(parse_literal("1d"), Tag::Double(1.0));
+    assert_eq!(parse_literal("true"), Tag::Byte(1));
+    assert_eq!(parse_literal("300b"), Tag::String("300b".to_string()));
+    assert_eq!(parse_literal("stone"), Tag::String("stone".to_string()));
+}
+
+#[test]
+fn test_parse_path() {
+    let segments = parse_path("Inventory[0].tag.\"display name\"[{id:\"a\",lvl:[I;1,2]}]").unwrap();
+
+    let mut pattern = CompoundTag::new();
+    pattern.insert_str("id", "a");
+    pattern.insert_i32_vec("lvl", vec![1, 2]);
+
+    assert_eq!(
+        segments,
+        vec![
+            PathSegment::Key("Inventory".to_string()),
+            PathSegment::Index(0),
+            PathSegment::Key("tag".to_string()),
+            PathSegment::Key("display name".to_string()),
+            PathSegment::Match(pattern),
+        ]
+    );
+
+    let mut filter = CompoundTag::new();
+    filter.insert_i8("b", 1);
+
+    assert_eq!(
+        parse_path("{b:1b}.a{b:1b}[][0]").unwrap(),
+        vec![
+            PathSegment::Filter(filter.clone()),
+            PathSegment::Key("a".to_string()),
+            PathSegment::Filter(filter),
+            PathSegment::All,
+            PathSegment::Index(0),
+        ]
+    );
+
+    assert_eq!(parse_path("").unwrap(), vec![]);
+    assert_eq!(
+        parse_path("a.{b:1b}").unwrap_err(),
+        PathParseError::UnexpectedChar {
+            position: 2,
+            found: '{',
+            expected: "name",
+        }
+    );
+    assert_eq!(
+        parse_path("a.").unwrap_err(),
+        PathParseError::UnexpectedEnd { expected: "name" }
+    );
+    assert_eq!(
+        parse_path("a[-1]").unwrap_err(),
+        PathParseError::UnexpectedChar {
+            position: 2,
+            found: '-',
+            expected: "index or compound tag",
+        }
+    );
+    assert_eq!(
+        parse_path("a[99999999999999999999]").unwrap_err(),
+        PathParseError::InvalidNumber { position: 2 }
+    );
+    assert_eq!(
+        parse_path("a[{b:[I;1b]}]").unwrap_err(),
+        PathParseError::UnexpectedChar {
+            position: 8,
+            found: '1',
+            expected: "array element",
+        }
+    );
+
+    let nested = format!(
+        "a[{{b:{}1{}}}]",
+        "[".repeat(MAX_DEPTH - 1),
+        "]".repeat(MAX_DEPTH - 1)
+    );
+    assert!(parse_path(&nested).is_ok());
+
+    let nested = format!("a[{{b:{}", "[".repeat(200_000));
+    assert_eq!(
+        parse_path(&nested).unwrap_err(),
+        PathParseError::TooDeep {
+            position: 5 + MAX_DEPTH - 1
+        }
+    );
+}
+
+#[test]
+fn test_parse_pointer() {
+    assert_eq!(
+        parse_pointer("/Data/Inventory/0/a~1b~0c/01/").unwrap(),
+        vec![
+            PathSegment::Key("Data".to_string()),
+            PathSegment::Key("Inventory".to_string()),
+            PathSegment::Index(0),
+            PathSegment::Key("a/b~c".to_string()),
+            PathSegment::Key("01".to_string()),
+            PathSegment::Key("".to_string()),
+        ]
+    );
+
+    assert_eq!(parse_pointer("").unwrap(), vec![]);
+    assert_eq!(
+        parse_pointer("Data").unwrap_err(),
+        PathParseError::UnexpectedChar {
+            position: 0,
+            found: 'D',
+            expected: "'/'",
+        }
+    );
+    assert_eq!(
+        parse_pointer("/a/b~2").unwrap_err(),
+        PathParseError::UnexpectedChar {
+            position: 5,
+            found: '2',
+            expected: "'0' or '1'",
+        }
+    );
+    assert_eq!(
+        parse_pointer("/a~").unwrap_err(),
+        PathParseError::UnexpectedEnd {
+            expected: "'0' or '1'",
+        }
+    );
+}
@@ -0,0 +1,116 @@
+use crate::{CompoundTag, Tag, TagType};
+use std::collections::HashMap;
+
+/// Summary of a tag tree returned by [`CompoundTag::stats`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TagStats {
+    /// Number of tags of each type, including the root compound tag.
+    pub tags: HashMap<TagType, u64>,
+    /// Maximum nesting depth of compound and list tags, root compound tag has depth 1.
+    pub max_depth: usize,
+    /// Total length of string tag values in bytes, names are not included.
+    pub string_bytes: u64,
+    /// Total number of elements of byte, int and long arrays.
+    pub array_elements: u64,
+}
+
+impl TagStats {
+    /// Returns number of tags of provided type.
+    pub fn count(&self, tag_type: TagType) -> u64 {
+        self.tags.get(&tag_type).copied().unwrap_or(0)
+    }
+
+    /// Returns number of tags of all types.
+    pub fn total(&self) -> u64 {
+        self.tags.values().sum()
+    }
+
+    fn add(&mut self, tag: &Tag, depth: usize) {
+        *self.tags.entry(tag.tag_type()).or_insert(0) += 1;
+
+        match tag {
+            Tag::String(value) => self.string_bytes += value.len() as u64,
+            Tag::ByteArray(value) => self.array_elements += value.len() as u64,
+            Tag::IntArray(value) => self.array_elements += value.len() as u64,
+            Tag::LongArray(value) => self.array_elements += value.len() as u64,
+            Tag::List(_) | Tag::Compound(_) => self.max_depth = self.max_depth.max(depth),
+            _ => {}
+        }
+    }
+}
+
+impl CompoundTag {
+    /// Counts tags of this tree by type together with its depth and size of strings and arrays.
+    ///
+    /// # Example
+    /// ```
+    /// use nbt::decode::read_compound_tag;
+    /// use nbt::TagType;
+    /// use std::io::Cursor;
+    ///
+    /// let mut cursor = Cursor::new(include_bytes!("../test/binary/servers.dat").to_vec());
+    /// let root_tag = read_compound_tag(&mut cursor).unwrap();
+    /// let stats = root_tag.stats();
+    ///
+    /// assert_eq!(stats.count(TagType::String), 2);
+    /// assert_eq!(stats.total(), 6);
+    /// assert_eq!(stats.max_depth, 3);
+    /// ```
+    pub fn stats(&self) -> TagStats {
+        let mut stats = TagStats {
+            max_depth: 1,
+            ..Default::default()
+        };
+        stats.tags.insert(TagType::Compound, 1);
+
+        let mut stack: Vec<(usize, &Tag)> = self.iter().map(|(_, tag)| (2, tag)).collect();
+
+        while let Some((depth, tag)) = stack.pop() {
+            stats.add(tag, depth);
+
+            match tag {
+                Tag::List(value) => stack.extend(value.iter().map(|tag| (depth + 1, tag))),
+                Tag::Compound(value) => stack.extend(value.iter().map(|(_, tag)| (depth + 1, tag))),
+                _ => {}
+            }
+        }
+
+        stats
+    }
+}
+
+#[test]
+fn test_stats() {
+    use crate::decode::{read_gzip_compound_tag, validate, DecodeOptions};
+    use crate::encode::write_compound_tag;
+    use std::io::Cursor;
+
+    let mut cursor = Cursor::new(include_bytes!("../test/binary/bigtest.dat").to_vec());
+    let root_tag = read_gzip_compound_tag(&mut cursor).unwrap();
+    let stats = root_tag.stats();
+
+    let mut vec = Vec::new();
+    write_compound_tag(&mut vec, &root_tag).unwrap();
+    let expected = validate(&mut Cursor::new(vec), &DecodeOptions::default()).unwrap();
+
+    assert_eq!(stats.total(), expected.tags);
+    assert_eq!(stats.max_depth, expected.max_depth);
+    assert_eq!(stats.count(TagType::Compound), 6);
+    assert_eq!(stats.count(TagType::IntArray), 0);
+    assert_eq!(stats.array_elements, 1000);
+    assert_eq!(
+        stats.string_bytes,
+        [
+            "HELLO WORLD THIS IS A TEST STRING ÅÄÖ!",
+            "Hampus",
+            "Eggbert",
+            "Compound tag #0",
+            "Compound tag #1"
+        ]
+        .iter()
+        .map(|value| value.len() as u64)
+        .sum::<u64>()
+    );
+
+    assert_eq!(CompoundTag::new().stats().max_depth, 1);
+}
@@ -0,0 +1,323 @@
+//! Recursive rewriting of tag trees.
+use crate::nbtpath::{NbtPath, PathSegment};
+use crate::{CompoundTag, Tag};
+
+/// What to do with a visited tag during [`CompoundTag::transform`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Action {
+    /// Keep tag (including any in place modifications) and visit its children.
+    Keep,
+    /// Replace tag with provided one. Replacement children are not visited.
+    Replace(Tag),
+    /// Remove tag from enclosing compound or list tag.
+    Remove,
+}
+
+impl CompoundTag {
+    /// Visits all nested tags in depth-first order and applies returned actions.
+    ///
+    /// Visitor receives path of the tag and the tag itself, which may also be modified in place.
+    /// List element paths refer to positions before any removals.
+    ///
+    /// # Example
+    /// ```
+    /// use nbt::transform::Action;
+    /// use nbt::{CompoundTag, Tag};
+    ///
+    /// let mut item = CompoundTag::new();
+    /// item.insert_str("id", "minecraft:grass");
+    /// item.insert_i8("Damage", 0);
+    ///
+    /// let mut root_tag = CompoundTag::new();
+    /// root_tag.insert_compound_tag_vec("Inventory", vec![item]);
+    ///
+    /// root_tag.transform(|_path, tag| match tag.as_str() {
+    ///     Some("minecraft:grass") => Action::Replace(Tag::from("minecraft:short_grass")),
+    ///     _ if tag.is_byte() => Action::Remove,
+    ///     _ => Action::Keep,
+    /// });
+    ///
+    /// let item = &root_tag.get_compound_tag_vec("Inventory").unwrap()[0];
+    /// assert_eq!(item.get_str("id").unwrap(), "minecraft:short_grass");
+    /// assert!(!item.contains_key("Damage"));
+    /// ```
+    pub fn transform<F>(&mut self, mut visitor: F)
+    where
+        F: FnMut(&NbtPath, &mut Tag) -> Action,
+    {
+        transform_compound(self, &mut NbtPath::new(), &mut visitor);
+    }
+}
+
+impl CompoundTag {
+    /// Removes nested tags for which matcher returns `true` anywhere in the tree,
+    /// and returns number of removed tags.
+    ///
+    /// Matcher receives path and name of each tag inside of a compound tag,
+    /// list elements are not matched. Children of removed tags are not visited.
+    ///
+    /// # Example
+    /// ```
+    /// use nbt::CompoundTag;
+    ///
+    /// let mut player = CompoundTag::new();
+    /// player.insert_f64_vec("Pos", vec![0.5, 64.0, 0.5]);
+    /// player.insert_i32_vec("UUID", vec![1, 2, 3, 4]);
+    /// player.insert_i32("XpLevel", 30);
+    ///
+    /// let mut root_tag = CompoundTag::new();
+    /// root_tag.insert_compound_tag("Player", player);
+    ///
+    /// let removed = root_tag.strip_keys(|_path, name| name == "Pos" || name == "UUID");
+    ///
+    /// assert_eq!(removed, 2);
+    /// assert_eq!(root_tag.to_string(), r#"{"Player":{"XpLevel":30}}"#);
+    /// ```
+    pub fn strip_keys<F>(&mut self, mut matcher: F) -> usize
+    where
+        F: FnMut(&NbtPath, &str) -> bool,
+    {
+        let mut removed = 0;
+
+        self.transform(|path, _tag| match path.segments().last() {
+            Some(PathSegment::Key(name)) if matcher(path, name) => {
+                removed += 1;
+                Action::Remove
+            }
+            _ => Action::Keep,
+        });
+
+        removed
+    }
+
+    /// Renames nested tags anywhere in the tree for which function returns a new name,
+    /// and returns number of renamed tags.
+    ///
+    /// Order of tags is kept. If new name is already taken inside of the same compound tag,
+    /// only the latter of the two tags is kept, at its own position.
+    ///
+    /// # Example
+    /// ```
+    /// use nbt::CompoundTag;
+    ///
+    /// let mut item = CompoundTag::new();
+    /// item.insert_str("id", "minecraft:stone");
+    /// item.insert_i8("Count", 1);
+    ///
+    /// let mut root_tag = CompoundTag::new();
+    /// root_tag.insert_compound_tag_vec("Inventory", vec![item]);
+    ///
+    /// let renamed = root_tag.rename_keys_deep(|name| match name {
+    ///     "Count" => Some("count".to_string()),
+    ///     _ => None,
+    /// });
+    ///
+    /// assert_eq!(renamed, 1);
+    /// assert_eq!(root_tag.to_string(), r#"{"Inventory":[{"id":"minecraft:stone","count":1b}]}"#);
+    /// ```
+    pub fn rename_keys_deep<F>(&mut self, mut rename: F) -> usize
+    where
+        F: FnMut(&str) -> Option<String>,
+    {
+        let mut renamed = rename_keys(self, &mut rename);
+
+        // Children of a compound tag are visited after it, so they are found by new names.
+        self.transform(|_path, tag| {
+            if let Tag::Compound(value) = tag {
+                renamed += rename_keys(value, &mut rename);
+            }
+
+            Action::Keep
+        });
+
+        renamed
+    }
+}
+
+impl Tag {
+    /// Visits all nested tags in depth-first order and applies returned actions.
+    ///
+    /// This tag itself is not visited. See [`CompoundTag::transform`] for details.
+    pub fn transform<F>(&mut self, mut visitor: F)
+    where
+        F: FnMut(&NbtPath, &mut Tag) -> Action,
+    {
+        transform_children(self, &mut NbtPath::new(), &mut visitor);
+    }
+}
+
+fn transform_children<F>(tag: &mut Tag, path: &mut NbtPath, visitor: &mut F)
+where
+    F: FnMut(&NbtPath, &mut Tag) -> Action,
+{
+    match tag {
+        Tag::List(value) => transform_list(value, path, visitor),
+        Tag::Compound(value) => transform_compound(value, path, visitor),
+        _ => {}
+    }
+}
+
+fn transform_list<F>(tags: &mut Vec<Tag>, path: &mut NbtPath, visitor: &mut F)
+where
+    F: FnMut(&NbtPath, &mut Tag) -> Action,
+{
+    let mut index = 0;
+
+    tags.retain_mut(|tag| {
+        path.push_index(index);
+        let keep = transform_tag(tag, path, visitor);
+        path.pop();
+        index += 1;
+
+        keep
+    });
+}
+
+fn transform_compound<F>(compound_tag: &mut CompoundTag, path: &mut NbtPath, visitor: &mut F)
+where
+    F: FnMut(&NbtPath, &mut Tag) -> Action,
+{
+    let mut removed = Vec::new();
+
+    for (name, tag) in compound_tag.tags.iter_mut() {
+        path.push_key(name);
+
+        if !transform_tag(tag, path, visitor) {
+            removed.push(name.to_string());
+        }
+
+        path.pop();
+    }
+
+    for name in removed {
+        compound_tag.tags.remove(&name);
+    }
+}
+
+/// Renames tags of compound tag, returns number of renamed tags.
+fn rename_keys<F>(compound_tag: &mut CompoundTag, rename: &mut F) -> usize
+where
+    F: FnMut(&str) -> Option<String>,
+{
+    let names: Vec<Option<String>> = compound_tag.iter().map(|(name, _)| rename(name)).collect();
+    let renamed = names.iter().filter(|name| name.is_some()).count();
+
+    if renamed > 0 {
+        compound_tag.tags = std::mem::take(&mut compound_tag.tags)
+            .into_iter()
+            .zip(names)
+            .map(|((name, tag), new_name)| (new_name.unwrap_or(name), tag))
+            .collect();
+    }
+
+    renamed
+}
+
+/// Returns false if tag should be removed.
+fn transform_tag<F>(tag: &mut Tag, path: &mut NbtPath, visitor: &mut F) -> bool
+where
+    F: FnMut(&NbtPath, &mut Tag) -> Action,
+{
+    match visitor(path, tag) {
+        Action::Keep => {
+            transform_children(tag, path, visitor);
+            true
+        }
+        Action::Replace(replacement) => {
+            *tag = replacement;
+            true
+        }
+        Action::Remove => false,
+    }
+}
+
+#[test]
+fn test_transform() {
+    let mut tag = Tag::List(vec![
+        Tag::Int(1),
+        Tag::Int(2),
+        Tag::List(vec![Tag::Int(3), Tag::Int(4)]),
+    ]);
+
+    let mut visited = Vec::new();
+
+    tag.transform(|path, tag| {
+        visited.push(path.to_string());
+
+        match tag {
+            Tag::Int(2) => Action::Remove,
+            Tag::Int(value) => {
+                *value *= 10;
+                Action::Keep
+            }
+            _ => Action::Keep,
+        }
+    });
+
+    assert_eq!(visited, vec!["[0]", "[1]", "[2]", "[2][0]", "[2][1]"]);
+    assert_eq!(
+        tag,
+        Tag::List(vec![
+            Tag::Int(10),
+            Tag::List(vec![Tag::Int(30), Tag::Int(40)])
+        ])
+    );
+}
+
+#[test]
+fn test_strip_keys() {
+    use crate::decode::read_gzip_compound_tag;
+    use std::io::Cursor;
+
+    let mut cursor = Cursor::new(include_bytes!("../test/binary/bigtest.dat").to_vec());
+    let mut root_tag = read_gzip_compound_tag(&mut cursor).unwrap();
+
+    let mut matched = Vec::new();
+    let removed = root_tag.strip_keys(|path, name| {
+        matched.push(path.to_string());
+        name == "name" || name == "ham"
+    });
+
+    assert_eq!(removed, 4);
+    assert!(matched.contains(&"\"listTest (compound)\"[1].name".to_string()));
+    assert!(!matched.contains(&"\"nested compound test\".ham.name".to_string()));
+    assert!(root_tag.walk().all(|(path, _)| {
+        let path = path.to_string();
+        !path.ends_with(".name") && !path.ends_with(".ham")
+    }));
+
+    let egg = root_tag.get_path("\"nested compound test\".egg").unwrap();
+    assert_eq!(egg.to_string(), r#"{"value":0.5f}"#);
+}
+
+#[test]
+fn test_rename_keys_deep() {
+    let mut nested = CompoundTag::new();
+    nested.insert_i32("a", 1);
+    nested.insert_i32("b", 2);
+
+    let mut root_tag = CompoundTag::new();
+    root_tag.insert_compound_tag_vec("a", vec![nested.clone(), nested]);
+    root_tag.insert_i32("c", 3);
+
+    let renamed = root_tag.rename_keys_deep(|name| match name {
+        "a" => Some("x".to_string()),
+        "c" => Some("b".to_string()),
+        _ => None,
+    });
+
+    assert_eq!(renamed, 4);
+    assert_eq!(
+        root_tag.to_string(),
+        r#"{"x":[{"x":1,"b":2},{"x":1,"b":2}],"b":3}"#
+    );
+
+    // Renaming onto an existing name keeps the latter tag.
+    let mut compound_tag = CompoundTag::new();
+    compound_tag.insert_i32("a", 1);
+    compound_tag.insert_i32("b", 2);
+    compound_tag.insert_i32("c", 3);
+
+    compound_tag.rename_keys_deep(|name| (name == "a").then(|| "b".to_string()));
+    assert_eq!(compound_tag.to_string(), r#"{"b":2,"c":3}"#);
+}
@@ -0,0 +1,174 @@
+use crate::nbtpath::NbtPath;
+use crate::{CompoundTag, Tag};
+
+/// Depth-first iterator over a tag tree.
+///
+/// Created by [`Tag::walk`] and [`CompoundTag::walk`].
+pub struct Walk<'a> {
+    stack: Vec<(NbtPath, &'a Tag)>,
+}
+
+impl<'a> Walk<'a> {
+    fn push_children(&mut self, path: &NbtPath, tag: &'a Tag) {
+        match tag {
+            Tag::List(value) => {
+                for (index, tag) in value.iter().enumerate().rev() {
+                    self.stack.push((path.join_index(index), tag));
+                }
+            }
+            Tag::Compound(value) => self.push_entries(path, value),
+            _ => {}
+        }
+    }
+
+    fn push_entries(&mut self, path: &NbtPath, compound_tag: &'a CompoundTag) {
+        for (name, tag) in compound_tag.tags.iter().rev() {
+            self.stack.push((path.join_key(name), tag));
+        }
+    }
+}
+
+impl<'a> Iterator for Walk<'a> {
+    type Item = (NbtPath, &'a Tag);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (path, tag) = self.stack.pop()?;
+        self.push_children(&path, tag);
+
+        Some((path, tag))
+    }
+}
+
+impl Tag {
+    /// Returns depth-first iterator over this tag and all nested tags together with their paths.
+    ///
+    /// This tag itself is yielded first with an empty path.
+    pub fn walk(&self) -> Walk<'_> {
+        Walk {
+            stack: vec![(NbtPath::new(), self)],
+        }
+    }
+}
+
+impl CompoundTag {
+    /// Returns depth-first iterator over all nested tags together with their paths.
+    ///
+    /// # Example
+    /// ```
+    /// use nbt::CompoundTag;
+    ///
+    /// let mut item = CompoundTag::new();
+    /// item.insert_str("id", "minecraft:stone");
+    ///
+    /// let mut root_tag = CompoundTag::new();
+    /// root_tag.insert_compound_tag_vec("Inventory", vec![item]);
+    ///
+    /// let paths: Vec<String> = root_tag.walk().map(|(path, _)| path.to_string()).collect();
+    /// assert_eq!(paths, vec!["Inventory", "Inventory[0]", "Inventory[0].id"]);
+    /// ```
+    pub fn walk(&self) -> Walk<'_> {
+        let mut walk = Walk { stack: Vec::new() };
+        walk.push_entries(&NbtPath::new(), self);
+
+        walk
+    }
+
+    /// Returns all nested tags for which predicate returns `true`, in depth-first order
+    /// together with their paths.
+    ///
+    /// # Example
+    /// ```
+    /// use nbt::CompoundTag;
+    ///
+    /// let mut item = CompoundTag::new();
+    /// item.insert_str("id", "minecraft:diamond");
+    ///
+    /// let mut chest = CompoundTag::new();
+    /// chest.insert_compound_tag_vec("Items", vec![item]);
+    ///
+    /// let mut root_tag = CompoundTag::new();
+    /// root_tag.insert_compound_tag_vec("block_entities", vec![chest]);
+    ///
+    /// let paths: Vec<String> = root_tag
+    ///     .find_all(|_, tag| tag.as_str() == Some("minecraft:diamond"))
+    ///     .map(|(path, _)| path.to_string())
+    ///     .collect();
+    ///
+    /// assert_eq!(paths, vec!["block_entities[0].Items[0].id"]);
+    /// ```
+    pub fn find_all<F>(&self, mut predicate: F) -> impl Iterator<Item = (NbtPath, &Tag)>
+    where
+        F: FnMut(&NbtPath, &Tag) -> bool,
+    {
+        self.walk().filter(move |(path, tag)| predicate(path, tag))
+    }
+
+    /// Returns the first nested tag in depth-first order for which predicate returns `true`
+    /// together with its path, see [`find_all`](CompoundTag::find_all).
+    pub fn find_first<F>(&self, predicate: F) -> Option<(NbtPath, &Tag)>
+    where
+        F: FnMut(&NbtPath, &Tag) -> bool,
+    {
+        self.find_all(predicate).next()
+    }
+}
+
+#[test]
+fn test_walk() {
+    use crate::decode::read_gzip_compound_tag;
+    use std::io::Cursor;
+
+    let mut cursor = Cursor::new(include_bytes!("../test/binary/bigtest.dat").to_vec());
+    let root_tag = read_gzip_compound_tag(&mut cursor).unwrap();
+
+    let paths: Vec<String> = root_tag
+        .walk()
+        .filter(|(_, tag)| tag.is_string())
+        .map(|(path, _)| path.to_string())
+        .collect();
+
+    assert_eq!(
+        paths,
+        vec![
+            "stringTest",
+            "\"nested compound test\".ham.name",
+            "\"nested compound test\".egg.name",
+            "\"listTest (compound)\"[0].name",
+            "\"listTest (compound)\"[1].name",
+        ]
+    );
+
+    let tag = Tag::List(vec![Tag::Int(1), Tag::List(vec![Tag::Int(2)])]);
+    let walked: Vec<(String, &Tag)> = tag
+        .walk()
+        .map(|(path, tag)| (path.to_string(), tag))
+        .collect();
+
+    assert_eq!(walked.len(), 4);
+    assert_eq!(walked[0].0, "");
+    assert_eq!(walked[3], ("[1][0]".to_string(), &Tag::Int(2)));
+}
+
+#[test]
+fn test_find() {
+    use crate::decode::read_gzip_compound_tag;
+    use std::io::Cursor;
+
+    let mut cursor = Cursor::new(include_bytes!("../test/binary/bigtest.dat").to_vec());
+    let root_tag = read_gzip_compound_tag(&mut cursor).unwrap();
+
+    let names: Vec<(String, &Tag)> = root_tag
+        .find_all(|path, _| path.to_string().ends_with(".name"))
+        .map(|(path, tag)| (path.to_string(), tag))
+        .collect();
+
+    assert_eq!(names.len(), 4);
+    assert_eq!(names[0].0, "\"nested compound test\".ham.name");
+    assert_eq!(names[3].1.as_str(), Some("Compound tag #1"));
+
+    let (path, tag) = root_tag.find_first(|_, tag| tag.is_string()).unwrap();
+    assert_eq!(path.to_string(), "stringTest");
+    assert!(tag.is_string());
+
+    assert!(root_tag.find_first(|_, tag| tag == &Tag::Int(-1)).is_none());
+}
@@ -0,0 +1,568 @@
+//! Conversion of tags to XML and back.
+//!
+//! Each tag is an element named after its type in the NBT specification, with tag name
+//! in `name` attribute. Numbers and strings are element text, arrays are space separated
+//! numbers and list elements are child elements without names:
+//!
+//! ```xml
+//! <TAG_Compound name="">
+//!   <TAG_String name="id">minecraft:zombie</TAG_String>
+//!   <TAG_Short name="Fire">-1</TAG_Short>
+//!   <TAG_List name="Pos" type="TAG_Double">
+//!     <TAG_Double>0.5</TAG_Double>
+//!     <TAG_Double>64</TAG_Double>
+//!     <TAG_Double>-0.5</TAG_Double>
+//!   </TAG_List>
+//!   <TAG_Int_Array name="UUID">1 2 3 4</TAG_Int_Array>
+//! </TAG_Compound>
+//! ```
+//!
+//! Unknown tags are written as `TAG_Unknown` elements with `id` attribute and space separated
+//! bytes of the payload.
+//!
+//! # Example
+//! ```
+//! use nbt::xml::{from_xml_str, to_xml_string};
+//! use nbt::CompoundTag;
+//!
+//! let mut compound_tag = CompoundTag::named("");
+//! compound_tag.insert_str("id", "minecraft:zombie");
+//! compound_tag.insert_i32_vec("UUID", vec![1, 2, 3, 4]);
+//!
+//! let xml = to_xml_string(&compound_tag);
+//! assert_eq!(
+//!     xml,
+//!     "<TAG_Compound name=\"\">\n  \
+//!          <TAG_String name=\"id\">minecraft:zombie</TAG_String>\n  \
+//!          <TAG_Int_Array name=\"UUID\">1 2 3 4</TAG_Int_Array>\n\
+//!      </TAG_Compound>"
+//! );
+//!
+//! assert_eq!(from_xml_str(&xml).unwrap(), compound_tag);
+//! ```
+use crate::nbtpath::{fmt_path, NbtPath};
+use crate::{CompoundTag, Tag, TagType};
+use quick_xml::events::{BytesStart, BytesText, Event};
+use quick_xml::{Reader, Writer};
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::io::{self, BufRead, Write};
+use std::str::FromStr;
+
+const UNKNOWN_ELEMENT: &str = "TAG_Unknown";
+
+/// Writes compound tag as indented XML document without declaration.
+pub fn write_xml<W: Write>(writer: W, compound_tag: &CompoundTag) -> io::Result<()> {
+    let mut writer = Writer::new_with_indent(writer, b' ', 2);
+    let mut start = BytesStart::new(TagType::Compound.name());
+
+    if let Some(name) = &compound_tag.name {
+        start.push_attribute(("name", name.as_str()));
+    }
+
+    let children = compound_tag.iter().map(|(name, tag)| (Some(name), tag));
+    write_children(&mut writer, start, children)
+}
+
+/// Formats compound tag as indented XML document without declaration.
+pub fn to_xml_string(compound_tag: &CompoundTag) -> String {
+    let mut bytes = Vec::new();
+    write_xml(&mut bytes, compound_tag).expect("Writing to vec doesn't fail");
+
+    String::from_utf8(bytes).expect("XML is valid UTF-8")
+}
+
+/// Reads compound tag from XML document, root element name becomes name of the compound tag.
+pub fn read_xml<R: BufRead>(reader: R) -> Result<CompoundTag, XmlError> {
+    XmlDecoder::new(reader).decode()
+}
+
+/// Parses compound tag from XML document, see [`read_xml`].
+pub fn from_xml_str(xml: &str) -> Result<CompoundTag, XmlError> {
+    read_xml(xml.as_bytes())
+}
+
+fn write_tag<W: Write>(writer: &mut Writer<W>, name: Option<&str>, tag: &Tag) -> io::Result<()> {
+    let element = match tag {
+        Tag::Unknown { .. } => UNKNOWN_ELEMENT,
+        tag => tag.type_name(),
+    };
+
+    let mut start = BytesStart::new(element);
+
+    if let Some(name) = name {
+        start.push_attribute(("name", name));
+    }
+
+    let text = match tag {
+        Tag::Byte(value) => value.to_string(),
+        Tag::Short(value) => value.to_string(),
+        Tag::Int(value) => value.to_string(),
+        Tag::Long(value) => value.to_string(),
+        Tag::Float(value) => value.to_string(),
+        Tag::Double(value) => value.to_string(),
+        Tag::ByteArray(value) => join(value),
+        Tag::String(value) => value.clone(),
+        Tag::IntArray(value) => join(value),
+        Tag::LongArray(value) => join(value),
+        Tag::Unknown { id, bytes } => {
+            start.push_attribute(("id", id.to_string().as_str()));
+            join(bytes)
+        }
+        Tag::List(value) => {
+            if let Some(first) = value.first() {
+                start.push_attribute(("type", first.type_name()));
+            }
+
+            return write_children(writer, start, value.iter().map(|tag| (None, tag)));
+        }
+        Tag::Compound(value) => {
+            let children = value.iter().map(|(name, tag)| (Some(name), tag));
+            return write_children(writer, start, children);
+        }
+    };
+
+    let end = start.to_end().into_owned();
+
+    writer.write_event(Event::Start(start))?;
+    writer.write_event(Event::Text(BytesText::new(&text)))?;
+    writer.write_event(Event::End(end))
+}
+
+fn write_children<'a, W: Write>(
+    writer: &mut Writer<W>,
+    start: BytesStart,
+    mut children: impl Iterator<Item = (Option<&'a str>, &'a Tag)>,
+) -> io::Result<()> {
+    let first = match children.next() {
+        Some(first) => first,
+        None => return writer.write_event(Event::Empty(start)),
+    };
+
+    let end = start.to_end().into_owned();
+    writer.write_event(Event::Start(start))?;
+
+    for (name, tag) in std::iter::once(first).chain(children) {
+        write_tag(writer, name, tag)?;
+    }
+
+    writer.write_event(Event::End(end))
+}
+
+fn join<T: ToString>(values: &[T]) -> String {
+    let values: Vec<_> = values.iter().map(ToString::to_string).collect();
+    values.join(" ")
+}
+
+/// Element which is being read.
+enum Frame {
+    Compound {
+        name: Option<String>,
+        compound_tag: CompoundTag,
+    },
+    List {
+        name: Option<String>,
+        tags: Vec<Tag>,
+    },
+    Value {
+        name: Option<String>,
+        tag_type: TagType,
+        text: String,
+    },
+}
+
+struct XmlDecoder<R> {
+    reader: Reader<R>,
+    stack: Vec<Frame>,
+    path: NbtPath,
+}
+
+impl<R: BufRead> XmlDecoder<R> {
+    fn new(reader: R) -> Self {
+        XmlDecoder {
+            reader: Reader::from_reader(reader),
+            stack: Vec::new(),
+            path: NbtPath::new(),
+        }
+    }
+
+    fn decode(mut self) -> Result<CompoundTag, XmlError> {
+        let mut buf = Vec::new();
+        let mut root = None;
+
+        loop {
+            let event = self.reader.read_event_into(&mut buf)?;
+
+            if root.is_some() {
+                match event {
+                    Event::Eof => break,
+                    Event::Text(text) if is_whitespace(&text) => {}
+                    Event::Comment(_) | Event::PI(_) => {}
+                    _ => {
+                        return Err(XmlError::UnexpectedContent {
+                            path: NbtPath::new(),
+                        })
+                    }
+                }
+
+                buf.clear();
+                continue;
+            }
+
+            match event {
+                Event::Start(start) => self.start(&start)?,
+                Event::Empty(start) => {
+                    self.start(&start)?;
+                    root = self.end()?;
+                }
+                Event::End(_) => root = self.end()?,
+                Event::Text(text) => match self.stack.last_mut() {
+                    Some(Frame::Value { text: value, .. }) => value.push_str(&text.unescape()?),
+                    _ if is_whitespace(&text) => {}
+                    _ => return Err(self.unexpected_content()),
+                },
+                Event::CData(data) => match self.stack.last_mut() {
+                    Some(Frame::Value { text, .. }) => text.push_str(&data.decode()?),
+                    _ => return Err(self.unexpected_content()),
+                },
+                Event::Eof => return Err(XmlError::UnexpectedEof),
+                Event::Decl(_) | Event::Comment(_) | Event::PI(_) | Event::DocType(_) => {}
+            }
+
+            buf.clear();
+        }
+
+        Ok(root.expect("Loop ends only after root"))
+    }
+
+    fn start(&mut self, start: &BytesStart) -> Result<(), XmlError> {
+        let element = String::from_utf8_lossy(start.name().as_ref()).into_owned();
+
+        let name = match start.try_get_attribute("name")? {
+            Some(name) => Some(name.unescape_value()?.into_owned()),
+            None => None,
+        };
+
+        // Path points to the tag being read.
+        match self.stack.last() {
+            Some(Frame::Compound { .. }) => match &name {
+                Some(name) => self.path.push_key(name.as_str()),
+                None => {
+                    return Err(XmlError::MissingName {
+                        path: self.path.clone(),
+                    })
+                }
+            },
+            Some(Frame::List { tags, .. }) => self.path.push_index(tags.len()),
+            Some(Frame::Value { .. }) => return Err(self.unexpected_content()),
+            None => {}
+        }
+
+        let tag_type = match element.as_str() {
+            UNKNOWN_ELEMENT => {
+                let id = match start.try_get_attribute("id")? {
+                    Some(id) => id.unescape_value()?.parse().ok(),
+                    None => None,
+                };
+
+                match id {
+                    Some(id) => TagType::Unknown(id),
+                    None => {
+                        return Err(XmlError::InvalidValue {
+                            path: self.path.clone(),
+                            tag_type: TagType::Unknown(0),
+                        })
+                    }
+                }
+            }
+            element => match (1..=12)
+                .filter_map(TagType::from_id)
+                .find(|tag_type| tag_type.name() == element)
+            {
+                Some(tag_type) => tag_type,
+                None => {
+                    return Err(XmlError::UnknownElement {
+                        path: self.path.clone(),
+                        element: element.to_string(),
+                    })
+                }
+            },
+        };
+
+        if self.stack.is_empty() && tag_type != TagType::Compound {
+            return Err(XmlError::RootMustBeCompoundTag);
+        }
+
+        let frame = match tag_type {
+            TagType::Compound => Frame::Compound {
+                name,
+                compound_tag: CompoundTag::new(),
+            },
+            TagType::List => Frame::List {
+                name,
+                tags: Vec::new(),
+            },
+            tag_type => Frame::Value {
+                name,
+                tag_type,
+                text: String::new(),
+            },
+        };
+
+        self.stack.push(frame);
+
+        Ok(())
+    }
+
+    /// Finishes the current element, returning the root compound tag if it's the last one.
+    fn end(&mut self) -> Result<Option<CompoundTag>, XmlError> {
+        let (name, tag) = match self.stack.pop().expect("Reader checks end names") {
+            Frame::Compound { name, compound_tag } => (name, Tag::Compound(compound_tag)),
+            Frame::List { name, tags } => (name, Tag::List(tags)),
+            Frame::Value {
+                name,
+                tag_type,
+                text,
+            } => (name, self.parse_value(tag_type, &text)?),
+        };
+
+        match self.stack.last_mut() {
+            Some(Frame::Compound { compound_tag, .. }) => {
+                compound_tag.insert(name.expect("Name is checked on start"), tag)
+            }
+            Some(Frame::List { tags, .. }) => tags.push(tag),
+            Some(Frame::Value { .. }) => unreachable!("Values have no children"),
+            None => {
+                return match tag {
+                    Tag::Compound(mut compound_tag) => {
+                        compound_tag.name = name;
+                        Ok(Some(compound_tag))
+                    }
+                    _ => unreachable!("Root type is checked on start"),
+                };
+            }
+        }
+
+        self.path.pop();
+
+        Ok(None)
+    }
+
+    fn parse_value(&self, tag_type: TagType, text: &str) -> Result<Tag, XmlError> {
+        let tag = match tag_type {
+            TagType::Byte => text.trim().parse().ok().map(Tag::Byte),
+            TagType::Short => text.trim().parse().ok().map(Tag::Short),
+            TagType::Int => text.trim().parse().ok().map(Tag::Int),
+            TagType::Long => text.trim().parse().ok().map(Tag::Long),
+            TagType::Float => text.trim().parse().ok().map(Tag::Float),
+            TagType::Double => text.trim().parse().ok().map(Tag::Double),
+            TagType::ByteArray => split(text).map(Tag::ByteArray),
+            TagType::String => Some(Tag::String(text.to_string())),
+            TagType::IntArray => split(text).map(Tag::IntArray),
+            TagType::LongArray => split(text).map(Tag::LongArray),
+            TagType::Unknown(id) => split(text).map(|bytes| Tag::Unknown { id, bytes }),
+            TagType::List | TagType::Compound => unreachable!("Containers are not values"),
+        };
+
+        tag.ok_or_else(|| XmlError::InvalidValue {
+            path: self.path.clone(),
+            tag_type,
+        })
+    }
+
+    fn unexpected_content(&self) -> XmlError {
+        XmlError::UnexpectedContent {
+            path: self.path.clone(),
+        }
+    }
+}
+
+fn split<T: FromStr>(text: &str) -> Option<Vec<T>> {
+    text.split_whitespace()
+        .map(|value| value.parse().ok())
+        .collect()
+}
+
+fn is_whitespace(text: &BytesText) -> bool {
+    text.iter().all(u8::is_ascii_whitespace)
+}
+
+/// Possible types of errors while reading tags from XML.
+#[derive(Debug)]
+pub enum XmlError {
+    /// Malformed XML or I/O error.
+    Xml(quick_xml::Error),
+    /// Root element is not `TAG_Compound`.
+    RootMustBeCompoundTag,
+    /// Document ended before the root element.
+    UnexpectedEof,
+    /// Element is not a tag type.
+    UnknownElement { path: NbtPath, element: String },
+    /// Element inside of `TAG_Compound` has no `name` attribute.
+    MissingName { path: NbtPath },
+    /// Text or element where it is not allowed, e.g. text inside of `TAG_List`.
+    UnexpectedContent { path: NbtPath },
+    /// Text doesn't fit into the tag type, e.g. byte out of range.
+    InvalidValue { path: NbtPath, tag_type: TagType },
+}
+
+impl XmlError {
+    /// Returns path of the problematic tag, if error is related to a tag.
+    pub fn path(&self) -> Option<&NbtPath> {
+        match self {
+            XmlError::UnknownElement { path, .. } => Some(path),
+            XmlError::MissingName { path } => Some(path),
+            XmlError::UnexpectedContent { path } => Some(path),
+            XmlError::InvalidValue { path, .. } => Some(path),
+            _ => None,
+        }
+    }
+}
+
+impl From<quick_xml::Error> for XmlError {
+    fn from(error: quick_xml::Error) -> Self {
+        XmlError::Xml(error)
+    }
+}
+
+impl From<quick_xml::events::attributes::AttrError> for XmlError {
+    fn from(error: quick_xml::events::attributes::AttrError) -> Self {
+        XmlError::Xml(error.into())
+    }
+}
+
+impl From<quick_xml::encoding::EncodingError> for XmlError {
+    fn from(error: quick_xml::encoding::EncodingError) -> Self {
+        XmlError::Xml(error.into())
+    }
+}
+
+impl Error for XmlError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            XmlError::Xml(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl Display for XmlError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            XmlError::Xml(error) => write!(f, "Invalid XML: {}", error),
+            XmlError::RootMustBeCompoundTag => write!(f, "Root element must be TAG_Compound"),
+            XmlError::UnexpectedEof => write!(f, "Document ended before root element"),
+            XmlError::UnknownElement { path, element } => {
+                write!(f, "Tag")?;
+                fmt_path(f, path)?;
+                write!(f, " has unknown element {}", element)
+            }
+            XmlError::MissingName { path } if path.is_empty() => {
+                write!(f, "Tag inside of root tag has no name")
+            }
+            XmlError::MissingName { path } => write!(f, "Tag inside of {} has no name", path),
+            XmlError::UnexpectedContent { path } => {
+                write!(f, "Tag")?;
+                fmt_path(f, path)?;
+                write!(f, " has unexpected content")
+            }
+            XmlError::InvalidValue { path, tag_type } => {
+                write!(f, "Tag")?;
+                fmt_path(f, path)?;
+                write!(f, " has invalid value for {}", tag_type)
+            }
+        }
+    }
+}
+
+#[test]
+fn test_xml_round_trip() {
+    use crate::decode::read_gzip_compound_tag;
+    use std::io::Cursor;
+
+    let mut cursor = Cursor::new(include_bytes!("../test/binary/bigtest.dat").to_vec());
+    let mut compound_tag = read_gzip_compound_tag(&mut cursor).unwrap();
+
+    compound_tag.insert_str("escaped", " <a & \"b\"> ");
+    compound_tag.insert_str("empty", "");
+    compound_tag.insert_f64("infinity", f64::NEG_INFINITY);
+    compound_tag.insert_compound_tag_vec("empty list", Vec::new());
+    compound_tag.insert(
+        "unknown",
+        Tag::Unknown {
+            id: 13,
+            bytes: vec![0, 255],
+        },
+    );
+
+    let xml = to_xml_string(&compound_tag);
+    assert!(xml.starts_with("<TAG_Compound name=\"Level\">\n"));
+    assert!(xml.contains("<TAG_List name=\"empty list\"/>"));
+    assert!(xml.contains("<TAG_Unknown name=\"unknown\" id=\"13\">0 255</TAG_Unknown>"));
+
+    let actual = from_xml_str(&xml).unwrap();
+    assert_eq!(actual.name.as_deref(), Some("Level"));
+    assert_eq!(actual.get_str("escaped").unwrap(), " <a & \"b\"> ");
+    assert_eq!(actual, compound_tag);
+
+    let xml = r#"<?xml version="1.0"?>
+        <!-- Edited by hand. -->
+        <TAG_Compound>
+            <TAG_Byte name="a"> 1 </TAG_Byte>
+            <TAG_String name="b"><![CDATA[<c>]]></TAG_String>
+            <TAG_Int_Array name="c">
+                1
+                2
+            </TAG_Int_Array>
+        </TAG_Compound>
+    "#;
+
+    let compound_tag = from_xml_str(xml).unwrap();
+    assert_eq!(compound_tag.name, None);
+    assert_eq!(compound_tag.get_i8("a").unwrap(), 1);
+    assert_eq!(compound_tag.get_str("b").unwrap(), "<c>");
+    assert_eq!(compound_tag.get_i32_vec("c").unwrap(), &vec![1, 2]);
+}
+
+#[test]
+fn test_xml_errors() {
+    let error = |xml: &str| from_xml_str(xml).unwrap_err().to_string();
+
+    assert_eq!(
+        error(
+            r#"<TAG_Compound><TAG_List name="a"><TAG_Byte>300</TAG_Byte></TAG_List></TAG_Compound>"#
+        ),
+        "Tag a[0] has invalid value for TAG_Byte"
+    );
+    assert_eq!(
+        error(
+            r#"<TAG_Compound><TAG_Compound name="a"><TAG_Int>1</TAG_Int></TAG_Compound></TAG_Compound>"#
+        ),
+        "Tag inside of a has no name"
+    );
+    assert_eq!(
+        error("<TAG_Compound><TAG_Int>1</TAG_Int></TAG_Compound>"),
+        "Tag inside of root tag has no name"
+    );
+    assert_eq!(
+        error(r#"<TAG_Compound><TAG_Uint name="a">1</TAG_Uint></TAG_Compound>"#),
+        "Tag a has unknown element TAG_Uint"
+    );
+    assert_eq!(
+        error(r#"<TAG_Compound><TAG_List name="a">1</TAG_List></TAG_Compound>"#),
+        "Tag a has unexpected content"
+    );
+    assert_eq!(
+        error("<TAG_Int>1</TAG_Int>"),
+        "Root element must be TAG_Compound"
+    );
+    assert_eq!(
+        error("<TAG_Compound/><TAG_Compound/>"),
+        "Tag has unexpected content"
+    );
+    assert_eq!(error(""), "Document ended before root element");
+    assert!(matches!(
+        from_xml_str("<TAG_Compound></TAG_List>"),
+        Err(XmlError::Xml(_))
+    ));
+}
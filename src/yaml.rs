@@ -0,0 +1,325 @@
+//! Conversion of tags to YAML values and back, e.g. for dumps edited by hand.
+//!
+//! Compound tags become mappings in the same order, lists become sequences. Strings, ints
+//! and doubles are written as plain YAML values, other types keep their types with tags:
+//!
+//! ```yaml
+//! id: minecraft:sheep
+//! Age: 0
+//! Health: !float 8.0
+//! Sheared: !byte 0
+//! UUID: !int_array [1, 2, 3, 4]
+//! Motion: [0.0, -0.08, 0.0]
+//! ```
+//!
+//! Plain booleans are read as bytes, so `Sheared: false` works as well.
+//! Unknown tags are written as `!unknown {id: 13, bytes: [...]}`.
+//!
+//! # Example
+//! ```
+//! use nbt::yaml::{compound_tag_from_yaml, compound_tag_to_yaml};
+//! use nbt::CompoundTag;
+//!
+//! let mut compound_tag = CompoundTag::new();
+//! compound_tag.insert_str("id", "minecraft:sheep");
+//! compound_tag.insert_i8("Sheared", 0);
+//! compound_tag.insert_f64_vec("Motion", vec![0.0, -0.08, 0.0]);
+//!
+//! let yaml = serde_yaml::to_string(&compound_tag_to_yaml(&compound_tag)).unwrap();
+//! assert_eq!(
+//!     yaml,
+//!     "id: minecraft:sheep\nSheared: !byte 0\nMotion:\n- 0.0\n- -0.08\n- 0.0\n"
+//! );
+//!
+//! let value = serde_yaml::from_str(&yaml).unwrap();
+//! assert_eq!(compound_tag_from_yaml(&value).unwrap(), compound_tag);
+//! ```
+use crate::nbtpath::NbtPath;
+use crate::{CompoundTag, Tag, TagType};
+use serde_yaml::value::{Tag as YamlTag, TaggedValue};
+use serde_yaml::{Mapping, Value};
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+
+/// Converts compound tag to YAML mapping.
+pub fn compound_tag_to_yaml(compound_tag: &CompoundTag) -> Value {
+    let mut mapping = Mapping::new();
+
+    for (name, tag) in compound_tag.iter() {
+        mapping.insert(Value::from(name), tag_to_yaml(tag));
+    }
+
+    Value::Mapping(mapping)
+}
+
+/// Converts tag to YAML value.
+pub fn tag_to_yaml(tag: &Tag) -> Value {
+    match tag {
+        Tag::Byte(value) => tagged("byte", Value::from(*value)),
+        Tag::Short(value) => tagged("short", Value::from(*value)),
+        Tag::Int(value) => Value::from(*value),
+        Tag::Long(value) => tagged("long", Value::from(*value)),
+        Tag::Float(value) => tagged("float", Value::from(float_to_f64(*value))),
+        Tag::Double(value) => Value::from(*value),
+        Tag::ByteArray(value) => tagged("byte_array", Value::from(value.clone())),
+        Tag::String(value) => Value::from(value.as_str()),
+        Tag::List(value) => Value::Sequence(value.iter().map(tag_to_yaml).collect()),
+        Tag::Compound(value) => compound_tag_to_yaml(value),
+        Tag::IntArray(value) => tagged("int_array", Value::from(value.clone())),
+        Tag::LongArray(value) => tagged("long_array", Value::from(value.clone())),
+        Tag::Unknown { id, bytes } => {
+            let mut mapping = Mapping::new();
+            mapping.insert(Value::from("id"), Value::from(*id));
+            mapping.insert(Value::from("bytes"), Value::from(bytes.clone()));
+
+            tagged("unknown", Value::Mapping(mapping))
+        }
+    }
+}
+
+/// Converts YAML mapping back to compound tag.
+pub fn compound_tag_from_yaml(value: &Value) -> Result<CompoundTag, YamlError> {
+    let mut path = NbtPath::new();
+
+    match value_to_tag(value, &mut path)? {
+        Tag::Compound(compound_tag) => Ok(compound_tag),
+        _ => Err(YamlError::UnexpectedValue { path }),
+    }
+}
+
+/// Converts YAML value back to tag.
+pub fn tag_from_yaml(value: &Value) -> Result<Tag, YamlError> {
+    value_to_tag(value, &mut NbtPath::new())
+}
+
+fn tagged(tag: &str, value: Value) -> Value {
+    Value::Tagged(Box::new(TaggedValue {
+        tag: YamlTag::new(tag),
+        value,
+    }))
+}
+
+/// Keeps short decimal form of the float, e.g. `0.1` instead of `0.10000000149011612`.
+fn float_to_f64(value: f32) -> f64 {
+    if value.is_finite() {
+        value
+            .to_string()
+            .parse()
+            .expect("Float is formatted as decimal")
+    } else {
+        f64::from(value)
+    }
+}
+
+fn int_from_yaml<T: TryFrom<i64>>(value: &Value) -> Option<T> {
+    value.as_i64().and_then(|value| T::try_from(value).ok())
+}
+
+fn ints_from_yaml<T: TryFrom<i64>>(value: &Value) -> Option<Vec<T>> {
+    value.as_sequence()?.iter().map(int_from_yaml).collect()
+}
+
+fn value_to_tag(value: &Value, path: &mut NbtPath) -> Result<Tag, YamlError> {
+    match value {
+        Value::Bool(value) => Ok(Tag::Byte(*value as i8)),
+        Value::Number(number) => match number.as_i64() {
+            Some(value) if !number.is_f64() => match i32::try_from(value) {
+                Ok(value) => Ok(Tag::Int(value)),
+                Err(_) => Ok(Tag::Long(value)),
+            },
+            _ => match number.as_f64() {
+                Some(value) if number.is_f64() => Ok(Tag::Double(value)),
+                _ => Err(YamlError::InvalidValue {
+                    path: path.clone(),
+                    tag_type: TagType::Long,
+                }),
+            },
+        },
+        Value::String(value) => Ok(Tag::String(value.clone())),
+        Value::Sequence(values) => {
+            let mut tags = Vec::with_capacity(values.len());
+
+            for (index, value) in values.iter().enumerate() {
+                path.push_index(index);
+                tags.push(value_to_tag(value, path)?);
+                path.pop();
+            }
+
+            Ok(Tag::List(tags))
+        }
+        Value::Mapping(mapping) => {
+            let mut compound_tag = CompoundTag::new();
+
+            for (name, value) in mapping {
+                let name = match name {
+                    Value::String(name) => name.as_str(),
+                    _ => return Err(YamlError::NonStringKey { path: path.clone() }),
+                };
+
+                path.push_key(name);
+                compound_tag.insert(name, value_to_tag(value, path)?);
+                path.pop();
+            }
+
+            Ok(Tag::Compound(compound_tag))
+        }
+        Value::Tagged(tagged) => tagged_to_tag(tagged, path),
+        Value::Null => Err(YamlError::UnexpectedValue { path: path.clone() }),
+    }
+}
+
+fn tagged_to_tag(tagged: &TaggedValue, path: &mut NbtPath) -> Result<Tag, YamlError> {
+    let value = &tagged.value;
+    let tag_name = tagged.tag.to_string();
+
+    let (tag_type, tag) = match tag_name.trim_start_matches('!') {
+        "byte" => (TagType::Byte, int_from_yaml(value).map(Tag::Byte)),
+        "short" => (TagType::Short, int_from_yaml(value).map(Tag::Short)),
+        "int" => (TagType::Int, int_from_yaml(value).map(Tag::Int)),
+        "long" => (TagType::Long, value.as_i64().map(Tag::Long)),
+        "float" => (
+            TagType::Float,
+            value.as_f64().map(|value| Tag::Float(value as f32)),
+        ),
+        "double" => (TagType::Double, value.as_f64().map(Tag::Double)),
+        "byte_array" => (
+            TagType::ByteArray,
+            ints_from_yaml(value).map(Tag::ByteArray),
+        ),
+        "int_array" => (TagType::IntArray, ints_from_yaml(value).map(Tag::IntArray)),
+        "long_array" => (
+            TagType::LongArray,
+            ints_from_yaml(value).map(Tag::LongArray),
+        ),
+        "unknown" => {
+            let id = value.get("id").and_then(int_from_yaml);
+            let bytes = value.get("bytes").and_then(ints_from_yaml);
+            let tag = id.zip(bytes).map(|(id, bytes)| Tag::Unknown { id, bytes });
+
+            (TagType::Unknown(id.unwrap_or_default()), tag)
+        }
+        _ => {
+            return Err(YamlError::UnknownType {
+                path: path.clone(),
+                type_name: tag_name,
+            })
+        }
+    };
+
+    tag.ok_or_else(|| YamlError::InvalidValue {
+        path: path.clone(),
+        tag_type,
+    })
+}
+
+/// Possible types of errors while converting YAML back to tags.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum YamlError {
+    /// YAML value can't be a tag, e.g. `null`.
+    UnexpectedValue { path: NbtPath },
+    /// Mapping has a key which is not a string.
+    NonStringKey { path: NbtPath },
+    /// YAML tag is not recognized.
+    UnknownType { path: NbtPath, type_name: String },
+    /// Value doesn't fit into the tag type, e.g. byte out of range.
+    InvalidValue { path: NbtPath, tag_type: TagType },
+}
+
+impl YamlError {
+    /// Returns path of the problematic tag.
+    pub fn path(&self) -> &NbtPath {
+        match self {
+            YamlError::UnexpectedValue { path } => path,
+            YamlError::NonStringKey { path } => path,
+            YamlError::UnknownType { path, .. } => path,
+            YamlError::InvalidValue { path, .. } => path,
+        }
+    }
+}
+
+impl Error for YamlError {}
+
+impl Display for YamlError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            YamlError::UnexpectedValue { path } => {
+                write!(f, "Tag {} has unexpected YAML value", path)
+            }
+            YamlError::NonStringKey { path } => {
+                write!(f, "Tag {} has key which is not a string", path)
+            }
+            YamlError::UnknownType { path, type_name } => {
+                write!(f, "Tag {} has unknown type {}", path, type_name)
+            }
+            YamlError::InvalidValue { path, tag_type } => {
+                write!(f, "Tag {} has invalid value for {}", path, tag_type)
+            }
+        }
+    }
+}
+
+#[test]
+fn test_yaml_round_trip() {
+    use crate::decode::read_gzip_compound_tag;
+    use std::io::Cursor;
+
+    let mut cursor = Cursor::new(include_bytes!("../test/binary/bigtest.dat").to_vec());
+    let mut compound_tag = read_gzip_compound_tag(&mut cursor).unwrap();
+
+    compound_tag.insert_f64("infinity", f64::INFINITY);
+    compound_tag.insert_f32("float", 0.1);
+    compound_tag.insert_i64("small long", 1);
+    compound_tag.insert_compound_tag_vec("empty", Vec::new());
+    compound_tag.insert(
+        "unknown",
+        Tag::Unknown {
+            id: 13,
+            bytes: vec![0, 255],
+        },
+    );
+
+    let yaml = serde_yaml::to_string(&compound_tag_to_yaml(&compound_tag)).unwrap();
+    assert!(yaml.contains("float: !float 0.1\n"));
+    assert!(yaml.contains("infinity: .inf\n"));
+
+    let value: Value = serde_yaml::from_str(&yaml).unwrap();
+    assert_eq!(compound_tag_from_yaml(&value).unwrap(), compound_tag);
+
+    let value: Value = serde_yaml::from_str("a: true\nb: !int 1\nc: !double 1\n").unwrap();
+    let compound_tag = compound_tag_from_yaml(&value).unwrap();
+    assert_eq!(compound_tag.get_i8("a").unwrap(), 1);
+    assert_eq!(compound_tag.get_i32("b").unwrap(), 1);
+    assert_eq!(compound_tag.get_f64("c").unwrap(), 1.0);
+}
+
+#[test]
+fn test_yaml_errors() {
+    let error = |yaml: &str| {
+        let value: Value = serde_yaml::from_str(yaml).unwrap();
+        compound_tag_from_yaml(&value).unwrap_err()
+    };
+
+    assert_eq!(
+        error("a:\n  b: [1, null]\n").to_string(),
+        "Tag a.b[1] has unexpected YAML value"
+    );
+    assert_eq!(
+        error("a: !byte 300\n").to_string(),
+        "Tag a has invalid value for TAG_Byte"
+    );
+    assert_eq!(
+        error("a: !uint 1\n"),
+        YamlError::UnknownType {
+            path: "a".parse().unwrap(),
+            type_name: "!uint".to_string(),
+        }
+    );
+    assert_eq!(
+        error("1: a\n"),
+        YamlError::NonStringKey {
+            path: NbtPath::new()
+        }
+    );
+    assert!(tag_from_yaml(&Value::from(u64::MAX)).is_err());
+}